@@ -1,5 +1,7 @@
-#![no_std]
-#![no_main]
+#![cfg_attr(target_os = "none", no_std)]
+#![cfg_attr(target_os = "none", no_main)]
+// Host builds only run the tests, without `entry`.
+#![cfg_attr(not(target_os = "none"), allow(dead_code))]
 
 use utils::prelude::*;
 
@@ -11,6 +13,11 @@ mod tasks {
     pub mod health;
 }
 
+/// Host builds only run the tests, and are never flashed.
+#[cfg(not(target_os = "none"))]
+fn main() {}
+
+#[cfg(target_os = "none")]
 #[embassy_executor::main]
 async fn entry(s: embassy_executor::Spawner) {
     let (_c, p) = utils::sys_init();
@@ -13,6 +13,56 @@ use timer::simple_pwm::{PwmPin, SimplePwm};
 const FPS: f32 = 1000.;
 const SPEED: u16 = 1;
 
+/// Duty cycle denominator, full brightness
+const FULL: u32 = 255;
+
+///
+/// # PWM Channel
+///
+/// What `RgbLed` needs of a channel, so tests can drive it without a timer.
+///
+trait Channel {
+    fn enable(&mut self);
+    fn set_duty_cycle_fraction(&mut self, num: u32, denom: u32);
+}
+
+impl Channel for SimplePwmChannel<'static, TIM5> {
+    fn enable(&mut self) {
+        SimplePwmChannel::enable(self)
+    }
+
+    fn set_duty_cycle_fraction(&mut self, num: u32, denom: u32) {
+        SimplePwmChannel::set_duty_cycle_fraction(self, num, denom)
+    }
+}
+
+///
+/// # RGB LED
+///
+/// Owns the three PWM channels of the LED.
+///
+struct RgbLed<C: Channel = SimplePwmChannel<'static, TIM5>> {
+    r: C,
+    g: C,
+    b: C,
+}
+
+impl<C: Channel> RgbLed<C> {
+    /// Enable all three channels
+    fn enable(&mut self) {
+        self.r.enable();
+        self.g.enable();
+        self.b.enable();
+    }
+
+    /// Set the color, each component scaled to `FULL`
+    fn set_rgb(&mut self, r: u8, g: u8, b: u8) {
+        self.r.set_duty_cycle_fraction(r as u32, FULL);
+        self.g.set_duty_cycle_fraction(g as u32, FULL);
+        self.b.set_duty_cycle_fraction(b as u32, FULL);
+    }
+}
+
 /// # HUE to RGB Conversion
 /// Converts a hue value (0-1535) to RGB values (0-255).
 fn color_wheel(hue: u16) -> (u8, u8, u8) {
@@ -27,33 +77,69 @@ fn color_wheel(hue: u16) -> (u8, u8, u8) {
     }
 }
 
+///
+/// # Blink Pattern
+///
+/// A repeating cycle of `period` ms, lit during each `(start, end)` window.
+///
+struct Pattern {
+    period: u32,
+    on: &'static [(u32, u32)],
+}
+
+impl Pattern {
+    /// Whether the LED is lit `ms` into the cycle
+    fn lit(&self, ms: u32) -> bool {
+        let ms = ms % self.period;
+        self.on
+            .iter()
+            .any(|&(start, end)| (start..end).contains(&ms))
+    }
+}
+
+/// # Mode to Pattern
+/// Slow blink when Normal, fast double-blink on Error, steady while Booting.
+fn pattern(mode: &SysMode) -> Pattern {
+    match mode {
+        SysMode::Normal => Pattern {
+            period: 2000,
+            on: &[(0, 1000)],
+        },
+        SysMode::Error => Pattern {
+            period: 1000,
+            on: &[(0, 100), (200, 300)],
+        },
+        SysMode::Boot => Pattern {
+            period: 1,
+            on: &[(0, 1)],
+        },
+    }
+}
+
 #[embassy_executor::task]
 pub async fn task(p: BlinkySrc) -> ! {
     let mut t = utils::init_ticker!(const { 1000. / FPS } as u64);
 
-    let (mut r, mut g, mut b) = init(p);
-    (r.enable(), g.enable(), b.enable());
+    let mut led = init(p);
+    led.enable();
 
     let mut hue: u16 = 0;
+    let mut ms: u32 = 0;
 
     loop {
-        let (rv, gv, bv) = color_wheel(hue);
-        r.set_duty_cycle_fraction(rv as u32, 255);
-        g.set_duty_cycle_fraction(gv as u32, 255);
-        b.set_duty_cycle_fraction(bv as u32, 255);
+        let (rv, gv, bv) = match pattern(&SysMode::get()).lit(ms) {
+            true => color_wheel(hue),
+            false => (0, 0, 0),
+        };
+        led.set_rgb(rv, gv, bv);
         hue = (hue + SPEED) % 1536;
+        ms = ms.wrapping_add(const { 1000. / FPS } as u32);
 
         t.next().await
     }
 }
 
-fn init(
-    p: BlinkySrc,
-) -> (
-    SimplePwmChannel<'static, TIM5>,
-    SimplePwmChannel<'static, TIM5>,
-    SimplePwmChannel<'static, TIM5>,
-) {
+fn init(p: BlinkySrc) -> RgbLed {
     let b = PwmPin::new(p.led_b, OutputType::PushPull);
     let g = PwmPin::new(p.led_g, OutputType::PushPull);
     let r = PwmPin::new(p.led_r, OutputType::PushPull);
@@ -69,5 +155,111 @@ fn init(
     )
     .split();
 
-    (chn.ch3, chn.ch2, chn.ch1)
+    RgbLed {
+        r: chn.ch3,
+        g: chn.ch2,
+        b: chn.ch1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether `mode` lights the LED, for each of `ms` ms from `from` on
+    fn lit(mode: SysMode, from: u32, ms: u32) -> Vec<bool> {
+        let p = pattern(&mode);
+        (from..from + ms).map(|ms| p.lit(ms)).collect()
+    }
+
+    /// Lit windows in `lit`, as `(start, end)` indices
+    fn windows(lit: &[bool]) -> Vec<(usize, usize)> {
+        let mut edges = Vec::new();
+        let mut start = None;
+        for (ms, &on) in lit.iter().chain([&false]).enumerate() {
+            match (on, start) {
+                (true, None) => start = Some(ms),
+                (false, Some(s)) => {
+                    edges.push((s, ms));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        edges
+    }
+
+    /// Channel state, as last set
+    #[derive(Default, Debug, PartialEq)]
+    struct MockChannel {
+        enabled: bool,
+        duty: (u32, u32),
+    }
+
+    impl Channel for MockChannel {
+        fn enable(&mut self) {
+            self.enabled = true;
+        }
+
+        fn set_duty_cycle_fraction(&mut self, num: u32, denom: u32) {
+            self.duty = (num, denom);
+        }
+    }
+
+    fn mock_led() -> RgbLed<MockChannel> {
+        RgbLed {
+            r: MockChannel::default(),
+            g: MockChannel::default(),
+            b: MockChannel::default(),
+        }
+    }
+
+    /// Channel at `num` out of `FULL`
+    fn mock(enabled: bool, num: u32) -> MockChannel {
+        MockChannel {
+            enabled,
+            duty: (num, FULL),
+        }
+    }
+
+    #[test]
+    fn test_rgb_led() {
+        let mut led = mock_led();
+        led.enable();
+        led.set_rgb(255, 128, 0);
+        assert_eq!(
+            (led.r, led.g, led.b),
+            (mock(true, 255), mock(true, 128), mock(true, 0))
+        );
+
+        // Each component reaches its own channel, out of `FULL`.
+        let mut led = mock_led();
+        led.set_rgb(1, 2, 3);
+        assert_eq!(
+            (led.r, led.g, led.b),
+            (mock(false, 1), mock(false, 2), mock(false, 3))
+        );
+    }
+
+    #[test]
+    fn test_pattern_normal() {
+        // Slow blink: on for one second, off for the next
+        let cycle = lit(SysMode::Normal, 0, 2000);
+        assert_eq!(windows(&cycle), [(0, 1000)]);
+        assert_eq!(lit(SysMode::Normal, 2000, 2000), cycle);
+    }
+
+    #[test]
+    fn test_pattern_error() {
+        // Fast double-blink, once a second
+        let cycle = lit(SysMode::Error, 0, 1000);
+        assert_eq!(windows(&cycle), [(0, 100), (200, 300)]);
+        assert_eq!(lit(SysMode::Error, 5000, 1000), cycle);
+    }
+
+    #[test]
+    fn test_pattern_boot() {
+        // Steady
+        assert!(lit(SysMode::Boot, 0, 3000).iter().all(|&on| on));
+    }
 }
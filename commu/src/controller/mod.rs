@@ -1,12 +1,39 @@
 use crate::system::*;
+use crate::tasks::blinky::{LED, LedState};
+use utils::prelude::frame::DjiValidator;
 
 #[embassy_executor::task]
 pub async fn main() {
     let mut t = utils::init_ticker!(1);
 
-    SysMode::Normal.set();
+    // Every frame is useless with a corrupted CRC table
+    if DjiValidator::self_test() {
+        SysMode::Normal.set();
+    } else {
+        SysMode::Error.set();
+    }
+
+    let mut last = None;
 
     loop {
+        // Indicate the System Mode on the LED
+        let state = match SysMode::get() {
+            SysMode::Error => LedState {
+                speed: 16,
+                brightness: 255,
+            },
+            SysMode::Boot => LedState {
+                speed: 1,
+                brightness: 64,
+            },
+            SysMode::Normal => LedState::DEFAULT,
+        };
+
+        if last != Some(state) {
+            LED.signal(state);
+            last = Some(state);
+        }
+
         t.next().await
     }
 }
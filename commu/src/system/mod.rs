@@ -12,6 +12,7 @@
 #[derive(defmt::Format, Debug, PartialEq)]
 pub enum Device {
     Placeholder = 0x0000,
+    Referee = 0x0001,
 }
 
 ///
@@ -19,6 +20,7 @@ pub enum Device {
 ///
 pub const WATCH_LIST: &[Device] = &[
     // Device::Placeholder,
+    Device::Referee,
 ];
 
 /// Settings for Heartbeat Monitoring
@@ -4,6 +4,7 @@
 
 use crate::{hal, system::*};
 
+use crate::sync::{blocking_mutex::raw, signal::Signal};
 use hal::peripherals::TIM5;
 use hal::{gpio::OutputType, time::khz, timer};
 use timer::low_level::CountingMode::EdgeAlignedUp;
@@ -13,18 +14,78 @@ use timer::simple_pwm::{PwmPin, SimplePwm};
 const FPS: f32 = 1000.;
 const SPEED: u16 = 1;
 
+///
+/// # LED State
+///
+/// - `speed`: hue steps per frame
+/// - `brightness`: overall scale, 255 is full brightness
+///
+#[derive(defmt::Format, Debug, Clone, Copy, PartialEq)]
+pub struct LedState {
+    pub speed: u16,
+    pub brightness: u8,
+}
+
+impl LedState {
+    pub const DEFAULT: Self = Self {
+        speed: SPEED,
+        brightness: 255,
+    };
+}
+
+/// Requested LED State, applied on the next frame
+pub static LED: Signal<raw::CriticalSectionRawMutex, LedState> = Signal::new();
+
 /// # HUE to RGB Conversion
-/// Converts a hue value (0-1535) to RGB values (0-255).
-fn color_wheel(hue: u16) -> (u8, u8, u8) {
+/// Converts a hue value (0-1535) to RGB values (0-255),
+/// scaled by `brightness` (0-255).
+fn color_wheel(hue: u16, brightness: u8) -> (u8, u8, u8) {
     let x = (hue & 0xFF) as u8;
-    match hue >> 8 {
+    let (r, g, b) = match hue >> 8 {
         0 => (255, x, 0),       // Red -> Yellow
         1 => (255 - x, 255, 0), // Yellow -> Green
         2 => (0, 255, x),       // Green -> Cyan
         3 => (0, 255 - x, 255), // Cyan -> Blue
         4 => (x, 0, 255),       // Blue -> Magenta
         _ => (255, 0, 255 - x), // Magenta -> Red
+    };
+
+    let scale = |v: u8| (v as u16 * brightness as u16 / 255) as u8;
+    (scale(r), scale(g), scale(b))
+}
+
+///
+/// # Gamma Lookup Table
+///
+/// Approximates gamma 2.2 by blending `v²` and `v³`,
+/// so that equal hue steps look equally bright.
+///
+const GAMMA: [u8; 256] = const {
+    let mut table = [0u8; 256];
+    let mut i = 0;
+    while i < 256 {
+        let v = i as u32;
+        table[i] = ((2 * v * v * v / 65025 + 3 * v * v / 255) / 5) as u8;
+        i += 1;
+    }
+    table
+};
+
+// The table must keep both ends and never decrease.
+const _: () = {
+    assert!(GAMMA[0] == 0 && GAMMA[255] == 255);
+    let mut i = 1;
+    while i < 256 {
+        assert!(GAMMA[i - 1] <= GAMMA[i], "Gamma Table not Monotonic");
+        i += 1;
     }
+};
+
+/// # Gamma Correction
+/// Maps a linear value (0-255) to PWM duty (0-255).
+#[inline]
+const fn gamma(v: u8) -> u8 {
+    GAMMA[v as usize]
 }
 
 #[embassy_executor::task]
@@ -35,13 +96,18 @@ pub async fn task(p: BlinkySrc) -> ! {
     (r.enable(), g.enable(), b.enable());
 
     let mut hue: u16 = 0;
+    let mut state = LedState::DEFAULT;
 
     loop {
-        let (rv, gv, bv) = color_wheel(hue);
-        r.set_duty_cycle_fraction(rv as u32, 255);
-        g.set_duty_cycle_fraction(gv as u32, 255);
-        b.set_duty_cycle_fraction(bv as u32, 255);
-        hue = (hue + SPEED) % 1536;
+        if let Some(x) = LED.try_take() {
+            state = x;
+        }
+
+        let (rv, gv, bv) = color_wheel(hue, state.brightness);
+        r.set_duty_cycle_fraction(gamma(rv) as u32, 255);
+        g.set_duty_cycle_fraction(gamma(gv) as u32, 255);
+        b.set_duty_cycle_fraction(gamma(bv) as u32, 255);
+        hue = (hue + state.speed % 1536) % 1536;
 
         t.next().await
     }
@@ -71,3 +137,30 @@ fn init(
 
     (chn.ch3, chn.ch2, chn.ch1)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_wheel() {
+        assert_eq!(color_wheel(0, 255), (255, 0, 0));
+        assert_eq!(color_wheel(384, 255), (127, 255, 0));
+        assert_eq!(color_wheel(1024, 255), (0, 0, 255));
+        assert_eq!(color_wheel(1535, 255), (255, 0, 0));
+    }
+
+    #[test]
+    fn test_color_wheel_brightness() {
+        // Half brightness halves each channel, rounding down.
+        assert_eq!(color_wheel(0, 128), (128, 0, 0));
+        assert_eq!(color_wheel(384, 128), (63, 128, 0));
+
+        for hue in 0..1536 {
+            let (r, g, b) = color_wheel(hue, 255);
+            let half = |v: u8| (v as u16 * 128 / 255) as u8;
+            assert_eq!(color_wheel(hue, 0), (0, 0, 0));
+            assert_eq!(color_wheel(hue, 128), (half(r), half(g), half(b)));
+        }
+    }
+}
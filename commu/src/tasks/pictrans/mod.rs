@@ -2,11 +2,93 @@
 //! # PicTrans Task
 //!
 
-use crate::{hal::usart, system::*};
+use crate::{frame::*, hal::usart, system::*};
 
-use dji_frame::*;
+use dji_pictrans::{Rejected, process_chunk};
 use usart::{Config, DataBits, Parity, StopBits, UartRx};
-use utils::heapless::Vec;
+use utils::{heapless::Vec, prelude::time::Instant, timeout};
+
+/// Link Silence before the Referee is Offline, in ms
+const TIMEOUT_MS: u64 = 200;
+
+///
+/// # Batch Window in us
+///
+/// Reads are coalesced until this long after the first unprocessed
+/// byte, so `process_chunk` runs once per window instead of once per
+/// idle burst. 0 processes every burst.
+///
+/// The window is checked as each read completes, and a read is never
+/// cut short, so after the last burst the batch waits for the link
+/// timeout. A batch is also processed early once the next read could
+/// overflow the accumulation buffer.
+///
+const BATCH_US: u64 = 0;
+
+/// Whether a batch started at `start_us` is due at `now_us`
+const fn batch_due(start_us: u64, now_us: u64, window_us: u64) -> bool {
+    now_us.saturating_sub(start_us) >= window_us
+}
+
+///
+/// # Batch Buffer
+///
+/// Link input waiting to be processed, and when its batch started.
+///
+struct Batch {
+    data: Vec<u8, 128>,
+    /// Arrival of the first unprocessed byte, in us
+    start: Option<u64>,
+    window_us: u64,
+}
+
+impl Batch {
+    const fn new(window_us: u64) -> Self {
+        Self {
+            data: Vec::new(),
+            start: None,
+            window_us,
+        }
+    }
+
+    ///
+    /// # Push a Read
+    ///
+    /// Add `bytes` read at `now_us`, and process the batch once it is
+    /// due, or once a next read of up to `next` bytes could overflow
+    /// it. Returns whether the batch was processed.
+    ///
+    fn push(&mut self, bytes: &[u8], next: usize, now_us: u64) -> bool {
+        let start = *self.start.get_or_insert(now_us);
+
+        let dropped = accumulate(&mut self.data, bytes);
+        if dropped > 0 {
+            defmt::warn!("RC Data Overflow, dropped {} bytes", dropped);
+        }
+
+        let full = self.data.len() + next > self.data.capacity();
+        if full || batch_due(start, now_us, self.window_us) {
+            return self.flush();
+        }
+
+        false
+    }
+
+    ///
+    /// # Flush
+    ///
+    /// Process a started batch, whether or not it is due, e.g. once
+    /// the link went quiet. Returns whether there was one.
+    ///
+    fn flush(&mut self) -> bool {
+        if self.start.take().is_none() {
+            return false;
+        }
+
+        process(&mut self.data);
+        true
+    }
+}
 
 #[embassy_executor::task]
 pub async fn task(p: Uart3pSrc) -> ! {
@@ -19,88 +101,173 @@ pub async fn task(p: Uart3pSrc) -> ! {
     // Safety: Config is valid, so Unwrap is safe.
     let mut pt = UartRx::new(p.uart_p, Irqs, p.uart_rx, p.dma_rx, config).unwrap();
 
+    run(async |buf: &mut [u8]| pt.read_until_idle(buf).await).await
+}
+
+///
+/// # Read Loop
+///
+/// Read the link with `read`, and process its input in batches.
+///
+/// Silence for `TIMEOUT_MS` marks the referee offline and puts the
+/// system in `SysMode::Error`; the previous mode is restored once
+/// data arrives again.
+///
+async fn run<E: defmt::Format>(mut read: impl AsyncFnMut(&mut [u8]) -> Result<usize, E>) -> ! {
     let mut buffer = [0u8; 64];
-    let mut data: _ = Vec::<u8, 128>::new();
+    let mut batch = Batch::new(BATCH_US);
+    // Mode to restore once a lost link is back
+    let mut lost: Option<SysMode> = None;
 
     loop {
-        match pt.read_until_idle(&mut buffer).await {
-            Ok(x) if x > 0 => {
-                if let Err(_) = data.extend_from_slice(&buffer[..x]) {
-                    defmt::warn!("RC Data Overflow, clearing buffer");
-                    data.clear();
-                    continue;
+        match timeout!(TIMEOUT_MS, read(&mut buffer)).await {
+            Ok(Ok(x)) if x > 0 => {
+                Device::Referee.feed();
+
+                if let Some(mode) = lost.take() {
+                    defmt::info!("RC Link Restored");
+                    mode.set();
                 }
 
-                let s = data_process::<_, 5>(&mut data);
-                defmt::info!("RC Data: {:X}", s);
+                let now = Instant::now().as_micros();
+                batch.push(&buffer[..x], buffer.len(), now);
             }
 
-            Ok(_) => {
+            Ok(Ok(_)) => {
                 // No data received
             }
 
-            Err(e) => {
+            Ok(Err(e)) => {
                 defmt::error!("RC Read Error: {:?}", e);
             }
+
+            Err(_) => {
+                batch.flush();
+
+                if Device::Referee.check() {
+                    defmt::warn!("RC Link Lost: no data for {} ms", TIMEOUT_MS);
+                }
+
+                Device::Referee.kill();
+                lost.get_or_insert_with(SysMode::get);
+                SysMode::Error.set();
+            }
         };
     }
 }
 
-#[derive(Debug, defmt::Format)]
-struct CustomRobotData<const N: usize> {
-    data: [u8; N],
-}
+/// Decode and log the complete messages in `data`
+fn process(data: &mut Vec<u8, 128>) {
+    // A batch may hold more messages than fit in one pass.
+    loop {
+        let msgs = process_chunk::<128, 4>(data, log_rejected);
+        for msg in &msgs {
+            defmt::info!("RC Data: {:X}", msg);
+        }
 
-impl<const N: usize> Marshaler for CustomRobotData<N> {
-    const CMD_ID: u16 = 0x0302;
+        if !msgs.is_full() {
+            break;
+        }
+    }
+}
 
-    fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
-        if dst.len() < N {
-            return Err(Error::BufferTooSmall {
-                need: N - dst.len(),
-            });
+/// Log input that `process_chunk` dropped
+fn log_rejected(r: Rejected<'_>) {
+    match r {
+        Rejected::Garbage(bytes, e) => {
+            defmt::debug!("Dropped RC Data ({}):\n{}", e, HexDump(bytes));
         }
+        Rejected::Unknown(id) => defmt::warn!("Unknown RC Data CMD ID: {}", id),
+        Rejected::Invalid(id, e) => defmt::warn!("Invalid RC Data (CMD ID {}): {}", id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+    use utils::mock_time::block_on;
+    use utils::prelude::time::Duration;
 
-        dst[..N].copy_from_slice(&self.data);
-        Ok(N)
+    #[test]
+    fn test_batch_due() {
+        // No window is always due; a window is due from its end on.
+        assert!(batch_due(1_000, 1_000, 0));
+        assert!(!batch_due(1_000, 1_499, 500));
+        assert!(batch_due(1_000, 1_500, 500));
+        assert!(!batch_due(1_000, 900, 500)); // clock behind the start
     }
 
-    fn unmarshal(raw: &[u8]) -> Result<Self> {
-        if raw.len() != N {
-            return Err(Error::InvalidDataLength { expected: N });
-        }
+    #[test]
+    fn test_batch_window() {
+        let mut batch = Batch::new(500);
+        assert!(!batch.push(&[0; 3], 64, 1_000));
+        assert!(!batch.push(&[0; 3], 64, 1_499));
+        assert_eq!(batch.data.len(), 6);
+
+        // Due once the window from the first read has passed
+        assert!(batch.push(&[0; 3], 64, 1_500));
+        assert_eq!(batch.start, None);
+
+        // The next read starts a new window.
+        assert!(!batch.push(&[0; 3], 64, 1_600));
+        assert_eq!(batch.start, Some(1_600));
 
-        let mut data = [0u8; N];
-        data.copy_from_slice(&raw[..N]);
-        Ok(CustomRobotData { data })
+        // Without a window, every read is processed.
+        let mut batch = Batch::new(0);
+        assert!(batch.push(&[0; 3], 64, 1_000));
     }
-}
 
-fn data_process<const N: usize, const R: usize>(
-    src: &mut Vec<u8, N>,
-) -> Option<CustomRobotData<R>> {
-    let msger: Messager<DjiValidator> = Messager::new(0);
-
-    match msger.unpack(src) {
-        Ok((x, size)) => {
-            // defmt::info!("Parsed RC Data: {:X}", x);
-            let id = x.cmd_id();
-            let seq = x.sequence();
-            let msg = match id {
-                CustomRobotData::<R>::CMD_ID => CustomRobotData::<R>::unmarshal(x.payload()).ok(),
-                _ => {
-                    defmt::warn!("Unknown RC Data CMD ID: {}", id);
-                    None
-                }
-            };
+    #[test]
+    fn test_batch_full() {
+        let mut batch = Batch::new(u64::MAX);
+        assert!(!batch.push(&[0; 32], 64, 0));
+        assert!(!batch.push(&[0; 32], 64, 0));
 
-            src.drain(..size);
-            msg
-        }
+        // Room for 64 more bytes is left until now.
+        assert!(batch.push(&[0; 1], 64, 0));
+        assert_eq!(batch.start, None);
+    }
 
-        Err(e) => {
-            src.drain(..e.skip());
-            None
-        }
+    #[test]
+    fn test_batch_flush() {
+        // Nothing to process before a read
+        let mut batch = Batch::new(u64::MAX);
+        assert!(!batch.flush());
+
+        // A started batch is processed before it is due, once.
+        assert!(!batch.push(&[0; 3], 64, 0));
+        assert!(batch.flush());
+        assert!(!batch.flush());
+    }
+
+    #[test]
+    fn test_link_timeout() {
+        SysMode::Normal.set();
+        let calls = Cell::new(0);
+        let start = Instant::now();
+
+        // Data at once, then a stalled read, then data again
+        let read = async |buf: &mut [u8]| -> Result<usize, ()> {
+            let n = calls.get();
+            calls.set(n + 1);
+            match n {
+                0 => {}
+                2 => {
+                    assert_eq!(start.elapsed(), Duration::from_millis(TIMEOUT_MS));
+                    assert!(!Device::Referee.check());
+                    assert_eq!(SysMode::get(), SysMode::Error);
+                }
+                _ => core::future::pending().await,
+            }
+            buf[0] = 0;
+            Ok(1)
+        };
+
+        // Stop past the restoring read, but before the next timeout.
+        let _ = block_on(timeout!(TIMEOUT_MS + TIMEOUT_MS / 2, run(read)));
+        assert_eq!(calls.get(), 4);
+        assert!(Device::Referee.check());
+        assert_eq!(SysMode::get(), SysMode::Normal);
     }
 }
@@ -0,0 +1,230 @@
+//! Derive macro for `dji_frame::Marshaler`.
+//!
+//! Most payloads are a fixed sequence of little-endian fields, so their
+//! `marshal`/`unmarshal` can be generated instead of hand-writing the
+//! offsets. Use it through the `derive` feature of `dji-frame`.
+//!
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Expr, Field, Fields, Ident, Meta, Type, parse_macro_input};
+
+///
+/// Derive `Marshaler` for a struct of fixed-size fields.
+///
+/// Fields are laid out in declaration order, without padding:
+///
+/// - `u8`, `i8`: 1 byte
+/// - `u16`, `i16`: 2 bytes, little-endian
+/// - `u32`, `i32`, `f32`: 4 bytes, little-endian
+/// - `u64`: 8 bytes, little-endian
+/// - `bool`: 1 byte, 0 or 1
+/// - `#[from_repr]` fields: 1 byte, for a `Copy`, `#[repr(u8)]` enum
+///   deriving `strum::FromRepr`
+///
+/// Any other field type is rejected.
+///
+/// The command id is given with `#[cmd_id = ...]`.
+///
+/// `unmarshal` returns `DecodeError` at the offset of a `bool` or
+/// enum field holding an unknown value.
+///
+/// ```ignore
+/// #[derive(Marshaler)]
+/// #[cmd_id = 0x0203]
+/// pub struct RobotPos {
+///     x: f32,
+///     y: f32,
+///     z: f32,
+/// }
+///
+/// #[derive(Marshaler)]
+/// #[cmd_id = 0x0F00]
+/// pub struct Status {
+///     #[from_repr]
+///     game_type: GameType,
+///     hp: u16,
+/// }
+/// ```
+///
+#[proc_macro_derive(Marshaler, attributes(cmd_id, from_repr))]
+pub fn derive_marshaler(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Encoding of one field.
+enum Kind {
+    /// Single byte, read and written directly.
+    Byte,
+    /// Signed single byte.
+    SignedByte,
+    /// Multi-byte value through the `le` helper of this name.
+    Le(&'static str, usize),
+    /// `bool` stored as 0 or 1.
+    Bool,
+    /// `#[repr(u8)]` enum decoded with `from_repr`, marked `#[from_repr]`.
+    Enum,
+}
+
+impl Kind {
+    fn of(field: &Field) -> syn::Result<Self> {
+        if let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("from_repr")) {
+            let Meta::Path(_) = &attr.meta else {
+                return Err(Error::new_spanned(attr, "expected `#[from_repr]`"));
+            };
+            return Ok(Self::Enum);
+        }
+
+        let ty = &field.ty;
+        let unsupported = || Error::new_spanned(ty, "unsupported field type");
+
+        let Type::Path(path) = ty else {
+            return Err(unsupported());
+        };
+
+        let Some(ident) = path.path.get_ident() else {
+            return Err(unsupported());
+        };
+
+        Ok(match ident.to_string().as_str() {
+            "u8" => Self::Byte,
+            "i8" => Self::SignedByte,
+            "u16" => Self::Le("u16", 2),
+            "i16" => Self::Le("i16", 2),
+            "u32" => Self::Le("u32", 4),
+            "i32" => Self::Le("i32", 4),
+            "f32" => Self::Le("f32", 4),
+            "u64" => Self::Le("u64", 8),
+            "bool" => Self::Bool,
+            _ => return Err(unsupported()),
+        })
+    }
+
+    const fn size(&self) -> usize {
+        match self {
+            Self::Le(_, size) => *size,
+            _ => 1,
+        }
+    }
+
+    /// Statement writing `value` at `at` into `dst`.
+    fn write(&self, value: TokenStream2, at: usize) -> TokenStream2 {
+        match self {
+            Self::Byte => quote! { dst[#at] = #value; },
+            Self::SignedByte | Self::Bool | Self::Enum => quote! { dst[#at] = #value as u8; },
+            Self::Le(name, _) => {
+                let write = format_ident!("write_{}_le", name);
+                quote! { ::dji_frame::le::#write(dst, #at, #value); }
+            }
+        }
+    }
+
+    /// Expression reading the field of type `ty` at `at` from `raw`.
+    fn read(&self, ty: &Type, at: usize) -> TokenStream2 {
+        match self {
+            Self::Byte => quote! { raw[#at] },
+            Self::SignedByte => quote! { raw[#at] as i8 },
+            Self::Le(name, _) => {
+                let read = format_ident!("read_{}_le", name);
+                quote! { ::dji_frame::le::#read(raw, #at) }
+            }
+            Self::Bool => quote! {
+                match raw[#at] {
+                    0 => false,
+                    1 => true,
+                    _ => return Err(::dji_frame::Error::DecodeError { at: #at }),
+                }
+            },
+            Self::Enum => quote! {
+                match <#ty>::from_repr(raw[#at]) {
+                    Some(value) => value,
+                    None => return Err(::dji_frame::Error::DecodeError { at: #at }),
+                }
+            },
+        }
+    }
+}
+
+/// Value of the `#[cmd_id = ...]` attribute.
+fn cmd_id(input: &DeriveInput) -> syn::Result<Expr> {
+    let mut found = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("cmd_id")) {
+        let Meta::NameValue(meta) = &attr.meta else {
+            return Err(Error::new_spanned(attr, "expected `#[cmd_id = ...]`"));
+        };
+
+        if found.replace(meta.value.clone()).is_some() {
+            return Err(Error::new_spanned(attr, "duplicate `cmd_id` attribute"));
+        }
+    }
+
+    found.ok_or_else(|| Error::new_spanned(&input.ident, "missing `#[cmd_id = ...]` attribute"))
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Marshaler` can only be derived for structs",
+        ));
+    };
+
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`Marshaler` can only be derived for structs with named fields",
+        ));
+    };
+
+    let cmd_id = cmd_id(input)?;
+
+    let mut at = 0;
+    let mut writes = Vec::new();
+    let mut reads = Vec::new();
+    for field in &fields.named {
+        // Safety: the fields are named, so Unwrap is safe.
+        let name: &Ident = field.ident.as_ref().unwrap();
+        let kind = Kind::of(field)?;
+
+        writes.push(kind.write(quote! { self.#name }, at));
+        let read = kind.read(&field.ty, at);
+        reads.push(quote! { #name: #read });
+
+        at += kind.size();
+    }
+    let size = at;
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::dji_frame::Marshaler for #ident #ty_generics #where_clause {
+            const CMD_ID: u16 = #cmd_id;
+            const MARSHALED_LEN: Option<usize> = Some(#size);
+
+            fn marshal(&self, dst: &mut [u8]) -> ::dji_frame::Result<usize> {
+                if dst.len() < #size {
+                    return Err(::dji_frame::Error::BufferTooSmall { need: #size });
+                }
+
+                #(#writes)*
+
+                Ok(#size)
+            }
+
+            fn unmarshal(raw: &[u8]) -> ::dji_frame::Result<Self> {
+                if raw.len() != #size {
+                    return Err(::dji_frame::Error::InvalidDataLength { expected: #size });
+                }
+
+                Ok(Self {
+                    #(#reads,)*
+                })
+            }
+        }
+    })
+}
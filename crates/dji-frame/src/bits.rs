@@ -0,0 +1,41 @@
+//!
+//! Bit flag accessors for bitmap fields.
+//!
+
+///
+/// Generate `const fn name(&self) -> bool` accessors for the bits
+/// of an integer field, inside an `impl` block.
+///
+/// Each accessor takes its visibility, attributes (such as doc
+/// comments) and bit index, counted from the least significant bit.
+///
+/// ```
+/// struct Status {
+///     flags: u16,
+/// }
+///
+/// impl Status {
+///     dji_frame::bitflags_accessors!(flags => {
+///         /// Gimbal is powered
+///         pub gimbal_on: 0,
+///         pub chassis_on: 1,
+///         pub shooter_on: 2,
+///     });
+/// }
+///
+/// let status = Status { flags: 0b101 };
+/// assert!(status.gimbal_on() && !status.chassis_on() && status.shooter_on());
+/// ```
+///
+#[macro_export]
+macro_rules! bitflags_accessors {
+    ($field:ident => { $($(#[$meta:meta])* $vis:vis $name:ident: $bit:expr),* $(,)? }) => {
+        $(
+            $(#[$meta])*
+            #[inline]
+            $vis const fn $name(&self) -> bool {
+                (self.$field >> $bit) & 1 != 0
+            }
+        )*
+    };
+}
@@ -0,0 +1,145 @@
+//!
+//! Streaming frame decoding.
+//!
+//! This module provides `FrameDecoder`, which walks a byte
+//! buffer and yields every valid frame in it, resynchronizing
+//! past corrupted or foreign bytes, and `accumulate`, which
+//! appends received chunks to a bounded stream buffer.
+//!
+//! With the `record` feature enabled, a recorder callback can be
+//! attached to capture the raw bytes of each decoded frame for
+//! offline replay. Without the feature the hook does not exist.
+//!
+
+use crate::msger::SOF;
+use crate::private::*;
+
+/// Callback receiving the raw bytes of a decoded frame.
+#[cfg(feature = "record")]
+pub type Recorder<'a> = &'a mut dyn FnMut(&[u8]);
+
+///
+/// Append a received chunk to a bounded stream buffer.
+///
+/// When the chunk does not fit, leading bytes are dropped up to the
+/// first `SOF` that leaves enough room, so a partial frame at the
+/// back of the buffer survives. A chunk larger than the buffer only
+/// keeps its tail.
+///
+/// Returns the number of dropped bytes.
+///
+pub fn accumulate<const N: usize>(buf: &mut heapless::Vec<u8, N>, chunk: &[u8]) -> usize {
+    let skip = chunk.len().saturating_sub(N);
+    let chunk = &chunk[skip..];
+
+    let need = (buf.len() + chunk.len()).saturating_sub(N);
+    let drain = match need {
+        0 => 0,
+        _ => buf[need..]
+            .iter()
+            .position(|&x| SOF == x)
+            .map_or(buf.len(), |start| need + start),
+    };
+    buf.drain(..drain);
+
+    // Safety: enough room has been drained, so Unwrap is safe.
+    buf.extend_from_slice(chunk).unwrap();
+    skip + drain
+}
+
+///
+/// Iterator over the frames contained in a byte buffer.
+///
+/// Bytes that cannot start a valid frame are skipped. Iteration
+/// stops at the first incomplete frame; the unread tail is
+/// available through `remaining` so it can be kept for the next read.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut decoder = FrameDecoder::<DjiValidator>::new(&buffer);
+/// for frame in &mut decoder {
+///     handle(frame);
+/// }
+/// let leftover = decoder.remaining();
+/// ```
+///
+pub struct FrameDecoder<'a, V: Validator> {
+    /// Messager used to validate frames.
+    msger: Messager<V>,
+    /// Bytes not yet consumed.
+    src: &'a [u8],
+    /// Tracker updated with the sequence of each decoded frame.
+    tracker: Option<&'a mut SeqTracker>,
+    /// Callback invoked with the raw bytes of each decoded frame.
+    #[cfg(feature = "record")]
+    recorder: Option<Recorder<'a>>,
+}
+
+impl<'a, V: Validator> FrameDecoder<'a, V> {
+    /// Create a new `FrameDecoder` over `src`.
+    pub const fn new(src: &'a [u8]) -> Self {
+        Self {
+            msger: Messager::new(0),
+            src,
+            tracker: None,
+            #[cfg(feature = "record")]
+            recorder: None,
+        }
+    }
+
+    /// Attach a `SeqTracker` updated with every decoded frame.
+    pub fn track(mut self, tracker: &'a mut SeqTracker) -> Self {
+        self.tracker = Some(tracker);
+        self
+    }
+
+    ///
+    /// Attach a recorder invoked with each decoded frame.
+    ///
+    /// The callback receives the whole frame, header and
+    /// CRC16 included, exactly as it appeared on the wire.
+    ///
+    #[cfg(feature = "record")]
+    pub fn on_frame(mut self, recorder: Recorder<'a>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Get the bytes not consumed yet.
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        self.src
+    }
+}
+
+impl<'a, V: Validator> Iterator for FrameDecoder<'a, V> {
+    type Item = RawFrame<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.src.is_empty() {
+            match self.msger.unpack(self.src) {
+                Ok((frame, size)) => {
+                    #[cfg(feature = "record")]
+                    if let Some(recorder) = self.recorder.as_mut() {
+                        recorder(&self.src[..size]);
+                    }
+
+                    if let Some(tracker) = self.tracker.as_mut() {
+                        tracker.update(frame.sequence());
+                    }
+
+                    self.src = &self.src[size..];
+                    return Some(frame);
+                }
+
+                Err(e) => match e.skip() {
+                    0 => break,
+                    skip => self.src = &self.src[skip..],
+                },
+            }
+        }
+
+        None
+    }
+}
@@ -0,0 +1,67 @@
+//!
+//! Typed dispatch of raw frames.
+//!
+
+///
+/// Generate a message enum over `Marshaler` types, and a function
+/// decoding a `RawFrame` into it by command id.
+///
+/// Each variant wraps one type. The function returns `Ok(None)` for
+/// a command id of none of them, and the payload error for a known
+/// command id with a bad payload. Colliding command ids fail the build.
+///
+/// ```
+/// use dji_frame::{Messager, DjiValidator, Raw};
+///
+/// dji_frame::dispatch! {
+///     #[derive(Debug)]
+///     pub enum Message {
+///         Ping(Raw<0x0001, 2>),
+///         Pong(Raw<0x0002, 4>),
+///     }
+///
+///     /// Decode a frame of this link.
+///     pub fn decode;
+/// }
+///
+/// let mut msger: Messager<DjiValidator> = Messager::new(0);
+/// let mut buf = [0u8; 32];
+/// let size = msger.pack(&Raw::<0x0002, 4>([1, 2, 3, 4]), &mut buf).unwrap();
+/// let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+///
+/// assert!(matches!(decode(&frame), Ok(Some(Message::Pong(Raw([1, 2, 3, 4]))))));
+/// ```
+///
+#[macro_export]
+macro_rules! dispatch {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident($ty:ty)),* $(,)?
+        }
+
+        $(#[$fmeta:meta])*
+        $fvis:vis fn $decode:ident;
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($(#[$vmeta])* $variant($ty),)*
+        }
+
+        const _: () = $crate::assert_unique_ids(&[
+            $(<$ty as $crate::Marshaler>::CMD_ID,)*
+        ]);
+
+        $(#[$fmeta])*
+        $fvis fn $decode(raw: &$crate::RawFrame) -> $crate::Result<::core::option::Option<$name>> {
+            let id = raw.cmd_id();
+            $(
+                if id == <$ty as $crate::Marshaler>::CMD_ID {
+                    let msg = <$ty as $crate::Marshaler>::unmarshal(raw.payload())?;
+                    return Ok(Some($name::$variant(msg)));
+                }
+            )*
+            Ok(None)
+        }
+    };
+}
@@ -19,7 +19,8 @@ pub type Result<T> = StdResult<T, Error>;
 /// - `Error::ReSync` — `skip` is the index where a valid header was found (bytes skipped).
 /// - `Error::MissingHeader` — `skip` is the number of bytes scanned (often `src.len()` when none found).
 /// - `Error::UnexpectedEnd` — `read` is the buffer length at the point the data was incomplete.
-/// - `Error::InvalidChecksum` — `at` is the offset immediately after the payload where CRC failed.
+/// - `Error::InvalidChecksum` — `at` is the offset immediately after the header whose CRC8 failed.
+/// - `Error::ChecksumMismatch` — `at` is the full frame length whose tail check failed.
 /// - `Error::ParseError` — `at` is the offset where payload parsing failed.
 ///
 ///
@@ -38,6 +39,12 @@ pub enum Error {
     MissingHeader { skip: usize },
     /// Checksum validation failed for the data.
     InvalidChecksum { at: usize },
+    /// The frame tail check differs from the received one.
+    ChecksumMismatch {
+        at: usize,
+        computed: u32,
+        received: u32,
+    },
     /// Failed to parse the payload or a field within the message.
     DecodeError { at: usize },
     /// Failed to encode the message.
@@ -49,8 +56,7 @@ pub enum Error {
 impl Error {
     /// Get the skip position associated with the error.
     pub fn skip(&self) -> usize {
-        #[cfg(feature = "log")]
-        trace!("MSG Skiped: {:?}", self);
+        trace_frame!("MSG Skiped: {:?}", self);
 
         match self {
             Self::BufferTooSmall { .. } => 0,
@@ -58,7 +64,10 @@ impl Error {
             Self::UnexpectedEnd { .. } => 0,
             Self::ReSync { skip } => *skip,
             Self::MissingHeader { skip } => *skip,
+            // A bad header may just be a stray SOF, so step over one byte.
             Self::InvalidChecksum { .. } => 1,
+            // A bad tail has a header-validated length, so drop the whole frame.
+            Self::ChecksumMismatch { at, .. } => *at,
             Self::DecodeError { at } => *at,
             Self::EncodeError { .. } => 0,
             Self::InvalidDataLength { .. } => 0,
@@ -86,6 +95,16 @@ impl Display for Error {
             Self::InvalidChecksum { at } => {
                 write!(f, "Invalid checksum at offset {at}")
             }
+            Self::ChecksumMismatch {
+                at,
+                computed,
+                received,
+            } => {
+                write!(
+                    f,
+                    "Checksum mismatch at offset {at}, computed {computed:#06X}, received {received:#06X}"
+                )
+            }
             Self::DecodeError { at } => {
                 write!(f, "Failed to parse payload at offset {at}")
             }
@@ -22,11 +22,27 @@
 //!   from raw payload data. Each implementation is bound to a fixed
 //!   command ID and is independent from framing details.
 //!
+//! - **`MarshalerRef`**
+//!   A borrowing counterpart to `Marshaler` for payload views that
+//!   keep referencing the input buffer instead of copying it.
+//!
 //! - **`RawFrame`**
 //!   A lightweight view of a decoded frame, exposing the command ID,
 //!   sequence number, and a borrowed payload slice without allocation.
 //!
+//! - **`OwnedFrame`**
+//!   A decoded frame owning a copy of its payload, for handing
+//!   frames across tasks.
+//!
+//! - **`RawPayload`**
+//!   A zero-copy `MarshalerRef` view over an undecoded payload.
+//!
+//! - **`Raw`**
+//!   A fixed-size byte payload bound to a command ID, for forwarding
+//!   data without a dedicated message type.
+//!
 
+use crate::msger::write_frame;
 use crate::private::*;
 
 ///
@@ -36,7 +52,22 @@ use crate::private::*;
 /// - CRC8 for the frame header
 /// - CRC16 for the frame body
 ///
+/// The field sizes default to the DJI layout and can be
+/// overridden by protocol variants. The header always starts
+/// with SOF, length and sequence, and ends with the CRC8;
+/// any bytes in between are reserved and written as zero.
+///
 pub trait Validator {
+    /// Short name of the validator, used in log output.
+    const NAME: &'static str = "Custom";
+
+    /// Size of the frame header (SOF + length + sequence + CRC8).
+    const HEADER_LEN: usize = 5;
+    /// Size of the command ID field, 1 or 2 bytes.
+    const CMDID_LEN: usize = 2;
+    /// Size of the tail check field, 1 to 4 bytes.
+    const TAIL_LEN: usize = 2;
+
     ///
     /// Calculate CRC8 over the given raw bytes.
     ///
@@ -50,6 +81,16 @@ pub trait Validator {
     /// (header + command + payload).
     ///
     fn calculate_crc16(raw: &[u8]) -> u16;
+
+    ///
+    /// Calculate the tail check over the given raw bytes.
+    ///
+    /// Only the low `TAIL_LEN` bytes are sent. Defaults to
+    /// `calculate_crc16`; override it for a wider tail.
+    ///
+    fn calculate_tail(raw: &[u8]) -> u32 {
+        Self::calculate_crc16(raw) as u32
+    }
 }
 
 ///
@@ -61,6 +102,8 @@ pub trait Validator {
 pub struct DjiValidator;
 
 impl Validator for DjiValidator {
+    const NAME: &'static str = "Dji";
+
     fn calculate_crc8(raw: &[u8]) -> u8 {
         calc_dji8(raw)
     }
@@ -70,6 +113,20 @@ impl Validator for DjiValidator {
     }
 }
 
+impl DjiValidator {
+    ///
+    /// Check both CRCs against their standard check values.
+    ///
+    /// A corrupted CRC table would make every frame fail to
+    /// validate, so call this once at boot before talking on
+    /// any link.
+    ///
+    pub fn self_test() -> bool {
+        const CHECK: &[u8] = b"123456789";
+        Self::calculate_crc8(CHECK) == 0x0B && Self::calculate_crc16(CHECK) == 0x6F91
+    }
+}
+
 ///
 /// Payload marshaling interface.
 ///
@@ -83,6 +140,13 @@ pub trait Marshaler: Sized {
     /// Command ID associated with this payload type.
     const CMD_ID: u16;
 
+    ///
+    /// Number of bytes `marshal` always writes, if fixed.
+    ///
+    /// Checked by `marshal_checked` in debug builds.
+    ///
+    const MARSHALED_LEN: Option<usize> = None;
+
     ///
     /// Serialize the payload into the destination buffer.
     ///
@@ -95,6 +159,21 @@ pub trait Marshaler: Sized {
     ///
     fn marshal(&self, dst: &mut [u8]) -> Result<usize>;
 
+    ///
+    /// Serialize the payload, asserting the written length.
+    ///
+    /// In debug builds, panics if `marshal` succeeds with a
+    /// length other than `MARSHALED_LEN`. Otherwise identical
+    /// to `marshal`.
+    ///
+    fn marshal_checked(&self, dst: &mut [u8]) -> Result<usize> {
+        let size = self.marshal(dst)?;
+        if let Some(len) = Self::MARSHALED_LEN {
+            debug_assert_eq!(size, len, "Marshaled length mismatch");
+        }
+        Ok(size)
+    }
+
     ///
     /// Deserialize a payload from raw bytes.
     ///
@@ -111,6 +190,50 @@ pub trait Marshaler: Sized {
     fn unmarshal(raw: &[u8]) -> Result<Self>;
 }
 
+///
+/// Assert that no command ID repeats.
+///
+/// Evaluated in a `const` item, a duplicate fails the build:
+///
+/// ```compile_fail
+/// const _: () = dji_frame::assert_unique_ids(&[0x0201, 0x0202, 0x0201]);
+/// ```
+///
+pub const fn assert_unique_ids(ids: &[u16]) {
+    let mut i = 0;
+    while i < ids.len() {
+        let mut j = i + 1;
+        while j < ids.len() {
+            assert!(ids[i] != ids[j], "Duplicate Command ID");
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+///
+/// Borrowing payload unmarshaling interface.
+///
+/// `MarshalerRef` is an optional companion to `Marshaler` for
+/// payload types that hold slices into the input buffer,
+/// avoiding a copy for large messages.
+///
+pub trait MarshalerRef<'a>: Sized {
+    ///
+    /// Deserialize a payload view from raw bytes.
+    ///
+    /// The input slice contains only the payload portion
+    /// (no header, command ID, or CRC), and the returned
+    /// value may borrow from it for `'a`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data is invalid
+    /// or does not match the expected payload format.
+    ///
+    fn unmarshal_ref(raw: &'a [u8]) -> Result<Self>;
+}
+
 ///
 /// A validated but undecoded frame.
 ///
@@ -125,7 +248,7 @@ pub trait Marshaler: Sized {
 ///
 /// The payload slice borrows from the original input buffer.
 ///
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct RawFrame<'t> {
     /// Command ID of the frame.
@@ -148,6 +271,32 @@ impl RawFrame<'_> {
     pub fn sequence(&self) -> u8 {
         self.sequence
     }
+
+    ///
+    /// Re-emit the full frame into the destination buffer.
+    ///
+    /// The header and tail CRCs are recomputed with `V`, so the
+    /// output only matches the original bytes if the same
+    /// validator produced them.
+    ///
+    /// Returns the number of bytes written on success.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the destination buffer is too small.
+    ///
+    pub fn reconstruct<V: Validator>(&self, dst: &mut [u8]) -> Result<usize> {
+        let size = self.payload.len();
+        let total = V::HEADER_LEN + V::CMDID_LEN + size + V::TAIL_LEN;
+        if dst.len() < total {
+            return Err(Error::BufferTooSmall { need: total });
+        }
+
+        let payload_offset = V::HEADER_LEN + V::CMDID_LEN;
+        dst[payload_offset..payload_offset + size].copy_from_slice(self.payload);
+
+        write_frame::<V>(self.cmd_id, self.sequence, size, dst)
+    }
 }
 
 impl<'t> RawFrame<'t> {
@@ -162,3 +311,144 @@ impl<'t> RawFrame<'t> {
         self.payload
     }
 }
+
+///
+/// A validated frame owning its payload.
+///
+/// `OwnedFrame` copies the payload of a `RawFrame` into a
+/// fixed-capacity buffer, so it can outlive the input buffer,
+/// for example to be posted to a channel.
+///
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OwnedFrame<const P: usize> {
+    /// Command ID of the frame.
+    cmd_id: u16,
+    /// Sequence number of the frame.
+    sequence: u8,
+    /// Copied payload bytes.
+    payload: heapless::Vec<u8, P>,
+}
+
+impl<const P: usize> OwnedFrame<P> {
+    /// Get the command ID of this frame.
+    #[inline]
+    pub fn cmd_id(&self) -> u16 {
+        self.cmd_id
+    }
+
+    /// Get the sequence number of this frame.
+    #[inline]
+    pub fn sequence(&self) -> u8 {
+        self.sequence
+    }
+
+    /// Get the payload bytes.
+    #[inline]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Borrow this frame as a `RawFrame`.
+    #[inline]
+    pub fn as_raw(&self) -> RawFrame<'_> {
+        RawFrame {
+            cmd_id: self.cmd_id,
+            sequence: self.sequence,
+            payload: &self.payload,
+        }
+    }
+}
+
+impl<const P: usize> TryFrom<&RawFrame<'_>> for OwnedFrame<P> {
+    type Error = Error;
+
+    fn try_from(frame: &RawFrame<'_>) -> Result<Self> {
+        let payload =
+            heapless::Vec::from_slice(frame.payload).map_err(|_| Error::BufferTooSmall {
+                need: frame.payload.len(),
+            })?;
+
+        Ok(Self {
+            cmd_id: frame.cmd_id,
+            sequence: frame.sequence,
+            payload,
+        })
+    }
+}
+
+///
+/// A borrowed, undecoded payload.
+///
+/// `RawPayload` is the zero-copy `MarshalerRef` view of a
+/// `RawFrame` payload. It accepts any payload and never copies.
+///
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RawPayload<'a>(&'a [u8]);
+
+impl<'a> RawPayload<'a> {
+    /// Get the borrowed payload bytes.
+    #[inline]
+    pub fn bytes(&self) -> &'a [u8] {
+        self.0
+    }
+}
+
+impl<'a> MarshalerRef<'a> for RawPayload<'a> {
+    fn unmarshal_ref(raw: &'a [u8]) -> Result<Self> {
+        Ok(Self(raw))
+    }
+}
+
+impl<'t> From<&RawFrame<'t>> for RawPayload<'t> {
+    fn from(frame: &RawFrame<'t>) -> Self {
+        Self(frame.payload)
+    }
+}
+
+///
+/// A fixed-size payload of raw bytes.
+///
+/// `Raw` implements `Marshaler` for any command ID and size, so
+/// opaque data can be forwarded without defining a message type.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Raw<const CMD: u16, const N: usize>(pub [u8; N]);
+
+impl<const CMD: u16, const N: usize> Raw<CMD, N> {
+    /// Wrap the given bytes.
+    #[inline]
+    pub const fn from_bytes(bytes: [u8; N]) -> Self {
+        Self(bytes)
+    }
+
+    /// Get the payload bytes.
+    #[inline]
+    pub const fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const CMD: u16, const N: usize> Marshaler for Raw<CMD, N> {
+    const CMD_ID: u16 = CMD;
+    const MARSHALED_LEN: Option<usize> = Some(N);
+
+    fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
+        if dst.len() < N {
+            return Err(Error::BufferTooSmall { need: N });
+        }
+
+        dst[..N].copy_from_slice(&self.0);
+        Ok(N)
+    }
+
+    fn unmarshal(raw: &[u8]) -> Result<Self> {
+        let bytes = raw
+            .try_into()
+            .map_err(|_| Error::InvalidDataLength { expected: N })?;
+
+        Ok(Self(bytes))
+    }
+}
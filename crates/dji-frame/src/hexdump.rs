@@ -0,0 +1,58 @@
+//!
+//! Readable byte dumps for logging.
+//!
+//! This module provides `HexDump`, a wrapper that formats a byte
+//! slice as offset-prefixed lines of eight hex bytes, for both
+//! `core::fmt` and `defmt` output.
+//!
+
+use crate::private::*;
+
+/// Number of bytes per line.
+const LINE: usize = 8;
+
+///
+/// Grouped hex view of a byte slice.
+///
+/// # Example
+///
+/// ```text
+/// 0000: A5 05 00 01 02 03 04 05
+/// 0008: 06 07
+/// ```
+///
+#[derive(Debug, Clone, Copy)]
+pub struct HexDump<'a>(pub &'a [u8]);
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for (i, line) in self.0.chunks(LINE).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+
+            write!(f, "{:04X}:", i * LINE)?;
+            for byte in line {
+                write!(f, " {byte:02X}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for HexDump<'_> {
+    fn format(&self, fmt: defmt::Formatter) {
+        for (i, line) in self.0.chunks(LINE).enumerate() {
+            if i > 0 {
+                defmt::write!(fmt, "\n");
+            }
+
+            defmt::write!(fmt, "{=usize:04X}:", i * LINE);
+            for byte in line {
+                defmt::write!(fmt, " {=u8:02X}", byte);
+            }
+        }
+    }
+}
@@ -0,0 +1,44 @@
+//!
+//! Little-endian field access.
+//!
+//! Every multi-byte field on the wire is little-endian. Marshalers read
+//! and write them through these helpers, so the byte order is decided in
+//! one place instead of at every `to_le_bytes` call.
+//!
+//! All helpers take the byte offset of the field and panic if it does
+//! not fit in the slice; callers check the payload length first.
+//!
+
+macro_rules! le_field {
+    ($ty:ty, $read:ident, $write:ident) => {
+        #[doc = concat!("Read a little-endian `", stringify!($ty), "` at offset `at`.")]
+        #[inline]
+        pub const fn $read(raw: &[u8], at: usize) -> $ty {
+            let mut bytes = [0u8; size_of::<$ty>()];
+            let mut i = 0;
+            while i < bytes.len() {
+                bytes[i] = raw[at + i];
+                i += 1;
+            }
+            <$ty>::from_le_bytes(bytes)
+        }
+
+        #[doc = concat!("Write a little-endian `", stringify!($ty), "` at offset `at`.")]
+        #[inline]
+        pub const fn $write(dst: &mut [u8], at: usize, value: $ty) {
+            let bytes = value.to_le_bytes();
+            let mut i = 0;
+            while i < bytes.len() {
+                dst[at + i] = bytes[i];
+                i += 1;
+            }
+        }
+    };
+}
+
+le_field!(u16, read_u16_le, write_u16_le);
+le_field!(i16, read_i16_le, write_i16_le);
+le_field!(u32, read_u32_le, write_u32_le);
+le_field!(i32, read_i32_le, write_i32_le);
+le_field!(u64, read_u64_le, write_u64_le);
+le_field!(f32, read_f32_le, write_f32_le);
@@ -13,6 +13,10 @@
 //! - **`DjiValidator`**
 //!   A concrete validator using DJI-compatible CRC8 and CRC16.
 //!
+//! - **`PolyValidator`**
+//!   A validator with the CRC polynomials and initial values as
+//!   const parameters, for protocol variants.
+//!
 //! - **`Marshaler`**
 //!   Describes how a typed payload is serialized into bytes and
 //!   deserialized from raw payload data.
@@ -21,9 +25,47 @@
 //!   Implements frame packing and unpacking, combining framing,
 //!   validation, and payload marshaling.
 //!
+//! - **`#[derive(Marshaler)]`**
+//!   Generates `Marshaler` for fixed-layout structs, with the
+//!   `derive` feature.
+//!
+//! - **`MarshalerRef`**
+//!   An optional borrowing variant of `Marshaler` for zero-copy payloads.
+//!
+//! - **`FrameWriter`**
+//!   Packs several messages back to back into one buffer.
+//!
+//! - **`FrameDecoder`**
+//!   Iterates over the valid frames in a buffer, skipping garbage.
+//!
+//! - **`FrameReader`**
+//!   Decodes frames from an asynchronous `ByteSource`, through the
+//!   `AsyncFrameSource` trait.
+//!
 //! - **`RawFrame`**
 //!   A validated, zero-copy view of a decoded frame.
 //!
+//! - **`can_split` / `CanReassembler`**
+//!   Tunnel packed frames over 8-byte CAN frames.
+//!
+//! - **`Raw`**
+//!   A fixed-size byte payload, for forwarding opaque data.
+//!
+//! - **`SeqTracker`**
+//!   Detects lost frames from gaps in the sequence number.
+//!
+//! - **`dispatch!`**
+//!   Generates a message enum and the decoder of `RawFrame`s into it.
+//!
+//! - **`bitflags_accessors!`**
+//!   Generates `bool` accessors for the bits of a bitmap field.
+//!
+//! - **`le`**
+//!   Little-endian field readers and writers for marshalers.
+//!
+//! - **`HexDump`**
+//!   Formats raw bytes as grouped hex lines for logging.
+//!
 //! # Typical Usage
 //!
 //! 1. Implement `Marshaler` for your message types
@@ -46,17 +88,59 @@
 //!
 #![cfg_attr(not(test), no_std)]
 
+/// Re-exports of `Heapless` Crate
+pub use heapless;
+
 pub use crc8_dji::calculate as calc_dji8;
 pub use crc16_dji::calculate as calc_dji16;
+#[cfg(feature = "record")]
+pub use decoder::Recorder;
+pub use decoder::{FrameDecoder, accumulate};
 pub use error::{Error, Result};
-pub use frame::{DjiValidator, Marshaler, RawFrame, Validator};
-pub use msger::Messager;
+pub use frame::{
+    DjiValidator, Marshaler, MarshalerRef, OwnedFrame, Raw, RawFrame, RawPayload, Validator,
+    assert_unique_ids,
+};
+pub use hexdump::HexDump;
+pub use msger::{Messager, NeedResult};
+pub use poly::PolyValidator;
+pub use seq::SeqTracker;
+pub use source::{AsyncFrameSource, ByteSource, FrameReader};
+pub use tunnel::{CanFrame, CanReassembler, TUNNEL_MAX, can_split};
+pub use writer::FrameWriter;
+
+#[cfg(feature = "derive")]
+pub use dji_frame_derive::Marshaler;
+
+///
+/// Trace framing events, enabled by the `log-frame` feature.
+///
+/// Expands to nothing otherwise, so the arguments are not evaluated.
+///
+macro_rules! trace_frame {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "log-frame")]
+        ::defmt::trace!($($arg)*);
+    };
+}
+
+/// Little-endian field helpers for marshalers
+pub mod le;
 
+mod bits;
 mod crc16_dji;
 mod crc8_dji;
+mod decoder;
+mod dispatch;
 mod error;
 mod frame;
+mod hexdump;
 mod msger;
+mod poly;
+mod seq;
+mod source;
+mod tunnel;
+mod writer;
 
 mod private {
     pub use super::*;
@@ -66,7 +150,7 @@ mod private {
     pub use ::defmt::{debug, error, info, trace, warn};
 
     pub use core::error::Error as StdError;
-    pub use core::fmt::{Display, Formatter, Result as FmtResult};
+    pub use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
     pub use core::marker::PhantomData;
     pub use core::result::Result as StdResult;
 }
@@ -24,14 +24,100 @@
 use crate::private::*;
 
 /// Start of Frame Byte
-const SOF: u8 = 0xA5;
+pub(crate) const SOF: u8 = 0xA5;
 
-/// Size of the frame header (SOF + length + sequence + CRC8).
-const HEAD_SIZE: usize = 5;
-/// Size of the command ID field.
-const CMDID_SIZE: usize = 2;
-/// Size of the tail CRC field.
-const TAIL_SIZE: usize = 2;
+///
+/// Reject field sizes the framing logic cannot handle.
+///
+/// Referenced from every entry point taking a `Validator`, so an
+/// invalid layout fails the build instead of corrupting frames.
+///
+struct Layout<V>(PhantomData<V>);
+
+impl<V: Validator> Layout<V> {
+    const CHECK: () = {
+        assert!(
+            V::HEADER_LEN >= 5,
+            "Header must hold SOF, LEN, SEQ and CRC8"
+        );
+        assert!(
+            V::CMDID_LEN >= 1 && V::CMDID_LEN <= 2,
+            "Command ID must be 1 or 2 bytes"
+        );
+        assert!(
+            V::TAIL_LEN >= 1 && V::TAIL_LEN <= 4,
+            "Tail must be 1 to 4 bytes"
+        );
+    };
+}
+
+/// Keep the low `len` bytes of a tail check value.
+const fn tail_mask(value: u32, len: usize) -> u32 {
+    match len {
+        4.. => value,
+        _ => value & ((1 << (8 * len)) - 1),
+    }
+}
+
+///
+/// Write header, command ID and tail around a payload of `size`
+/// bytes already placed at `V::HEADER_LEN + V::CMDID_LEN`.
+///
+/// The caller must have checked that `dst` holds the whole frame.
+///
+pub(crate) fn write_frame<V: Validator>(
+    cmd_id: u16,
+    sequence: u8,
+    size: usize,
+    dst: &mut [u8],
+) -> Result<usize> {
+    let () = Layout::<V>::CHECK;
+
+    let header = V::HEADER_LEN;
+    let payload_offset = header + V::CMDID_LEN;
+
+    // Reject command IDs wider than the field.
+    let cmd_bytes = cmd_id.to_le_bytes();
+    if cmd_bytes[V::CMDID_LEN..].iter().any(|&x| x != 0) {
+        return Err(Error::EncodeError { inner: header });
+    }
+
+    // Write frame header, zeroing reserved bytes.
+    let size_bytes = (size as u16).to_le_bytes();
+    dst[0] = SOF;
+    dst[1] = size_bytes[0];
+    dst[2] = size_bytes[1];
+    dst[3] = sequence;
+    dst[4..header - 1].fill(0);
+    dst[header - 1] = V::calculate_crc8(&dst[..header - 1]);
+
+    // Write command ID.
+    dst[header..payload_offset].copy_from_slice(&cmd_bytes[..V::CMDID_LEN]);
+
+    // Write frame tail.
+    let cursor = payload_offset + size;
+    let tail = V::calculate_tail(&dst[..cursor]).to_le_bytes();
+    dst[cursor..cursor + V::TAIL_LEN].copy_from_slice(&tail[..V::TAIL_LEN]);
+
+    Ok(cursor + V::TAIL_LEN)
+}
+
+///
+/// Result of `Messager::needed` on a possibly partial buffer.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum NeedResult {
+    /// A whole frame is buffered and `unpack` can be called.
+    Complete,
+    /// At least this many more bytes are required.
+    ///
+    /// Before the header is complete this only covers the header;
+    /// once the header validates it covers the whole frame.
+    Need(usize),
+    /// Drop this many leading bytes before retrying.
+    Resync(usize),
+}
 
 ///
 /// Frame encoder and decoder.
@@ -55,8 +141,9 @@ const TAIL_SIZE: usize = 2;
 /// +--------+--------+--------+--------+--------+---------+--------+
 /// ```
 ///
-#[derive(Debug)]
-#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+/// The sizes shown are `DjiValidator`'s; other validators may
+/// declare their own `HEADER_LEN`, `CMDID_LEN` and `TAIL_LEN`.
+///
 pub struct Messager<V: Validator> {
     /// Current frame sequence number.
     sequence: u8,
@@ -64,9 +151,31 @@ pub struct Messager<V: Validator> {
     _marker: PhantomData<V>,
 }
 
+impl<V: Validator> Debug for Messager<V> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("Messager")
+            .field("seq", &self.sequence)
+            .field("validator", &V::NAME)
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<V: Validator> defmt::Format for Messager<V> {
+    fn format(&self, fmt: defmt::Formatter) {
+        defmt::write!(
+            fmt,
+            "Messager {{ seq: {}, validator: {=str} }}",
+            self.sequence,
+            V::NAME
+        );
+    }
+}
+
 impl<V: Validator> Messager<V> {
     /// Create a new `Messager` with the given initial sequence number.
     pub const fn new(seq: u8) -> Self {
+        let () = Layout::<V>::CHECK;
         Self {
             sequence: seq,
             _marker: PhantomData,
@@ -97,10 +206,33 @@ impl<V: Validator> Messager<V> {
     /// - Payload marshaling fails
     ///
     pub fn pack<M: Marshaler>(&mut self, msg: &M, dst: &mut [u8]) -> Result<usize> {
-        let mut cursor: usize = 0;
+        let size = self.pack_with_seq(msg, self.sequence, dst)?;
+
+        // Advance sequence number.
+        self.sequence = self.sequence.wrapping_add(1);
+
+        Ok(size)
+    }
 
+    ///
+    /// Pack a message with an explicit sequence number.
+    ///
+    /// Same as `pack`, but the frame carries `sequence` and the
+    /// internal counter is left untouched, so several `Messager`s
+    /// can share one external sequence source.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `pack`.
+    ///
+    pub fn pack_with_seq<M: Marshaler>(
+        &self,
+        msg: &M,
+        sequence: u8,
+        dst: &mut [u8],
+    ) -> Result<usize> {
         // Ensure space for header and command ID.
-        let payload_offset = HEAD_SIZE + CMDID_SIZE;
+        let payload_offset = V::HEADER_LEN + V::CMDID_LEN;
         if dst.len() < payload_offset {
             return Err(Error::BufferTooSmall {
                 need: payload_offset,
@@ -108,7 +240,7 @@ impl<V: Validator> Messager<V> {
         }
 
         // Serialize payload directly into the destination buffer.
-        let size = msg.marshal(&mut dst[payload_offset..])?;
+        let size = msg.marshal_checked(&mut dst[payload_offset..])?;
 
         // Validate payload length.
         if size > u16::MAX as usize {
@@ -118,57 +250,50 @@ impl<V: Validator> Messager<V> {
         }
 
         // Ensure space for the entire frame.
-        let total = HEAD_SIZE + CMDID_SIZE + size + TAIL_SIZE;
+        let total = payload_offset + size + V::TAIL_LEN;
         if dst.len() < total {
             return Err(Error::BufferTooSmall {
                 need: total - dst.len(),
             });
         }
 
-        // Prepare Header
+        // Frame the payload (already written).
         let cmd_id = M::CMD_ID;
-        let sequence = self.sequence;
-
-        // Build frame header.
-        let header = {
-            let mut temp = [0; 5];
-            let size_bytes = (size as u16).to_le_bytes();
-            temp[0] = SOF;
-            temp[1] = size_bytes[0];
-            temp[2] = size_bytes[1];
-            temp[3] = sequence;
-            temp[4] = V::calculate_crc8(&temp[..4]);
-            temp
-        };
-
-        // Write header.
-        dst[cursor..cursor + HEAD_SIZE].copy_from_slice(&header);
-        cursor += HEAD_SIZE;
-
-        // Write command ID.
-        dst[cursor..cursor + CMDID_SIZE].copy_from_slice(&cmd_id.to_le_bytes());
-        cursor += CMDID_SIZE;
-
-        // Skip over payload (already written).
-        cursor += size;
-
-        // Write frame CRC.
-        let crc = V::calculate_crc16(&dst[..cursor]);
-        dst[cursor..cursor + TAIL_SIZE].copy_from_slice(&crc.to_le_bytes());
-        cursor += TAIL_SIZE;
+        let cursor = write_frame::<V>(cmd_id, sequence, size, dst)?;
 
-        // Advance sequence number.
-        self.sequence = self.sequence.wrapping_add(1);
-
-        #[cfg(feature = "log")]
-        trace!(
+        trace_frame!(
             "Packed Frame: {{ CMD: {}, SEQ: {}, LEN: {} }}",
-            cmd_id, sequence, cursor
+            cmd_id,
+            sequence,
+            cursor
         );
 
         Ok(cursor)
     }
 
+    ///
+    /// Pack a message into an owned, stack-allocated frame buffer.
+    ///
+    /// The returned `Vec` holds exactly the written frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `pack`, including
+    /// `BufferTooSmall` if `N` cannot hold the frame.
+    ///
+    pub fn pack_vec<M: Marshaler, const N: usize>(
+        &mut self,
+        msg: &M,
+    ) -> Result<heapless::Vec<u8, N>> {
+        let mut vec = heapless::Vec::new();
+        // Cannot fail: the length equals the capacity.
+        let _ = vec.resize_default(N);
+
+        let size = self.pack(msg, &mut vec)?;
+        vec.truncate(size);
+        Ok(vec)
+    }
+
     ///
     /// Unpack a binary frame from raw bytes.
     ///
@@ -193,8 +318,6 @@ impl<V: Validator> Messager<V> {
     /// - CRC validation fails
     ///
     pub fn unpack<'t>(&self, src: &'t [u8]) -> Result<(RawFrame<'t>, usize)> {
-        let mut cursor = 0;
-
         // Locate start-of-frame.
         if !src.starts_with(&[SOF]) {
             if let Some(start) = src.iter().position(|&x| SOF == x) {
@@ -205,16 +328,15 @@ impl<V: Validator> Messager<V> {
         }
 
         // Read header.
-        let Some(header) = src.get(cursor..cursor + HEAD_SIZE) else {
+        let Some(header) = src.get(..V::HEADER_LEN) else {
             return Err(Error::UnexpectedEnd { read: src.len() });
         };
-        cursor += HEAD_SIZE;
 
         // Validate header and extract metadata.
         let (length, sequence) = {
-            let (raw, crc) = (&header[..4], header[4]);
-            if V::calculate_crc8(raw) != crc {
-                return Err(Error::InvalidChecksum { at: cursor });
+            let (raw, crc) = header.split_at(V::HEADER_LEN - 1);
+            if V::calculate_crc8(raw) != crc[0] {
+                return Err(Error::InvalidChecksum { at: V::HEADER_LEN });
             }
 
             let length = u16::from_le_bytes([raw[1], raw[2]]);
@@ -222,45 +344,45 @@ impl<V: Validator> Messager<V> {
             (length as usize, sequence)
         };
 
-        // Read command ID.
-        let Some(cmd) = src.get(cursor..cursor + CMDID_SIZE) else {
+        // Bound the whole frame once; every slice below is within it.
+        let payload_offset = V::HEADER_LEN + V::CMDID_LEN;
+        let tail_offset = payload_offset + length;
+        let cursor = tail_offset + V::TAIL_LEN;
+        let Some(frame) = src.get(..cursor) else {
             return Err(Error::UnexpectedEnd { read: src.len() });
         };
-        cursor += CMDID_SIZE;
 
-        // Read payload.
-        let Some(payload) = src.get(cursor..cursor + length) else {
-            return Err(Error::UnexpectedEnd { read: src.len() });
-        };
-        cursor += length;
-
-        // Get the raw data for CRC calculation
-        // Safety: `cursor` is within bounds due to previous checks
-        let raw = src.get(..cursor).unwrap();
-
-        // Read and validate tail CRC.
-        let Some(tail) = src.get(cursor..cursor + TAIL_SIZE) else {
-            return Err(Error::UnexpectedEnd { read: src.len() });
-        };
-        cursor += TAIL_SIZE;
+        let (raw, tail) = frame.split_at(tail_offset);
+        let cmd = &raw[V::HEADER_LEN..payload_offset];
+        let payload = &raw[payload_offset..];
 
         {
-            // Safety: `tail` has a Fixed Length of 2
-            let crc = u16::from_le_bytes([tail[0], tail[1]]);
+            // `tail` holds `TAIL_LEN` bytes, at most 4.
+            let mut bytes = [0u8; 4];
+            bytes[..tail.len()].copy_from_slice(tail);
+            let crc = u32::from_le_bytes(bytes);
 
             // Validate CRC
-            if V::calculate_crc16(raw) != crc {
-                return Err(Error::InvalidChecksum { at: cursor });
+            let computed = tail_mask(V::calculate_tail(raw), V::TAIL_LEN);
+            if computed != crc {
+                return Err(Error::ChecksumMismatch {
+                    at: cursor,
+                    computed,
+                    received: crc,
+                });
             }
         }
 
         // Parse Cmd ID
-        let cmd_id = u16::from_le_bytes([cmd[0], cmd[1]]);
+        let mut bytes = [0u8; 2];
+        bytes[..cmd.len()].copy_from_slice(cmd);
+        let cmd_id = u16::from_le_bytes(bytes);
 
-        #[cfg(feature = "log")]
-        trace!(
+        trace_frame!(
             "Unpacked Frame: {{ CMD: {}, SEQ: {}, LEN: {} }}",
-            cmd_id, sequence, cursor
+            cmd_id,
+            sequence,
+            cursor
         );
 
         // Construct Payload
@@ -273,4 +395,57 @@ impl<V: Validator> Messager<V> {
             cursor,
         ))
     }
+
+    ///
+    /// Unpack a binary frame, copying its payload.
+    ///
+    /// Same as `unpack`, but the returned `OwnedFrame` does not
+    /// borrow `src`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `unpack`, or `BufferTooSmall`
+    /// if the payload exceeds `P` bytes.
+    ///
+    pub fn unpack_owned<const P: usize>(&self, src: &[u8]) -> Result<(OwnedFrame<P>, usize)> {
+        let (frame, size) = self.unpack(src)?;
+        Ok((OwnedFrame::try_from(&frame)?, size))
+    }
+
+    ///
+    /// Report how many more bytes the frame at the start of `src` needs.
+    ///
+    /// This only inspects the header, so it is cheap to call on every
+    /// partial read. The frame length is trusted once the header CRC8
+    /// validates; the body CRC16 is left to `unpack`.
+    ///
+    pub fn needed(&self, src: &[u8]) -> NeedResult {
+        // Locate start-of-frame.
+        if !src.starts_with(&[SOF]) {
+            return match src.iter().position(|&x| SOF == x) {
+                Some(start) => NeedResult::Resync(start),
+                None if src.is_empty() => NeedResult::Need(V::HEADER_LEN),
+                None => NeedResult::Resync(src.len()),
+            };
+        }
+
+        // Wait for the full header.
+        let Some(header) = src.get(..V::HEADER_LEN) else {
+            return NeedResult::Need(V::HEADER_LEN - src.len());
+        };
+
+        // A bad header is treated as a stray SOF.
+        let (raw, crc) = header.split_at(V::HEADER_LEN - 1);
+        if V::calculate_crc8(raw) != crc[0] {
+            return NeedResult::Resync(1);
+        }
+
+        let length = u16::from_le_bytes([raw[1], raw[2]]) as usize;
+        let total = V::HEADER_LEN + V::CMDID_LEN + length + V::TAIL_LEN;
+
+        match total.checked_sub(src.len()) {
+            Some(0) | None => NeedResult::Complete,
+            Some(need) => NeedResult::Need(need),
+        }
+    }
 }
@@ -0,0 +1,89 @@
+//!
+//! Parameterized CRC validator.
+//!
+//! This module provides `PolyValidator`, a `Validator` whose CRC8
+//! and CRC16 polynomials and initial values are const parameters.
+//! Lookup tables are built at compile time for each configuration.
+//!
+
+use crate::private::*;
+
+///
+/// CRC validator with configurable polynomials.
+///
+/// Both checksums use the reflected (LSB-first) algorithm without a
+/// final XOR, as the DJI protocol does. Polynomials are given in
+/// reflected form, so DJI's configuration is:
+///
+/// ```
+/// use dji_frame::{PolyValidator, Validator};
+///
+/// type Dji = PolyValidator<0x8C, 0xFF, 0x8408, 0xFFFF>;
+/// assert_eq!(Dji::calculate_crc16(b"123456789"), 0x6F91);
+/// ```
+///
+pub struct PolyValidator<const POLY8: u8, const INIT8: u8, const POLY16: u16, const INIT16: u16>;
+
+impl<const POLY8: u8, const INIT8: u8, const POLY16: u16, const INIT16: u16>
+    PolyValidator<POLY8, INIT8, POLY16, INIT16>
+{
+    /// CRC8 lookup table for `POLY8`.
+    const TABLE8: [u8; 256] = {
+        let mut table = [0u8; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u8;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY8
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+
+    /// CRC16 lookup table for `POLY16`.
+    const TABLE16: [u16; 256] = {
+        let mut table = [0u16; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u16;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ POLY16
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    };
+}
+
+impl<const POLY8: u8, const INIT8: u8, const POLY16: u16, const INIT16: u16> Validator
+    for PolyValidator<POLY8, INIT8, POLY16, INIT16>
+{
+    const NAME: &'static str = "Poly";
+
+    fn calculate_crc8(raw: &[u8]) -> u8 {
+        raw.iter()
+            .fold(INIT8, |crc, &byte| Self::TABLE8[(crc ^ byte) as usize])
+    }
+
+    fn calculate_crc16(raw: &[u8]) -> u16 {
+        raw.iter().fold(INIT16, |crc, &byte| {
+            let idx = ((crc ^ (byte as u16)) & 0xff) as usize;
+            (crc >> 8) ^ Self::TABLE16[idx]
+        })
+    }
+}
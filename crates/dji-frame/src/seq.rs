@@ -0,0 +1,64 @@
+//!
+//! Sequence gap detection.
+//!
+//! Every frame carries a sequence byte that the sender increments
+//! by one per frame. This module provides `SeqTracker`, which follows
+//! that byte across frames and counts the ones that never arrived.
+//!
+
+///
+/// Tracker of lost frames based on the sequence number.
+///
+/// The sequence is a `u8` that wraps from 255 to 0, which is not
+/// counted as a gap. A repeated sequence number is ignored.
+///
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SeqTracker {
+    /// Sequence number of the previous frame.
+    last: Option<u8>,
+    /// Frames missing before the most recent frame.
+    last_gap: u8,
+    /// Frames missing since creation.
+    lost_count: u32,
+}
+
+impl SeqTracker {
+    /// Create a new `SeqTracker` that has not seen any frame.
+    pub const fn new() -> Self {
+        Self {
+            last: None,
+            last_gap: 0,
+            lost_count: 0,
+        }
+    }
+
+    ///
+    /// Record the sequence number of a received frame.
+    ///
+    /// Returns the number of frames missing right before it.
+    ///
+    pub fn update(&mut self, sequence: u8) -> u8 {
+        let gap = match self.last {
+            Some(last) if last != sequence => sequence.wrapping_sub(last).wrapping_sub(1),
+            _ => 0,
+        };
+
+        self.last = Some(sequence);
+        self.last_gap = gap;
+        self.lost_count = self.lost_count.saturating_add(gap as u32);
+        gap
+    }
+
+    /// Get the number of frames missing before the most recent frame.
+    #[inline]
+    pub const fn last_gap(&self) -> u8 {
+        self.last_gap
+    }
+
+    /// Get the total number of frames missing so far.
+    #[inline]
+    pub const fn lost_count(&self) -> u32 {
+        self.lost_count
+    }
+}
@@ -0,0 +1,167 @@
+//!
+//! Asynchronous frame sources.
+//!
+//! This module decouples frame decoding from the transport it runs on.
+//! A `ByteSource` is anything that yields bytes asynchronously, such as
+//! a UART, a CAN reassembler, a USB endpoint, or an in-memory buffer.
+//! `FrameReader` buffers those bytes and implements `AsyncFrameSource`,
+//! yielding one validated frame per call.
+//!
+//! The traits only rely on `async fn`, so they work with any executor,
+//! including `embassy`.
+//!
+
+use crate::private::*;
+
+///
+/// Asynchronous source of raw bytes.
+///
+#[allow(async_fn_in_trait)]
+pub trait ByteSource {
+    ///
+    /// Read some bytes into `buf`.
+    ///
+    /// Returns the number of bytes read; `0` means the
+    /// source is exhausted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the underlying transport fails.
+    ///
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+}
+
+impl ByteSource for &[u8] {
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let size = buf.len().min(self.len());
+        let (head, tail) = self.split_at(size);
+        buf[..size].copy_from_slice(head);
+        *self = tail;
+        Ok(size)
+    }
+}
+
+///
+/// Asynchronous source of validated frames.
+///
+#[allow(async_fn_in_trait)]
+pub trait AsyncFrameSource {
+    ///
+    /// Wait for the next valid frame.
+    ///
+    /// The returned frame borrows the source until it is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UnexpectedEnd` once the byte source is exhausted,
+    /// or the error of the underlying `ByteSource`.
+    ///
+    async fn next_frame<'t>(&'t mut self) -> Result<RawFrame<'t>>;
+}
+
+///
+/// Frame decoder over a `ByteSource`.
+///
+/// Bytes are collected in an `N`-byte buffer until a whole frame is
+/// available. Garbage and corrupted frames are skipped, like in
+/// `FrameDecoder`; a frame larger than the buffer is dropped.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut reader = FrameReader::<_, DjiValidator, 256>::new(uart);
+/// loop {
+///     let frame = reader.next_frame().await?;
+///     handle(frame);
+/// }
+/// ```
+///
+pub struct FrameReader<S: ByteSource, V: Validator, const N: usize> {
+    /// Underlying byte source.
+    source: S,
+    /// Messager used to validate frames.
+    msger: Messager<V>,
+    /// Bytes read but not yet consumed.
+    buf: heapless::Vec<u8, N>,
+    /// Size of the frame returned last, dropped on the next call.
+    consumed: usize,
+}
+
+impl<S: ByteSource, V: Validator, const N: usize> FrameReader<S, V, N> {
+    /// Create a new `FrameReader` over `source`.
+    pub const fn new(source: S) -> Self {
+        Self {
+            source,
+            msger: Messager::new(0),
+            buf: heapless::Vec::new(),
+            consumed: 0,
+        }
+    }
+
+    /// Get the bytes buffered but not decoded yet.
+    #[inline]
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf[self.consumed..]
+    }
+
+    /// Release the underlying byte source.
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    /// Read more bytes into the free space of the buffer.
+    async fn fill(&mut self) -> Result<()> {
+        let len = self.buf.len();
+        // Cannot fail: the length equals the capacity.
+        let _ = self.buf.resize_default(N);
+
+        let read = self.source.read(&mut self.buf[len..]).await;
+        let size = read.as_ref().map_or(0, |&size| size);
+        self.buf.truncate(len + size);
+
+        match read? {
+            0 => Err(Error::UnexpectedEnd { read: len }),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<S: ByteSource, V: Validator, const N: usize> AsyncFrameSource for FrameReader<S, V, N> {
+    async fn next_frame<'t>(&'t mut self) -> Result<RawFrame<'t>> {
+        self.buf.drain(..self.consumed);
+        self.consumed = 0;
+
+        // Locate the frame as owned data first, so the buffer can
+        // still be modified while searching.
+        let (cmd_id, sequence, payload, size) = loop {
+            let skip = match self.msger.needed(&self.buf) {
+                NeedResult::Complete => match self.msger.unpack(&self.buf) {
+                    Ok((frame, size)) => {
+                        let start = V::HEADER_LEN + V::CMDID_LEN;
+                        let payload = start..start + frame.payload.len();
+                        break (frame.cmd_id, frame.sequence, payload, size);
+                    }
+                    Err(e) => e.skip().max(1),
+                },
+
+                // Too large to ever fit, step over its SOF.
+                NeedResult::Need(_) if self.buf.is_full() => 1,
+                NeedResult::Need(_) => {
+                    self.fill().await?;
+                    continue;
+                }
+
+                NeedResult::Resync(skip) => skip,
+            };
+
+            self.buf.drain(..skip);
+        };
+
+        self.consumed = size;
+        Ok(RawFrame {
+            cmd_id,
+            sequence,
+            payload: &self.buf[payload],
+        })
+    }
+}
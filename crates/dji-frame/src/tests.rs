@@ -12,6 +12,11 @@ fn test_dji_crc16() {
     assert_eq!(calc_dji16(data), 0x6F91);
 }
 
+#[test]
+fn test_self_test() {
+    assert!(DjiValidator::self_test());
+}
+
 struct TestCase<const N: usize> {
     payload: [u8; N],
 }
@@ -153,5 +158,913 @@ fn test_invalid_tail_checksum() {
     ];
     let msger: Messager<DjiValidator> = Messager::new(0x56);
     let result = msger.unpack(&invalid_data);
-    assert!(matches!(result, Err(Error::InvalidChecksum { at: 14 })));
+    assert!(matches!(
+        result,
+        Err(Error::ChecksumMismatch {
+            at: 14,
+            computed: 0x7184,
+            received: 0x0000,
+        })
+    ));
+}
+
+#[test]
+fn test_stream_crc_recovery() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let frames = [
+        TestCase::new([1, 2, 3, 4, 5]),
+        TestCase::new([6, 7, 8, 9, 10]),
+        TestCase::new([11, 12, 13, 14, 15]),
+    ];
+
+    let mut buffer = [0u8; 64];
+    let mut len = 0;
+    for frame in &frames {
+        len += msger.pack(frame, &mut buffer[len..]).unwrap();
+    }
+
+    // Flip one data byte in the middle frame.
+    let frame_size = len / frames.len();
+    buffer[frame_size + 9] ^= 0x10;
+
+    let mut decoded = Vec::new();
+    let mut errors = Vec::new();
+    let mut rest = &buffer[..len];
+
+    while !rest.is_empty() {
+        match msger.unpack(rest) {
+            Ok((raw, size)) => {
+                decoded.push(TestCase::<5>::unmarshal(raw.payload()).unwrap().payload);
+                rest = &rest[size..];
+            }
+
+            Err(e) => {
+                let skip = e.skip();
+                errors.push(e);
+                if skip == 0 {
+                    break;
+                }
+                rest = &rest[skip..];
+            }
+        }
+    }
+
+    assert_eq!(decoded, [frames[0].payload, frames[2].payload]);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        Error::ChecksumMismatch { at, .. } if at == frame_size
+    ));
+}
+
+#[test]
+fn test_borrowed_payload() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let test = TestCase::new([0x11; 112]);
+    let mut buffer = [0u8; 128];
+    let size = msger.pack(&test, &mut buffer).unwrap();
+
+    let (raw, _) = msger.unpack(&buffer[..size]).unwrap();
+    let view = RawPayload::unmarshal_ref(raw.payload()).unwrap();
+
+    assert_eq!(view.bytes(), &test.payload);
+    assert_eq!(view.bytes().as_ptr(), buffer[7..].as_ptr());
+    assert_eq!(
+        RawPayload::from(&raw).bytes().as_ptr(),
+        view.bytes().as_ptr()
+    );
+}
+
+#[test]
+fn test_pack_repeated() {
+    let test = TestCase::new([1, 2, 3, 4, 5]);
+    let mut buffer = [0u8; 64];
+    let mut msger: Messager<DjiValidator> = Messager::new(0x56);
+
+    let expected = {
+        let size = msger.pack(&test, &mut buffer).unwrap();
+        buffer[..size].to_vec()
+    };
+
+    const ROUNDS: u32 = 10_000;
+    for _ in 0..ROUNDS {
+        let size = msger.pack(&test, &mut buffer).unwrap();
+        assert_eq!(
+            &buffer[DjiValidator::HEADER_LEN..size - 2],
+            &expected[DjiValidator::HEADER_LEN..expected.len() - 2]
+        );
+    }
+
+    // A fresh messager with the same sequence reproduces the frame exactly.
+    msger = Messager::new(0x56);
+    let size = msger.pack(&test, &mut buffer).unwrap();
+    assert_eq!(&buffer[..5], &[0xA5, 0x5, 0x0, 0x56, 0xF0]);
+    assert_eq!(&buffer[..size], &expected[..]);
+}
+
+#[test]
+fn test_needed() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0x56);
+
+    let test = TestCase::new([1, 2, 3, 4, 5]);
+    let mut buffer = [0u8; 64];
+    let size = msger.pack(&test, &mut buffer).unwrap();
+
+    // Truncated inside the header
+    assert_eq!(msger.needed(&[]), NeedResult::Need(5));
+    assert_eq!(msger.needed(&buffer[..2]), NeedResult::Need(3));
+
+    // Header only, the rest of the frame is known
+    assert_eq!(msger.needed(&buffer[..5]), NeedResult::Need(size - 5));
+    assert_eq!(msger.needed(&buffer[..size - 1]), NeedResult::Need(1));
+
+    assert_eq!(msger.needed(&buffer[..size]), NeedResult::Complete);
+    assert_eq!(msger.needed(&buffer[..size + 3]), NeedResult::Complete);
+}
+
+#[test]
+fn test_needed_resync() {
+    let msger: Messager<DjiValidator> = Messager::new(0);
+
+    assert_eq!(msger.needed(&[0x00, 0x01, 0xA5]), NeedResult::Resync(2));
+    assert_eq!(msger.needed(&[0x00, 0x01, 0x02]), NeedResult::Resync(3));
+
+    // Invalid header CRC8
+    let invalid = [0xA5, 0x5, 0xFF, 0x56, 0xF0];
+    assert_eq!(msger.needed(&invalid), NeedResult::Resync(1));
+}
+
+#[test]
+fn test_frame_writer() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0x10);
+    let mut buffer = [0u8; 64];
+
+    let frames = [
+        TestCase::new([1, 2, 3]),
+        TestCase::new([4, 5, 6]),
+        TestCase::new([7, 8, 9]),
+    ];
+
+    let mut writer = FrameWriter::new(&mut msger, &mut buffer);
+    assert!(writer.is_empty());
+    for frame in &frames {
+        writer.write(frame).unwrap();
+    }
+    assert_eq!(writer.len(), 3 * 12);
+
+    // Not enough room for a fourth frame
+    assert!(matches!(
+        writer.write(&TestCase::new([0; 24])),
+        Err(Error::BufferTooSmall { .. })
+    ));
+
+    let written = writer.finish();
+    assert_eq!(written.len(), 3 * 12);
+
+    let decoder: Messager<DjiValidator> = Messager::new(0);
+    let mut rest = written;
+    for (i, frame) in frames.iter().enumerate() {
+        let (raw, size) = decoder.unpack(rest).unwrap();
+        assert_eq!(raw.sequence(), 0x10 + i as u8);
+        assert_eq!(
+            TestCase::<3>::unmarshal(raw.payload()).unwrap().payload,
+            frame.payload
+        );
+        rest = &rest[size..];
+    }
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_frame_decoder() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut buffer = [0u8; 64];
+    let mut len = 0;
+    buffer[len..len + 3].copy_from_slice(&[0x00, 0xFF, 0x12]);
+    len += 3;
+    len += msger
+        .pack(&TestCase::new([1, 2, 3]), &mut buffer[len..])
+        .unwrap();
+    len += msger
+        .pack(&TestCase::new([4, 5, 6]), &mut buffer[len..])
+        .unwrap();
+
+    // Leave the last frame incomplete.
+    let partial = msger
+        .pack(&TestCase::new([7, 8, 9]), &mut buffer[len..])
+        .unwrap();
+    len += partial - 1;
+
+    let mut decoder = FrameDecoder::<DjiValidator>::new(&buffer[..len]);
+    let payloads: Vec<_> = (&mut decoder).map(|f| f.payload().to_vec()).collect();
+
+    assert_eq!(payloads, [[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(decoder.remaining().len(), partial - 1);
+}
+
+#[cfg(feature = "record")]
+#[test]
+fn test_frame_recorder() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut buffer = [0u8; 64];
+    let first = msger.pack(&TestCase::new([1, 2, 3]), &mut buffer).unwrap();
+    buffer[first] = 0x00; // garbage between frames
+    let second = msger
+        .pack(&TestCase::new([4, 5]), &mut buffer[first + 1..])
+        .unwrap();
+    let len = first + 1 + second;
+
+    let mut recorded: Vec<Vec<u8>> = Vec::new();
+    let mut recorder = |raw: &[u8]| recorded.push(raw.to_vec());
+
+    let decoded = FrameDecoder::<DjiValidator>::new(&buffer[..len])
+        .on_frame(&mut recorder)
+        .count();
+
+    assert_eq!(decoded, 2);
+    assert_eq!(recorded.len(), 2);
+    assert_eq!(recorded[0], &buffer[..first]);
+    assert_eq!(recorded[1], &buffer[first + 1..len]);
+}
+
+#[test]
+fn test_checksum_mismatch() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut buffer = [0u8; 16];
+    let len = msger.pack(&TestCase::new([1, 2, 3]), &mut buffer).unwrap();
+    let expected = calc_dji16(&buffer[..len - 2]) as u32;
+
+    // Corrupt the tail CRC only.
+    buffer[len - 1] ^= 0xFF;
+    let received = u16::from_le_bytes([buffer[len - 2], buffer[len - 1]]) as u32;
+
+    let err = msger.unpack(&buffer[..len]).unwrap_err();
+    assert!(matches!(
+        err,
+        Error::ChecksumMismatch { at, computed, received: r }
+            if at == len && computed == expected && r == received
+    ));
+    assert_eq!(err.skip(), len);
+}
+
+#[test]
+fn test_unpack_one_byte_short() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut buffer = [0u8; 16];
+    let len = msger.pack(&TestCase::new([1, 2, 3]), &mut buffer).unwrap();
+
+    let result = msger.unpack(&buffer[..len - 1]);
+    assert!(matches!(result, Err(Error::UnexpectedEnd { read }) if read == len - 1));
+    assert!(msger.unpack(&buffer[..len]).is_ok());
+}
+
+#[test]
+fn test_pack_vec() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let msg = TestCase::new([1, 2, 3, 4, 5]);
+
+    let frame: heapless::Vec<u8, 32> = msger.pack_vec(&msg).unwrap();
+    assert_eq!(frame.len(), 5 + 2 + 5 + 2);
+
+    let (raw, size) = msger.unpack(&frame).unwrap();
+    assert_eq!(size, frame.len());
+    assert_eq!(
+        TestCase::<5>::unmarshal(raw.payload()).unwrap().payload,
+        msg.payload
+    );
+
+    let small = msger.pack_vec::<_, 8>(&msg);
+    assert!(matches!(small, Err(Error::BufferTooSmall { .. })));
+}
+
+#[test]
+#[should_panic(expected = "Duplicate Command ID")]
+fn test_duplicate_cmd_id() {
+    assert_unique_ids(&[0x0001, 0x0002]);
+    assert_unique_ids(&[0x0001, 0x0002, 0x0001]);
+}
+
+#[test]
+fn test_messager_format() {
+    let mut msger: Messager<DjiValidator> = Messager::new(41);
+    msger.pack(&TestCase::new([0]), &mut [0u8; 16]).unwrap();
+
+    let text = format!("{msger:?}");
+    assert_eq!(text, r#"Messager { seq: 42, validator: "Dji" }"#);
+    assert!(!text.contains("PhantomData"));
+}
+
+#[test]
+fn test_hex_dump() {
+    let bytes = [0xA5, 0x05, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0xFF];
+    assert_eq!(
+        HexDump(&bytes).to_string(),
+        "0000: A5 05 00 01 02 03 04 05\n0008: 06 FF"
+    );
+    assert_eq!(HexDump(&[]).to_string(), "");
+}
+
+#[test]
+fn test_seq_tracker() {
+    let mut tracker = SeqTracker::new();
+    let gaps: Vec<_> = [3, 4, 6, 7].map(|seq| tracker.update(seq)).into();
+
+    assert_eq!(gaps, [0, 0, 1, 0]);
+    assert_eq!(tracker.last_gap(), 0);
+    assert_eq!(tracker.lost_count(), 1);
+
+    // Wrapping from 255 to 0 is not a gap.
+    let mut tracker = SeqTracker::new();
+    for seq in [254, 255, 0, 1] {
+        assert_eq!(tracker.update(seq), 0);
+    }
+    assert_eq!(tracker.update(3), 1);
+    assert_eq!(tracker.lost_count(), 1);
+}
+
+#[test]
+fn test_decoder_seq_tracker() {
+    let mut msger: Messager<DjiValidator> = Messager::new(3);
+
+    let mut buffer = [0u8; 64];
+    let mut lost = [0u8; 16];
+    let mut len = 0;
+    len += msger.pack(&TestCase::new([3]), &mut buffer[len..]).unwrap();
+    len += msger.pack(&TestCase::new([4]), &mut buffer[len..]).unwrap();
+    msger.pack(&TestCase::new([5]), &mut lost).unwrap();
+    len += msger.pack(&TestCase::new([6]), &mut buffer[len..]).unwrap();
+
+    let mut tracker = SeqTracker::new();
+    let decoded = FrameDecoder::<DjiValidator>::new(&buffer[..len])
+        .track(&mut tracker)
+        .count();
+
+    assert_eq!(decoded, 3);
+    assert_eq!(tracker.last_gap(), 1);
+    assert_eq!(tracker.lost_count(), 1);
+}
+
+#[test]
+fn test_raw_payload() {
+    type Forward = Raw<0x0302, 30>;
+    assert_eq!(Forward::CMD_ID, 0x0302);
+
+    let mut data = [0u8; 30];
+    data.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+    let raw = Forward::from_bytes(data);
+
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buffer = [0u8; 64];
+    let size = msger.pack(&raw, &mut buffer).unwrap();
+
+    let (frame, _) = msger.unpack(&buffer[..size]).unwrap();
+    assert_eq!(frame.cmd_id(), 0x0302);
+
+    let decoded = Forward::unmarshal(frame.payload()).unwrap();
+    assert_eq!(decoded.as_bytes(), &data);
+    assert_eq!(decoded, raw);
+
+    assert!(matches!(
+        Forward::unmarshal(&data[..29]),
+        Err(Error::InvalidDataLength { expected: 30 })
+    ));
+}
+
+#[test]
+fn test_accumulate_overflow() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut frame = [0u8; 32];
+    let size = msger
+        .pack(&TestCase::new([1, 2, 3, 4, 5, 6, 7, 8]), &mut frame)
+        .unwrap();
+    let frame = &frame[..size];
+
+    // Garbage, then the first half of a frame straddling the next read.
+    let mut buf = heapless::Vec::<u8, 32>::new();
+    assert_eq!(accumulate(&mut buf, &[0x11; 20]), 0);
+    assert_eq!(accumulate(&mut buf, &frame[..8]), 0);
+
+    // Only the garbage in front of the partial frame is dropped.
+    assert_eq!(accumulate(&mut buf, &frame[8..]), 20);
+    assert_eq!(&buf[..], frame);
+
+    let (raw, used) = msger.unpack(&buf).unwrap();
+    assert_eq!(used, size);
+    assert_eq!(raw.payload(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    // A chunk larger than the buffer keeps its tail.
+    assert_eq!(accumulate(&mut buf, &[0x22; 40]), size + 8);
+    assert_eq!(&buf[..], &[0x22; 32]);
+}
+
+#[test]
+fn test_poly_validator() {
+    type Dji = PolyValidator<0x8C, 0xFF, 0x8408, 0xFFFF>;
+
+    let data = b"123456789";
+    assert_eq!(
+        Dji::calculate_crc8(data),
+        DjiValidator::calculate_crc8(data)
+    );
+    assert_eq!(
+        Dji::calculate_crc16(data),
+        DjiValidator::calculate_crc16(data)
+    );
+
+    let mut msger: Messager<Dji> = Messager::new(0);
+    let mut buffer = [0u8; 32];
+    let size = msger.pack(&TestCase::new([7, 8, 9]), &mut buffer).unwrap();
+    assert!(
+        Messager::<DjiValidator>::new(0)
+            .unpack(&buffer[..size])
+            .is_ok()
+    );
+}
+
+#[test]
+fn test_error_display() {
+    let cases = [
+        (Error::BufferTooSmall { need: 11 }, "need 11 bytes"),
+        (Error::InputTooLarge { max: 12 }, "size of 12 bytes"),
+        (Error::UnexpectedEnd { read: 13 }, "offset 13"),
+        (Error::ReSync { skip: 14 }, "skipped 14 bytes"),
+        (Error::MissingHeader { skip: 15 }, "offset 15"),
+        (Error::InvalidChecksum { at: 16 }, "offset 16"),
+        (
+            Error::ChecksumMismatch {
+                at: 17,
+                computed: 0x1234,
+                received: 0xABCD,
+            },
+            "offset 17, computed 0x1234, received 0xABCD",
+        ),
+        (Error::DecodeError { at: 18 }, "offset 18"),
+        (Error::EncodeError { inner: 19 }, "encode message: 19"),
+        (
+            Error::InvalidDataLength { expected: 20 },
+            "expected 20 bytes",
+        ),
+    ];
+
+    for (err, msg) in cases {
+        let out = err.to_string();
+        assert!(out.contains(msg), "{out:?} does not contain {msg:?}");
+    }
+}
+
+#[test]
+fn test_reconstruct() {
+    let mut msger: Messager<DjiValidator> = Messager::new(42);
+    let mut buffer = [0u8; 32];
+    let size = msger
+        .pack(&TestCase::new([1, 2, 3, 4]), &mut buffer)
+        .unwrap();
+    let (frame, _) = msger.unpack(&buffer[..size]).unwrap();
+
+    let mut forward = [0u8; 32];
+    let len = frame.reconstruct::<DjiValidator>(&mut forward).unwrap();
+    assert_eq!(&forward[..len], &buffer[..size]);
+
+    let (copy, used) = msger.unpack(&forward[..len]).unwrap();
+    assert_eq!(used, len);
+    assert_eq!(copy, frame);
+
+    assert!(matches!(
+        frame.reconstruct::<DjiValidator>(&mut forward[..len - 1]),
+        Err(Error::BufferTooSmall { need }) if need == len
+    ));
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "Marshaled length mismatch")]
+fn test_marshaled_len_mismatch() {
+    struct ShortWrite;
+
+    impl Marshaler for ShortWrite {
+        const CMD_ID: u16 = 0x0042;
+        const MARSHALED_LEN: Option<usize> = Some(4);
+
+        fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
+            dst[..3].copy_from_slice(&[1, 2, 3]);
+            Ok(3)
+        }
+
+        fn unmarshal(_: &[u8]) -> Result<Self> {
+            Ok(Self)
+        }
+    }
+
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buffer = [0u8; 32];
+    let _ = msger.pack(&ShortWrite, &mut buffer);
+}
+
+#[test]
+fn test_zero_length_payload() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buffer = [0u8; 16];
+    let size = msger.pack(&TestCase::new([]), &mut buffer).unwrap();
+    assert_eq!(size, DjiValidator::HEADER_LEN + 2 + 2);
+    assert_eq!(&buffer[1..3], &[0, 0]);
+
+    let (raw, used) = msger.unpack(&buffer[..size]).unwrap();
+    assert_eq!(used, size);
+    assert_eq!(raw.cmd_id(), 0x1234);
+    assert!(raw.payload().is_empty());
+    assert!(TestCase::<0>::unmarshal(raw.payload()).is_ok());
+
+    // Still one byte short without the tail.
+    assert!(matches!(
+        msger.unpack(&buffer[..size - 1]),
+        Err(Error::UnexpectedEnd { .. })
+    ));
+}
+
+#[test]
+fn test_pack_with_seq() {
+    let msger: Messager<DjiValidator> = Messager::new(5);
+    let (mut a, mut b) = ([0u8; 16], [0u8; 16]);
+
+    let size_a = msger
+        .pack_with_seq(&TestCase::new([1]), 77, &mut a)
+        .unwrap();
+    let size_b = msger
+        .pack_with_seq(&TestCase::new([1]), 77, &mut b)
+        .unwrap();
+    assert_eq!(a[3], 77);
+    assert_eq!(a[..size_a], b[..size_b]);
+
+    let (raw, _) = msger.unpack(&a[..size_a]).unwrap();
+    assert_eq!(raw.sequence(), 77);
+
+    // The internal counter is untouched.
+    let mut msger = msger;
+    msger.pack(&TestCase::new([1]), &mut a).unwrap();
+    assert_eq!(a[3], 5);
+}
+
+/// Variant with a reserved header byte and a 4-byte FNV-1a tail.
+struct WideValidator;
+
+impl Validator for WideValidator {
+    const NAME: &'static str = "Wide";
+    const HEADER_LEN: usize = 6;
+    const TAIL_LEN: usize = 4;
+
+    fn calculate_crc8(raw: &[u8]) -> u8 {
+        calc_dji8(raw)
+    }
+
+    fn calculate_crc16(raw: &[u8]) -> u16 {
+        calc_dji16(raw)
+    }
+
+    fn calculate_tail(raw: &[u8]) -> u32 {
+        raw.iter().fold(0x811C_9DC5, |h, &b| {
+            (h ^ b as u32).wrapping_mul(0x0100_0193)
+        })
+    }
+}
+
+#[test]
+fn test_validator_layout() {
+    let mut msger: Messager<WideValidator> = Messager::new(7);
+
+    let mut buffer = [0u8; 32];
+    let size = msger.pack(&TestCase::new([1, 2, 3]), &mut buffer).unwrap();
+    assert_eq!(size, 6 + 2 + 3 + 4);
+    assert_eq!(buffer[4], 0);
+    assert_eq!(msger.needed(&buffer[..size - 1]), NeedResult::Need(1));
+
+    let (frame, used) = msger.unpack(&buffer[..size]).unwrap();
+    assert_eq!(used, size);
+    assert_eq!(frame.sequence(), 7);
+    assert_eq!(frame.payload(), &[1, 2, 3]);
+
+    let mut again = [0u8; 32];
+    assert_eq!(
+        frame.reconstruct::<WideValidator>(&mut again).unwrap(),
+        size
+    );
+    assert_eq!(again[..size], buffer[..size]);
+
+    // Every tail byte is checked.
+    buffer[size - 1] ^= 0x01;
+    assert!(matches!(
+        msger.unpack(&buffer[..size]),
+        Err(Error::ChecksumMismatch { at, .. }) if at == size
+    ));
+}
+
+/// Poll a future to completion, for sources that never pend.
+fn block_on<F: Future>(fut: F) -> F::Output {
+    let mut fut = core::pin::pin!(fut);
+    let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+    loop {
+        if let core::task::Poll::Ready(out) = fut.as_mut().poll(&mut cx) {
+            return out;
+        }
+    }
+}
+
+#[test]
+fn test_frame_reader() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut stream = [0u8; 64];
+    let mut len = 0;
+    stream[len] = 0x00; // Leading garbage
+    len += 1;
+    len += msger
+        .pack(&TestCase::new([1, 2, 3]), &mut stream[len..])
+        .unwrap();
+    len += msger
+        .pack(&TestCase::new([4, 5]), &mut stream[len..])
+        .unwrap();
+
+    // A small buffer forces several reads per frame.
+    let mut reader = FrameReader::<&[u8], DjiValidator, 16>::new(&stream[..len]);
+
+    let frame = block_on(reader.next_frame()).unwrap();
+    assert_eq!(frame.sequence(), 0);
+    assert_eq!(frame.payload(), &[1, 2, 3]);
+
+    let frame = block_on(reader.next_frame()).unwrap();
+    assert_eq!(frame.sequence(), 1);
+    assert_eq!(frame.payload(), &[4, 5]);
+
+    assert!(matches!(
+        block_on(reader.next_frame()),
+        Err(Error::UnexpectedEnd { read: 0 })
+    ));
+}
+
+#[test]
+fn test_unpack_owned() {
+    let mut msger: Messager<DjiValidator> = Messager::new(3);
+
+    // The source buffer goes out of scope with the block.
+    let (frame, used) = {
+        let mut buffer = [0u8; 16];
+        let size = msger.pack(&TestCase::new([1, 2, 3]), &mut buffer).unwrap();
+        msger.unpack_owned::<8>(&buffer[..size]).unwrap()
+    };
+
+    assert_eq!(used, 12);
+    assert_eq!(frame.cmd_id(), 0x1234);
+    assert_eq!(frame.sequence(), 3);
+    assert_eq!(frame.payload(), &[1, 2, 3]);
+    assert_eq!(frame.as_raw().payload(), &[1, 2, 3]);
+
+    let mut buffer = [0u8; 16];
+    let size = msger.pack(&TestCase::new([1, 2, 3]), &mut buffer).unwrap();
+    assert!(matches!(
+        msger.unpack_owned::<2>(&buffer[..size]),
+        Err(Error::BufferTooSmall { need: 3 })
+    ));
+}
+
+#[test]
+fn test_can_tunnel() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut buffer = [0u8; 32];
+    let size = msger.pack(&TestCase::new([7u8; 11]), &mut buffer).unwrap();
+    assert_eq!(size, 20);
+
+    let frames = can_split(&buffer[..size]).unwrap();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(
+        frames.iter().map(|f| f.bytes().len()).sum::<usize>(),
+        20 + 3
+    );
+    assert!(frames.iter().all(|f| f.total() == 3));
+    assert_eq!(frames[2].index(), 2);
+
+    let mut reasm = CanReassembler::<32>::new();
+    assert!(reasm.push(frames[0].bytes()).unwrap().is_none());
+    assert!(reasm.push(frames[1].bytes()).unwrap().is_none());
+    let bytes = reasm.push(frames[2].bytes()).unwrap().unwrap();
+    assert_eq!(bytes, &buffer[..size]);
+
+    let (frame, _) = msger.unpack(bytes).unwrap();
+    assert_eq!(frame.payload(), &[7u8; 11]);
+
+    // A skipped frame drops the partial frame.
+    assert!(reasm.push(frames[0].bytes()).unwrap().is_none());
+    assert!(matches!(
+        reasm.push(frames[2].bytes()),
+        Err(Error::DecodeError { at: 0 })
+    ));
+
+    // An index past the total is rejected, not taken for the last frame.
+    assert!(reasm.push(frames[0].bytes()).unwrap().is_none());
+    assert!(matches!(
+        reasm.push(&[0x10, 7, 7]),
+        Err(Error::DecodeError { at: 0 })
+    ));
+
+    // And the partial frame is gone with it.
+    assert!(matches!(
+        reasm.push(frames[1].bytes()),
+        Err(Error::DecodeError { at: 0 })
+    ));
+    assert!(reasm.push(frames[0].bytes()).unwrap().is_none());
+    assert!(reasm.push(frames[1].bytes()).unwrap().is_none());
+    assert!(reasm.push(frames[2].bytes()).unwrap().is_some());
+
+    assert!(matches!(
+        can_split(&[0u8; TUNNEL_MAX + 1]),
+        Err(Error::InputTooLarge { max: TUNNEL_MAX })
+    ));
+}
+
+#[test]
+fn test_le_helpers() {
+    use crate::le::*;
+
+    let raw = [0xFF, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08];
+    assert_eq!(read_u16_le(&raw, 1), 0x0201);
+    assert_eq!(read_i16_le(&[0x00, 0x80], 0), i16::MIN);
+    assert_eq!(read_u32_le(&raw, 1), 0x0403_0201);
+    assert_eq!(read_i32_le(&[0xFE, 0xFF, 0xFF, 0xFF], 0), -2);
+    assert_eq!(read_u64_le(&raw, 1), 0x0807_0605_0403_0201);
+    assert_eq!(read_f32_le(&[0x00, 0x00, 0x80, 0x3F], 0), 1.0);
+
+    let mut dst = [0u8; 9];
+    write_u16_le(&mut dst, 1, 0x0201);
+    assert_eq!(dst[..3], [0x00, 0x01, 0x02]);
+    write_i16_le(&mut dst, 0, -2);
+    assert_eq!(dst[..2], [0xFE, 0xFF]);
+    write_u32_le(&mut dst, 1, 0x0403_0201);
+    assert_eq!(dst[1..5], [0x01, 0x02, 0x03, 0x04]);
+    write_i32_le(&mut dst, 0, i32::MIN);
+    assert_eq!(dst[..4], [0x00, 0x00, 0x00, 0x80]);
+    write_u64_le(&mut dst, 1, 0x0807_0605_0403_0201);
+    assert_eq!(dst[1..], [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08]);
+    write_f32_le(&mut dst, 0, 1.0);
+    assert_eq!(dst[..4], [0x00, 0x00, 0x80, 0x3F]);
+}
+
+/// Deterministic xorshift32 generator, so fuzz runs are reproducible.
+struct XorShift(u32);
+
+impl XorShift {
+    fn next(&mut self) -> u32 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.0 = x;
+        x
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.next() as usize % n
+    }
+
+    fn fill(&mut self, dst: &mut [u8]) {
+        dst.iter_mut().for_each(|b| *b = self.next() as u8);
+    }
+}
+
+/// Payload of any length, packed as is.
+struct Bytes<'a>(&'a [u8]);
+
+impl Marshaler for Bytes<'_> {
+    const CMD_ID: u16 = 0x1234;
+
+    fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
+        if dst.len() < self.0.len() {
+            return Err(Error::BufferTooSmall { need: self.0.len() });
+        }
+        dst[..self.0.len()].copy_from_slice(self.0);
+        Ok(self.0.len())
+    }
+
+    fn unmarshal(_: &[u8]) -> Result<Self> {
+        Err(Error::DecodeError { at: 0 })
+    }
+}
+
+#[test]
+fn test_fuzz_round_trip() {
+    let mut rng = XorShift(0x1234_5678);
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut payload = [0u8; 512];
+    let mut buffer = [0u8; 1024];
+    for round in 0..2000 {
+        let payload = &mut payload[..rng.below(513)];
+        rng.fill(payload);
+
+        // Packed through a marshaler, advancing the sequence.
+        let size = msger.pack(&Bytes(payload), &mut buffer).unwrap();
+        let (frame, used) = msger.unpack(&buffer[..size]).unwrap();
+        assert_eq!(used, size, "round {round}");
+        assert_eq!(frame.cmd_id(), Bytes::CMD_ID);
+        assert_eq!(frame.sequence(), round as u8);
+        assert_eq!(frame.payload(), &payload[..]);
+
+        // Rebuilt with an arbitrary command id and sequence.
+        let raw = RawFrame {
+            cmd_id: rng.next() as u16,
+            sequence: rng.next() as u8,
+            payload,
+        };
+        let size = raw.reconstruct::<DjiValidator>(&mut buffer).unwrap();
+        let (frame, used) = msger.unpack(&buffer[..size]).unwrap();
+        assert_eq!(used, size, "round {round}");
+        assert_eq!(frame.cmd_id(), raw.cmd_id);
+        assert_eq!(frame.sequence(), raw.sequence);
+        assert_eq!(frame.payload(), raw.payload);
+    }
+}
+
+#[test]
+fn test_fuzz_decode() {
+    let mut rng = XorShift(0x9E37_79B9);
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+
+    let mut payload = [0u8; 64];
+    let mut buffer = [0u8; 256];
+    for _ in 0..5000 {
+        let payload = &mut payload[..rng.below(65)];
+        rng.fill(payload);
+        let size = msger.pack(&Bytes(payload), &mut buffer).unwrap();
+
+        // Corrupt a few bytes, or replace the frame with garbage.
+        let src = &mut buffer[..size];
+        match rng.below(4) {
+            0 => rng.fill(src),
+            _ => {
+                for _ in 0..=rng.below(3) {
+                    src[rng.below(size)] ^= (rng.next() as u8) | 1;
+                }
+            }
+        }
+
+        let src = &buffer[..rng.below(size) + 1];
+        if let Ok((frame, used)) = msger.unpack(src) {
+            assert!(used <= src.len());
+            assert!(frame.payload().len() < used);
+        }
+        let _ = msger.needed(src);
+
+        let mut decoder = FrameDecoder::<DjiValidator>::new(src);
+        let frames = (&mut decoder).count();
+        assert!(frames <= 1);
+        assert!(decoder.remaining().len() <= src.len());
+    }
+}
+
+crate::dispatch! {
+    enum TestMessage {
+        Case(TestCase<5>),
+        Ping(Raw<0x0001, 2>),
+    }
+
+    fn decode_test;
+}
+
+#[test]
+fn test_dispatch_macro() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buffer = [0u8; 64];
+
+    let size = msger
+        .pack(&TestCase::new([1, 2, 3, 4, 5]), &mut buffer)
+        .unwrap();
+    let (frame, _) = msger.unpack(&buffer[..size]).unwrap();
+    match decode_test(&frame) {
+        Ok(Some(TestMessage::Case(case))) => assert_eq!(case.payload, [1, 2, 3, 4, 5]),
+        _ => panic!("Unexpected Dispatch"),
+    }
+
+    let size = msger.pack(&Raw::<0x0001, 2>([7, 8]), &mut buffer).unwrap();
+    let (frame, _) = msger.unpack(&buffer[..size]).unwrap();
+    assert!(matches!(
+        decode_test(&frame),
+        Ok(Some(TestMessage::Ping(Raw([7, 8]))))
+    ));
+
+    // Unknown command id.
+    let size = msger.pack(&Raw::<0x0002, 2>([0; 2]), &mut buffer).unwrap();
+    let (frame, _) = msger.unpack(&buffer[..size]).unwrap();
+    assert!(matches!(decode_test(&frame), Ok(None)));
+
+    // Known command id with a bad payload.
+    let size = msger.pack(&Raw::<0x1234, 2>([0; 2]), &mut buffer).unwrap();
+    let (frame, _) = msger.unpack(&buffer[..size]).unwrap();
+    assert!(matches!(
+        decode_test(&frame),
+        Err(Error::InvalidDataLength { expected: 5 })
+    ));
 }
@@ -0,0 +1,188 @@
+//!
+//! Tunneling packed frames over CAN.
+//!
+//! A CAN frame carries at most 8 data bytes, so a packed frame is split
+//! into several `CanFrame`s with `can_split` and joined back with a
+//! `CanReassembler` before `unpack`.
+//!
+//! Each CAN frame starts with one sub-frame byte, followed by up to 7
+//! bytes of the packed frame:
+//!
+//! ```text
+//! +-------------+---------------+------------+
+//! | INDEX (7:4) | TOTAL-1 (3:0) | 0..7 bytes |
+//! +-------------+---------------+------------+
+//! ```
+//!
+//! So a packed frame spans at most 16 CAN frames, 112 bytes.
+//!
+
+use crate::private::*;
+
+/// Data bytes of a classic CAN frame.
+const CAN_MTU: usize = 8;
+/// Packed frame bytes carried per CAN frame.
+const CHUNK: usize = CAN_MTU - 1;
+/// Maximum number of CAN frames per packed frame.
+const MAX_CHUNKS: usize = 16;
+/// Largest packed frame that can be tunneled.
+pub const TUNNEL_MAX: usize = CHUNK * MAX_CHUNKS;
+
+///
+/// Data field of one tunneled CAN frame.
+///
+/// Carries the sub-frame byte and a slice of the packed frame;
+/// pass `bytes()` as the data of the transport's CAN frame.
+///
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CanFrame {
+    data: [u8; CAN_MTU],
+    len: u8,
+}
+
+impl CanFrame {
+    /// Get the data bytes to send.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        &self.data[..self.len as usize]
+    }
+
+    /// Get the index of this frame within the packed frame.
+    #[inline]
+    pub fn index(&self) -> u8 {
+        self.data[0] >> 4
+    }
+
+    /// Get the number of CAN frames of the packed frame.
+    #[inline]
+    pub fn total(&self) -> u8 {
+        (self.data[0] & 0xF) + 1
+    }
+}
+
+///
+/// Split a packed frame into CAN frames.
+///
+/// # Errors
+///
+/// Returns `InputTooLarge` if `frame` exceeds `TUNNEL_MAX` bytes.
+///
+pub fn can_split(frame: &[u8]) -> Result<heapless::Vec<CanFrame, MAX_CHUNKS>> {
+    if frame.len() > TUNNEL_MAX {
+        return Err(Error::InputTooLarge { max: TUNNEL_MAX });
+    }
+
+    let total = frame.len().div_ceil(CHUNK).max(1);
+    let mut out = heapless::Vec::new();
+    for index in 0..total {
+        let chunk = &frame[index * CHUNK..frame.len().min((index + 1) * CHUNK)];
+
+        let mut data = [0u8; CAN_MTU];
+        data[0] = ((index as u8) << 4) | (total as u8 - 1);
+        data[1..1 + chunk.len()].copy_from_slice(chunk);
+
+        // Safety: `total` is at most `MAX_CHUNKS`, so Unwrap is safe.
+        out.push(CanFrame {
+            data,
+            len: (1 + chunk.len()) as u8,
+        })
+        .unwrap();
+    }
+
+    Ok(out)
+}
+
+///
+/// Collects tunneled CAN frames back into a packed frame.
+///
+/// Frames must arrive in order. A frame with index 0 always starts
+/// a new packed frame, so a lost frame only drops the frame it
+/// belonged to.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut reasm = CanReassembler::<128>::new();
+/// if let Some(bytes) = reasm.push(envelope.frame.data())? {
+///     let (frame, _) = msger.unpack(bytes)?;
+/// }
+/// ```
+///
+#[derive(Debug)]
+pub struct CanReassembler<const N: usize> {
+    /// Bytes collected so far.
+    buf: heapless::Vec<u8, N>,
+    /// Index of the next expected frame, `None` when idle.
+    next: Option<u8>,
+}
+
+impl<const N: usize> CanReassembler<N> {
+    /// Create an idle reassembler.
+    pub const fn new() -> Self {
+        Self {
+            buf: heapless::Vec::new(),
+            next: None,
+        }
+    }
+
+    ///
+    /// Feed the data of one received CAN frame.
+    ///
+    /// Returns the packed frame once its last CAN frame arrives.
+    ///
+    /// # Errors
+    ///
+    /// - `DecodeError` if the frame is empty, out of order, or its
+    ///   index is not below its total.
+    /// - `BufferTooSmall` if the packed frame exceeds `N` bytes.
+    ///
+    /// Either way the partial frame is dropped.
+    ///
+    pub fn push(&mut self, data: &[u8]) -> Result<Option<&[u8]>> {
+        let Some((&sub, chunk)) = data.split_first() else {
+            self.reset();
+            return Err(Error::DecodeError { at: 0 });
+        };
+        let (index, total) = (sub >> 4, (sub & 0xF) + 1);
+
+        if index >= total {
+            self.reset();
+            return Err(Error::DecodeError { at: 0 });
+        }
+
+        if index == 0 {
+            self.buf.clear();
+        } else if self.next != Some(index) {
+            self.reset();
+            return Err(Error::DecodeError { at: 0 });
+        }
+
+        if self.buf.extend_from_slice(chunk).is_err() {
+            self.reset();
+            return Err(Error::BufferTooSmall {
+                need: self.buf.len() + chunk.len(),
+            });
+        }
+
+        if index + 1 < total {
+            self.next = Some(index + 1);
+            return Ok(None);
+        }
+
+        self.next = None;
+        Ok(Some(&self.buf))
+    }
+
+    /// Drop the partial frame and wait for a new one.
+    fn reset(&mut self) {
+        self.buf.clear();
+        self.next = None;
+    }
+}
+
+impl<const N: usize> Default for CanReassembler<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
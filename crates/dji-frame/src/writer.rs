@@ -0,0 +1,77 @@
+//!
+//! Batch frame encoding.
+//!
+//! This module provides `FrameWriter`, which packs several
+//! messages back to back into one transmit buffer so they
+//! can be sent with a single write.
+//!
+
+use crate::private::*;
+
+///
+/// Sequential frame encoder over a caller-provided buffer.
+///
+/// `FrameWriter` borrows a `Messager` for sequencing, so every
+/// frame written through it advances one shared sequence counter.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut writer = FrameWriter::new(&mut msger, &mut buffer);
+/// writer.write(&a)?;
+/// writer.write(&b)?;
+/// uart.write(writer.finish()).await?;
+/// ```
+///
+pub struct FrameWriter<'a, V: Validator> {
+    /// Messager providing the sequence counter.
+    msger: &'a mut Messager<V>,
+    /// Destination buffer.
+    dst: &'a mut [u8],
+    /// Number of bytes written so far.
+    cursor: usize,
+}
+
+impl<'a, V: Validator> FrameWriter<'a, V> {
+    /// Create a new `FrameWriter` starting at the beginning of `dst`.
+    pub fn new(msger: &'a mut Messager<V>, dst: &'a mut [u8]) -> Self {
+        Self {
+            msger,
+            dst,
+            cursor: 0,
+        }
+    }
+
+    ///
+    /// Pack a message after the previously written frames.
+    ///
+    /// On error nothing is committed and the sequence number
+    /// is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as `Messager::pack`.
+    ///
+    pub fn write<M: Marshaler>(&mut self, msg: &M) -> Result<()> {
+        let size = self.msger.pack(msg, &mut self.dst[self.cursor..])?;
+        self.cursor += size;
+        Ok(())
+    }
+
+    /// Get the number of bytes written so far.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cursor
+    }
+
+    /// Check whether no frame has been written yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cursor == 0
+    }
+
+    /// Consume the writer and get the written frames.
+    pub fn finish(self) -> &'a [u8] {
+        &self.dst[..self.cursor]
+    }
+}
@@ -2,6 +2,44 @@ use crate::private::*;
 
 const SIZE: usize = 8;
 
+/// Remaining energy bucket of the robot
+///
+/// Decoded from the `remain_energy` bitfield, where each of bits 0..=4
+/// is set while the remaining energy is at or above 50%, 30%, 15%, 5%
+/// and 1% respectively. The server only sends the bits below 50% and
+/// reports the default `0x32` otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EnergyBuff {
+    AtLeast50,
+    AtLeast30,
+    AtLeast15,
+    AtLeast5,
+    AtLeast1,
+    Depleted,
+}
+
+impl EnergyBuff {
+    /// Value sent by the server while energy is at or above 50%
+    const DEFAULT: u8 = 0x32;
+
+    pub const fn from_bits(bits: u8) -> Self {
+        if bits == Self::DEFAULT || (bits & (1 << 0)) != 0 {
+            Self::AtLeast50
+        } else if (bits & (1 << 1)) != 0 {
+            Self::AtLeast30
+        } else if (bits & (1 << 2)) != 0 {
+            Self::AtLeast15
+        } else if (bits & (1 << 3)) != 0 {
+            Self::AtLeast5
+        } else if (bits & (1 << 4)) != 0 {
+            Self::AtLeast1
+        } else {
+            Self::Depleted
+        }
+    }
+}
+
 /// Server to Robot
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -35,14 +73,39 @@ impl RobotBuff {
         self.attack_rate
     }
 
-    /// TODO: Need More Bits Info
+    ///
+    /// Damage reduction in percent.
+    ///
+    /// `defence_rate` is sent as a direct percentage: 30 means
+    /// incoming damage is reduced by 30%.
+    ///
+    pub const fn defence_percent(&self) -> f32 {
+        self.defence_rate as f32
+    }
+
+    ///
+    /// Factor applied to the damage dealt.
+    ///
+    /// `attack_rate` is sent as the extra damage in percent, so
+    /// 50 means 1.5x and 0 means unbuffed (1.0x).
+    ///
+    pub const fn attack_multiplier(&self) -> f32 {
+        1. + self.attack_rate as f32 / 100.
+    }
+
+    /// Raw remaining energy bitfield, see `EnergyBuff`
     pub const fn remain_energy(&self) -> u8 {
         self.remain_energy
     }
+
+    pub const fn energy_buff(&self) -> EnergyBuff {
+        EnergyBuff::from_bits(self.remain_energy)
+    }
 }
 
 impl Marshaler for RobotBuff {
     const CMD_ID: u16 = 0x0204;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
@@ -50,10 +113,10 @@ impl Marshaler for RobotBuff {
         }
 
         dst[0] = self.recovery_rate;
-        dst[1..3].copy_from_slice(&self.colling_value.to_le_bytes());
+        le::write_u16_le(dst, 1, self.colling_value);
         dst[3] = self.defence_rate;
         dst[4] = self.vulnerablity_rate;
-        dst[5..7].copy_from_slice(&self.attack_rate.to_le_bytes());
+        le::write_u16_le(dst, 5, self.attack_rate);
         dst[7] = self.remain_energy;
 
         Ok(SIZE)
@@ -65,10 +128,10 @@ impl Marshaler for RobotBuff {
         }
 
         let recovery_rate = raw[0];
-        let colling_value = u16::from_le_bytes([raw[1], raw[2]]);
+        let colling_value = le::read_u16_le(raw, 1);
         let defence_rate = raw[3];
         let vulnerablity_rate = raw[4];
-        let attack_rate = u16::from_le_bytes([raw[5], raw[6]]);
+        let attack_rate = le::read_u16_le(raw, 5);
         let remain_energy = raw[7];
 
         Ok(RobotBuff {
@@ -106,3 +169,56 @@ fn test() {
     assert_eq!(buff2.attack_rate(), 1500);
     assert_eq!(buff2.remain_energy(), 80);
 }
+
+#[cfg(test)]
+#[test]
+fn test_energy_buff() {
+    let buff = RobotBuff {
+        recovery_rate: 0,
+        colling_value: 0,
+        defence_rate: 0,
+        vulnerablity_rate: 0,
+        attack_rate: 0,
+        remain_energy: 0b0000_1100,
+    };
+
+    let mut buf = [0u8; SIZE];
+    buff.marshal(&mut buf).unwrap();
+    let decoded = RobotBuff::unmarshal(&buf).unwrap();
+    assert_eq!(decoded.remain_energy(), 0b0000_1100);
+    assert_eq!(decoded.energy_buff(), EnergyBuff::AtLeast15);
+
+    assert_eq!(EnergyBuff::from_bits(0x32), EnergyBuff::AtLeast50);
+    assert_eq!(EnergyBuff::from_bits(0x1F), EnergyBuff::AtLeast50);
+    assert_eq!(EnergyBuff::from_bits(0x1E), EnergyBuff::AtLeast30);
+    assert_eq!(EnergyBuff::from_bits(0x1C), EnergyBuff::AtLeast15);
+    assert_eq!(EnergyBuff::from_bits(0x18), EnergyBuff::AtLeast5);
+    assert_eq!(EnergyBuff::from_bits(0x10), EnergyBuff::AtLeast1);
+    assert_eq!(EnergyBuff::from_bits(0x00), EnergyBuff::Depleted);
+}
+
+#[cfg(test)]
+#[test]
+fn test_rates() {
+    let buff = |defence_rate, attack_rate| RobotBuff {
+        recovery_rate: 0,
+        colling_value: 0,
+        defence_rate,
+        vulnerablity_rate: 0,
+        attack_rate,
+        remain_energy: 0,
+    };
+
+    assert_eq!(buff(0, 0).defence_percent(), 0.);
+    assert_eq!(buff(0, 0).attack_multiplier(), 1.);
+
+    let buffed = buff(50, 100);
+    assert_eq!(buffed.defence_rate(), 50);
+    assert_eq!(buffed.defence_percent(), 50.);
+    assert_eq!(buffed.attack_rate(), 100);
+    assert_eq!(buffed.attack_multiplier(), 2.);
+
+    assert_eq!(buff(30, 50).attack_multiplier(), 1.5);
+    assert_eq!(buff(100, 300).defence_percent(), 100.);
+    assert_eq!(buff(100, 300).attack_multiplier(), 4.);
+}
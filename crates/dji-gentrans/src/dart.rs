@@ -23,6 +23,7 @@ impl DartInfo {
 
 impl Marshaler for DartInfo {
     const CMD_ID: u16 = 0x0105;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
@@ -30,7 +31,7 @@ impl Marshaler for DartInfo {
         }
 
         dst[0] = self.remaining_time;
-        dst[1..3].copy_from_slice(&self.dart_info.to_le_bytes());
+        le::write_u16_le(dst, 1, self.dart_info);
 
         Ok(SIZE)
     }
@@ -41,7 +42,7 @@ impl Marshaler for DartInfo {
         }
 
         let remaining_time = raw[0];
-        let dart_info = u16::from_le_bytes([raw[1], raw[2]]);
+        let dart_info = le::read_u16_le(raw, 1);
 
         Ok(DartInfo {
             remaining_time,
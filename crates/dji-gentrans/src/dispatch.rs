@@ -0,0 +1,72 @@
+use crate::buff::RobotBuff;
+use crate::dart::DartInfo;
+use crate::event::GameEvent;
+use crate::health::GameRobotHP;
+use crate::heat::PowerHeat;
+use crate::hurt::HurtData;
+use crate::pos::RobotPos;
+use crate::radar::RadarInfo;
+use crate::result::GameResult;
+use crate::sentry::SentryInfo;
+use crate::states::GameStatus;
+use crate::status::RobotStatus;
+use crate::warning::RefereeWarning;
+
+dji_frame::dispatch! {
+    /// Any referee system message
+    #[derive(Debug, Clone, Copy)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub enum GenMessage {
+        Status(GameStatus),
+        Result(GameResult),
+        Health(GameRobotHP),
+        Event(GameEvent),
+        Warning(RefereeWarning),
+        Dart(DartInfo),
+        Robot(RobotStatus),
+        Heat(PowerHeat),
+        Pos(RobotPos),
+        Buff(RobotBuff),
+        Hurt(HurtData),
+        Sentry(SentryInfo),
+        Radar(RadarInfo),
+    }
+
+    ///
+    /// Decode a frame received from the referee system.
+    ///
+    /// Returns `Ok(None)` for command IDs not defined in this crate,
+    /// and the payload error for a known command ID with a bad payload.
+    ///
+    pub fn dispatch;
+}
+
+#[cfg(test)]
+#[test]
+fn test_dispatch() {
+    use crate::private::{Error, Marshaler};
+    use crate::result::Winner;
+    use dji_frame::{DjiValidator, Messager, Raw};
+
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buf = [0u8; 64];
+
+    let result = GameResult::unmarshal(&[2]).unwrap();
+    let size = msger.pack(&result, &mut buf).unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+    match dispatch(&frame) {
+        Ok(Some(GenMessage::Result(result))) => assert_eq!(result.winner(), Winner::Blue),
+        other => panic!("Unexpected Dispatch: {other:?}"),
+    }
+
+    let size = msger.pack(&Raw::<0x0301, 2>([0; 2]), &mut buf).unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+    assert!(matches!(dispatch(&frame), Ok(None)));
+
+    let size = msger.pack(&Raw::<0x0002, 2>([0; 2]), &mut buf).unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+    assert!(matches!(
+        dispatch(&frame),
+        Err(Error::InvalidDataLength { .. })
+    ));
+}
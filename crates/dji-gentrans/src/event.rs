@@ -18,13 +18,14 @@ impl GameEvent {
 
 impl Marshaler for GameEvent {
     const CMD_ID: u16 = 0x0101;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
             return Err(Error::BufferTooSmall { need: SIZE });
         }
 
-        dst[0..4].copy_from_slice(&self.event_data.to_le_bytes());
+        le::write_u32_le(dst, 0, self.event_data);
 
         Ok(SIZE)
     }
@@ -34,7 +35,7 @@ impl Marshaler for GameEvent {
             return Err(Error::InvalidDataLength { expected: SIZE });
         }
 
-        let event_data = u32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
+        let event_data = le::read_u32_le(raw, 0);
 
         Ok(GameEvent { event_data })
     }
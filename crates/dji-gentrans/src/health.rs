@@ -1,113 +1,371 @@
 use crate::private::*;
 
+/// One team, as sent by the current protocol
 const SIZE: usize = 16;
+/// Red then blue team, as sent by older protocol revisions
+const SIZE_BOTH: usize = 2 * SIZE;
+
+/// Color of a team
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Team {
+    Red,
+    Blue,
+}
+
+impl Team {
+    /// Team of a robot id, red (1-11) or blue (101-111).
+    pub const fn of(robot_id: u8) -> Option<Self> {
+        match robot_id {
+            1..=11 => Some(Self::Red),
+            101..=111 => Some(Self::Blue),
+            _ => None,
+        }
+    }
+
+    pub const fn opponent(self) -> Self {
+        match self {
+            Self::Red => Self::Blue,
+            Self::Blue => Self::Red,
+        }
+    }
+}
+
+/// HP of the robots and buildings of one team
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TeamHP {
+    robot_1: u16,
+    robot_2: u16,
+    robot_3: u16,
+    robot_4: u16,
+    robot_5: u16,
+    robot_7: u16,
+    outpost: u16,
+    base: u16,
+}
+
+impl TeamHP {
+    pub const fn get_robot1_hp(&self) -> u16 {
+        self.robot_1
+    }
+
+    pub const fn get_robot2_hp(&self) -> u16 {
+        self.robot_2
+    }
+
+    pub const fn get_robot3_hp(&self) -> u16 {
+        self.robot_3
+    }
+
+    pub const fn get_robot4_hp(&self) -> u16 {
+        self.robot_4
+    }
+
+    /// Standard 5 in older revisions, reserved and 0 since.
+    pub const fn get_robot5_hp(&self) -> u16 {
+        self.robot_5
+    }
+
+    pub const fn get_robot7_hp(&self) -> u16 {
+        self.robot_7
+    }
+
+    pub const fn get_outpost_hp(&self) -> u16 {
+        self.outpost
+    }
+
+    pub const fn get_base_hp(&self) -> u16 {
+        self.base
+    }
+
+    fn write(&self, dst: &mut [u8]) {
+        le::write_u16_le(dst, 0, self.robot_1);
+        le::write_u16_le(dst, 2, self.robot_2);
+        le::write_u16_le(dst, 4, self.robot_3);
+        le::write_u16_le(dst, 6, self.robot_4);
+        le::write_u16_le(dst, 8, self.robot_5);
+        le::write_u16_le(dst, 10, self.robot_7);
+        le::write_u16_le(dst, 12, self.outpost);
+        le::write_u16_le(dst, 14, self.base);
+    }
+
+    fn read(raw: &[u8]) -> Self {
+        Self {
+            robot_1: le::read_u16_le(raw, 0),
+            robot_2: le::read_u16_le(raw, 2),
+            robot_3: le::read_u16_le(raw, 4),
+            robot_4: le::read_u16_le(raw, 6),
+            robot_5: le::read_u16_le(raw, 8),
+            robot_7: le::read_u16_le(raw, 10),
+            outpost: le::read_u16_le(raw, 12),
+            base: le::read_u16_le(raw, 14),
+        }
+    }
+}
 
 /// Server to Robot
+///
+/// The current protocol only sends the ally team. Older revisions send
+/// the red team then the blue team, see [`GameRobotHP::team`].
+///
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct GameRobotHP {
-    ally_1: u16,
-    ally_2: u16,
-    ally_3: u16,
-    ally_4: u16,
-    _reserved: u16,
-    ally_7: u16,
-    ally_outpost: u16,
-    ally_base: u16,
+    first: TeamHP,
+    blue: Option<TeamHP>,
 }
 
 impl GameRobotHP {
+    /// Message of the current protocol, with the ally team only.
+    pub const fn ally(ally: TeamHP) -> Self {
+        Self {
+            first: ally,
+            blue: None,
+        }
+    }
+
+    /// Message of older revisions, with both teams.
+    pub const fn both(red: TeamHP, blue: TeamHP) -> Self {
+        Self {
+            first: red,
+            blue: Some(blue),
+        }
+    }
+
+    ///
+    /// HP seen by a robot of team `color`.
+    ///
+    /// With both teams sent, the ally team is the one of `color` and the
+    /// other is the enemy. Otherwise everything is the ally team, and there
+    /// is no enemy.
+    ///
+    pub const fn team(&self, color: Team) -> TeamView {
+        match (self.blue, color) {
+            (None, _) => TeamView {
+                ally: self.first,
+                enemy: None,
+            },
+            (Some(blue), Team::Red) => TeamView {
+                ally: self.first,
+                enemy: Some(blue),
+            },
+            (Some(blue), Team::Blue) => TeamView {
+                ally: blue,
+                enemy: Some(self.first),
+            },
+        }
+    }
+
+    /// Whether both teams were sent.
+    pub const fn has_both(&self) -> bool {
+        self.blue.is_some()
+    }
+
+    // The accessors below read the first team of the message, which is the
+    // red team if both were sent. Prefer `team` then.
+
     pub const fn get_ally1_hp(&self) -> u16 {
-        self.ally_1
+        self.first.robot_1
     }
 
     pub const fn get_ally2_hp(&self) -> u16 {
-        self.ally_2
+        self.first.robot_2
     }
 
     pub const fn get_ally3_hp(&self) -> u16 {
-        self.ally_3
+        self.first.robot_3
     }
 
     pub const fn get_ally4_hp(&self) -> u16 {
-        self.ally_4
+        self.first.robot_4
+    }
+
+    /// Standard 5 in older revisions, reserved and 0 since.
+    pub const fn get_ally5_hp(&self) -> u16 {
+        self.first.robot_5
     }
 
     pub const fn get_ally7_hp(&self) -> u16 {
-        self.ally_7
+        self.first.robot_7
     }
 
     pub const fn get_outpost_hp(&self) -> u16 {
-        self.ally_outpost
+        self.first.outpost
     }
 
     pub const fn get_base_hp(&self) -> u16 {
-        self.ally_base
+        self.first.base
+    }
+}
+
+/// HP relative to the team of a robot, see [`GameRobotHP::team`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TeamView {
+    ally: TeamHP,
+    enemy: Option<TeamHP>,
+}
+
+impl TeamView {
+    pub const fn ally(&self) -> &TeamHP {
+        &self.ally
+    }
+
+    /// Enemy team, if the message carried it.
+    pub const fn enemy(&self) -> Option<&TeamHP> {
+        self.enemy.as_ref()
+    }
+
+    pub const fn get_ally1_hp(&self) -> u16 {
+        self.ally.robot_1
+    }
+
+    pub const fn get_ally2_hp(&self) -> u16 {
+        self.ally.robot_2
+    }
+
+    pub const fn get_ally3_hp(&self) -> u16 {
+        self.ally.robot_3
+    }
+
+    pub const fn get_ally4_hp(&self) -> u16 {
+        self.ally.robot_4
+    }
+
+    pub const fn get_ally5_hp(&self) -> u16 {
+        self.ally.robot_5
+    }
+
+    pub const fn get_ally7_hp(&self) -> u16 {
+        self.ally.robot_7
+    }
+
+    pub const fn get_ally_outpost_hp(&self) -> u16 {
+        self.ally.outpost
+    }
+
+    pub const fn get_ally_base_hp(&self) -> u16 {
+        self.ally.base
+    }
+
+    pub const fn get_enemy1_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.robot_1),
+            None => None,
+        }
+    }
+
+    pub const fn get_enemy2_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.robot_2),
+            None => None,
+        }
+    }
+
+    pub const fn get_enemy3_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.robot_3),
+            None => None,
+        }
+    }
+
+    pub const fn get_enemy4_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.robot_4),
+            None => None,
+        }
+    }
+
+    pub const fn get_enemy5_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.robot_5),
+            None => None,
+        }
+    }
+
+    pub const fn get_enemy7_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.robot_7),
+            None => None,
+        }
+    }
+
+    pub const fn get_enemy_outpost_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.outpost),
+            None => None,
+        }
+    }
+
+    pub const fn get_enemy_base_hp(&self) -> Option<u16> {
+        match &self.enemy {
+            Some(enemy) => Some(enemy.base),
+            None => None,
+        }
     }
 }
 
 impl Marshaler for GameRobotHP {
     const CMD_ID: u16 = 0x0003;
+    // Either one or both teams.
+    const MARSHALED_LEN: Option<usize> = None;
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
-        if dst.len() < SIZE {
+        let size = if self.blue.is_some() { SIZE_BOTH } else { SIZE };
+        if dst.len() < size {
             return Err(Error::BufferTooSmall {
-                need: SIZE - dst.len(),
+                need: size - dst.len(),
             });
         }
 
-        dst[0..2].copy_from_slice(&self.ally_1.to_le_bytes());
-        dst[2..4].copy_from_slice(&self.ally_2.to_le_bytes());
-        dst[4..6].copy_from_slice(&self.ally_3.to_le_bytes());
-        dst[6..8].copy_from_slice(&self.ally_4.to_le_bytes());
-        dst[8..10].copy_from_slice(&self._reserved.to_le_bytes());
-        dst[10..12].copy_from_slice(&self.ally_7.to_le_bytes());
-        dst[12..14].copy_from_slice(&self.ally_outpost.to_le_bytes());
-        dst[14..16].copy_from_slice(&self.ally_base.to_le_bytes());
+        self.first.write(dst);
+        if let Some(blue) = &self.blue {
+            blue.write(&mut dst[SIZE..]);
+        }
 
-        Ok(SIZE)
+        Ok(size)
     }
 
     fn unmarshal(raw: &[u8]) -> Result<Self> {
-        if raw.len() != SIZE {
-            return Err(Error::InvalidDataLength { expected: SIZE });
-        }
-
-        let ally_1 = u16::from_le_bytes([raw[0], raw[1]]);
-        let ally_2 = u16::from_le_bytes([raw[2], raw[3]]);
-        let ally_3 = u16::from_le_bytes([raw[4], raw[5]]);
-        let ally_4 = u16::from_le_bytes([raw[6], raw[7]]);
-        let _reserved = u16::from_le_bytes([raw[8], raw[9]]);
-        let ally_7 = u16::from_le_bytes([raw[10], raw[11]]);
-        let ally_outpost = u16::from_le_bytes([raw[12], raw[13]]);
-        let ally_base = u16::from_le_bytes([raw[14], raw[15]]);
-
-        Ok(Self {
-            ally_1,
-            ally_2,
-            ally_3,
-            ally_4,
-            _reserved,
-            ally_7,
-            ally_outpost,
-            ally_base,
-        })
+        match raw.len() {
+            SIZE => Ok(Self::ally(TeamHP::read(raw))),
+            SIZE_BOTH => Ok(Self::both(
+                TeamHP::read(&raw[..SIZE]),
+                TeamHP::read(&raw[SIZE..]),
+            )),
+            _ => Err(Error::InvalidDataLength { expected: SIZE }),
+        }
+    }
+}
+
+#[cfg(test)]
+const fn team(base: u16) -> TeamHP {
+    TeamHP {
+        robot_1: base + 1,
+        robot_2: base + 2,
+        robot_3: base + 3,
+        robot_4: base + 4,
+        robot_5: base + 5,
+        robot_7: base + 7,
+        outpost: base + 8,
+        base: base + 9,
     }
 }
 
 #[cfg(test)]
 #[test]
 fn test() {
-    let status = GameRobotHP {
-        ally_1: 1000,
-        ally_2: 2000,
-        ally_3: 3000,
-        ally_4: 4000,
-        _reserved: 0,
-        ally_7: 7000,
-        ally_outpost: 8000,
-        ally_base: 9000,
-    };
+    let status = GameRobotHP::ally(TeamHP {
+        robot_1: 1000,
+        robot_2: 2000,
+        robot_3: 3000,
+        robot_4: 4000,
+        robot_5: 0,
+        robot_7: 7000,
+        outpost: 8000,
+        base: 9000,
+    });
 
     let mut buf = [0u8; SIZE + 10];
     let sz = status.marshal(&mut buf).unwrap();
@@ -118,7 +376,56 @@ fn test() {
     assert_eq!(decoded.get_ally2_hp(), 2000);
     assert_eq!(decoded.get_ally3_hp(), 3000);
     assert_eq!(decoded.get_ally4_hp(), 4000);
+    assert_eq!(decoded.get_ally5_hp(), 0);
     assert_eq!(decoded.get_ally7_hp(), 7000);
     assert_eq!(decoded.get_outpost_hp(), 8000);
     assert_eq!(decoded.get_base_hp(), 9000);
+    assert!(!decoded.has_both());
+
+    assert!(matches!(
+        GameRobotHP::unmarshal(&buf[..SIZE - 1]),
+        Err(Error::InvalidDataLength { expected: SIZE })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_team() {
+    assert_eq!(Team::of(3), Some(Team::Red));
+    assert_eq!(Team::of(107), Some(Team::Blue));
+    assert_eq!(Team::of(0), None);
+    assert_eq!(Team::Red.opponent(), Team::Blue);
+
+    let (red, blue) = (team(100), team(200));
+    let mut buf = [0u8; SIZE_BOTH];
+    let sz = GameRobotHP::both(red, blue).marshal(&mut buf).unwrap();
+    assert_eq!(sz, SIZE_BOTH);
+
+    let decoded = GameRobotHP::unmarshal(&buf).unwrap();
+    assert!(decoded.has_both());
+
+    // A red robot sees the red team as ally.
+    let view = decoded.team(Team::Red);
+    assert_eq!(view.ally(), &red);
+    assert_eq!(view.enemy(), Some(&blue));
+    assert_eq!(view.get_ally3_hp(), 103);
+    assert_eq!(view.get_enemy3_hp(), Some(203));
+    assert_eq!(view.get_enemy_base_hp(), Some(209));
+
+    // And a blue robot the other way around.
+    let view = decoded.team(Team::Blue);
+    assert_eq!(view.ally(), &blue);
+    assert_eq!(view.enemy(), Some(&red));
+    assert_eq!(view.get_ally1_hp(), 201);
+    assert_eq!(view.get_enemy1_hp(), Some(101));
+    assert_eq!(view.get_enemy_outpost_hp(), Some(108));
+
+    // With the ally team only, there is no enemy for either color.
+    let decoded = GameRobotHP::unmarshal(&buf[..SIZE]).unwrap();
+    for color in [Team::Red, Team::Blue] {
+        let view = decoded.team(color);
+        assert_eq!(view.ally(), &red);
+        assert_eq!(view.enemy(), None);
+        assert_eq!(view.get_enemy7_hp(), None);
+    }
 }
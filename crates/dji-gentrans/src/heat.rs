@@ -28,20 +28,31 @@ impl PowerHeat {
     }
 }
 
+impl Display for PowerHeat {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "PowerHeat {{ buffer: {} J, heat 17mm: {}, heat 42mm: {} }}",
+            self.buffer_energy, self.shooter_heat_17mm, self.shooter_heat_42mm
+        )
+    }
+}
+
 impl Marshaler for PowerHeat {
     const CMD_ID: u16 = 0x0202;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
             return Err(Error::BufferTooSmall { need: SIZE });
         }
 
-        dst[0..2].copy_from_slice(&self._reserved_1.to_le_bytes());
-        dst[2..4].copy_from_slice(&self._reserved_2.to_le_bytes());
-        dst[4..8].copy_from_slice(&self._reserved_3.to_le_bytes());
-        dst[8..10].copy_from_slice(&self.buffer_energy.to_le_bytes());
-        dst[10..12].copy_from_slice(&self.shooter_heat_17mm.to_le_bytes());
-        dst[12..14].copy_from_slice(&self.shooter_heat_42mm.to_le_bytes());
+        le::write_u16_le(dst, 0, self._reserved_1);
+        le::write_u16_le(dst, 2, self._reserved_2);
+        le::write_u32_le(dst, 4, self._reserved_3);
+        le::write_u16_le(dst, 8, self.buffer_energy);
+        le::write_u16_le(dst, 10, self.shooter_heat_17mm);
+        le::write_u16_le(dst, 12, self.shooter_heat_42mm);
 
         Ok(SIZE)
     }
@@ -51,12 +62,12 @@ impl Marshaler for PowerHeat {
             return Err(Error::InvalidDataLength { expected: SIZE });
         }
 
-        let _reserved_1 = u16::from_le_bytes([raw[0], raw[1]]);
-        let _reserved_2 = u16::from_le_bytes([raw[2], raw[3]]);
-        let _reserved_3 = u32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]);
-        let buffer_energy = u16::from_le_bytes([raw[8], raw[9]]);
-        let shooter_heat_17mm = u16::from_le_bytes([raw[10], raw[11]]);
-        let shooter_heat_42mm = u16::from_le_bytes([raw[12], raw[13]]);
+        let _reserved_1 = le::read_u16_le(raw, 0);
+        let _reserved_2 = le::read_u16_le(raw, 2);
+        let _reserved_3 = le::read_u32_le(raw, 4);
+        let buffer_energy = le::read_u16_le(raw, 8);
+        let shooter_heat_17mm = le::read_u16_le(raw, 10);
+        let shooter_heat_42mm = le::read_u16_le(raw, 12);
 
         Ok(PowerHeat {
             _reserved_1,
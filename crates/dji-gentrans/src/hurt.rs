@@ -3,7 +3,7 @@ use crate::private::*;
 const SIZE: usize = 1;
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Reason {
     HitByProjectile = 0,
@@ -31,6 +31,7 @@ impl HurtData {
 
 impl Marshaler for HurtData {
     const CMD_ID: u16 = 0x0206;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
@@ -48,12 +49,8 @@ impl Marshaler for HurtData {
         }
 
         let armor_id = raw[0] & 0x0F;
-        let deduction_reason = match (raw[0] >> 4) & 0xF {
-            0 => Reason::HitByProjectile,
-            1 => Reason::ModuleOffline,
-            5 => Reason::StruckByImpact,
-
-            _ => return Err(Error::DecodeError { at: 0 }),
+        let Some(deduction_reason) = Reason::from_repr((raw[0] >> 4) & 0xF) else {
+            return Err(Error::DecodeError { at: 0 });
         };
 
         Ok(HurtData {
@@ -80,3 +77,14 @@ fn test() {
     assert_eq!(hurt2.armor_id(), 3);
     assert_eq!(hurt2.deduction_reason(), Reason::ModuleOffline);
 }
+
+#[cfg(test)]
+#[test]
+fn test_invalid_discriminant() {
+    for reason in [2u8, 3, 4, 6, 0xF] {
+        assert!(matches!(
+            HurtData::unmarshal(&[reason << 4]),
+            Err(Error::DecodeError { at: 0 })
+        ));
+    }
+}
@@ -1,5 +1,26 @@
 #![cfg_attr(not(test), no_std)]
 
+use dji_frame::Marshaler as _;
+
+/// Command IDs of all messages in this crate, checked for collisions.
+pub const CMD_IDS: &[u16] = &[
+    states::GameStatus::CMD_ID,
+    result::GameResult::CMD_ID,
+    health::GameRobotHP::CMD_ID,
+    event::GameEvent::CMD_ID,
+    warning::RefereeWarning::CMD_ID,
+    dart::DartInfo::CMD_ID,
+    status::RobotStatus::CMD_ID,
+    heat::PowerHeat::CMD_ID,
+    pos::RobotPos::CMD_ID,
+    buff::RobotBuff::CMD_ID,
+    hurt::HurtData::CMD_ID,
+    sentry::SentryInfo::CMD_ID,
+    radar::RadarInfo::CMD_ID,
+];
+
+const _: () = dji_frame::assert_unique_ids(CMD_IDS);
+
 /// 0x0001 - Game Status
 pub mod states;
 
@@ -33,12 +54,26 @@ pub mod buff;
 /// 0x0206 - Robot Hurt Data
 pub mod hurt;
 
+/// 0x020D - Sentry Info
+pub mod sentry;
+
+/// 0x020E - Radar Info
+pub mod radar;
+
+/// Damage report aggregated from status, hurt and warning messages
+pub mod report;
+
+/// Typed decoding of every message in this crate
+pub mod dispatch;
+
 mod private {
     #[allow(unused_imports)]
     #[cfg(feature = "defmt")]
     pub use ::defmt::{debug, error, info, trace, warn};
 
-    pub use dji_frame::{Error, Marshaler, Result};
+    pub use core::fmt::{Display, Formatter, Result as FmtResult};
+    pub use dji_frame::{Error, Marshaler, RawFrame, Result, le};
+    pub use strum::FromRepr;
 }
 
 #[cfg(test)]
@@ -57,4 +92,17 @@ fn test_command_id() {
     assert_eq!(pos::RobotPos::CMD_ID, 0x0203);
     assert_eq!(buff::RobotBuff::CMD_ID, 0x0204);
     assert_eq!(hurt::HurtData::CMD_ID, 0x0206);
+    assert_eq!(sentry::SentryInfo::CMD_ID, 0x020D);
+    assert_eq!(radar::RadarInfo::CMD_ID, 0x020E);
+}
+
+#[cfg(test)]
+#[test]
+fn test_command_id_unique() {
+    let mut ids = [CMD_IDS, dji_pictrans::CMD_IDS, dji_ui::CMD_IDS].concat();
+    let len = ids.len();
+
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), len);
 }
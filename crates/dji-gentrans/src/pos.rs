@@ -26,17 +26,28 @@ impl RobotPos {
     }
 }
 
+impl Display for RobotPos {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "RobotPos {{ x: {} m, y: {} m, angle: {}° }}",
+            self.x, self.y, self.z
+        )
+    }
+}
+
 impl Marshaler for RobotPos {
     const CMD_ID: u16 = 0x0203;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
             return Err(Error::BufferTooSmall { need: SIZE });
         }
 
-        dst[0..4].copy_from_slice(&self.x.to_le_bytes());
-        dst[4..8].copy_from_slice(&self.y.to_le_bytes());
-        dst[8..12].copy_from_slice(&self.z.to_le_bytes());
+        le::write_f32_le(dst, 0, self.x);
+        le::write_f32_le(dst, 4, self.y);
+        le::write_f32_le(dst, 8, self.z);
 
         Ok(SIZE)
     }
@@ -46,11 +57,21 @@ impl Marshaler for RobotPos {
             return Err(Error::InvalidDataLength { expected: SIZE });
         }
 
-        let x = f32::from_le_bytes([raw[0], raw[1], raw[2], raw[3]]);
-        let y = f32::from_le_bytes([raw[4], raw[5], raw[6], raw[7]]);
-        let z = f32::from_le_bytes([raw[8], raw[9], raw[10], raw[11]]);
+        // Reject NaN or infinite fields, pointing at their offset.
+        let field = |at: usize| {
+            let v = le::read_f32_le(raw, at);
+            if v.is_finite() {
+                Ok(v)
+            } else {
+                Err(Error::DecodeError { at })
+            }
+        };
 
-        Ok(RobotPos { x, y, z })
+        Ok(RobotPos {
+            x: field(0)?,
+            y: field(4)?,
+            z: field(8)?,
+        })
     }
 }
 
@@ -72,3 +93,116 @@ fn test() {
     assert_eq!(pos2.pos_y(), 2.0);
     assert_eq!(pos2.angle(), 3.0);
 }
+
+#[cfg(test)]
+#[test]
+fn test_display() {
+    let pos = RobotPos {
+        x: 1.5,
+        y: -2.25,
+        z: 90.0,
+    };
+
+    let text = pos.to_string();
+    assert!(text.contains("x: 1.5 m"));
+    assert!(text.contains("y: -2.25 m"));
+    assert!(text.contains("angle: 90°"));
+}
+
+#[cfg(test)]
+#[test]
+fn test_non_finite() {
+    let mut buf = [0u8; SIZE];
+    buf[4..8].copy_from_slice(&f32::NAN.to_le_bytes());
+    assert!(matches!(
+        RobotPos::unmarshal(&buf),
+        Err(Error::DecodeError { at: 4 })
+    ));
+
+    buf[4..8].copy_from_slice(&0f32.to_le_bytes());
+    buf[8..12].copy_from_slice(&f32::INFINITY.to_le_bytes());
+    assert!(matches!(
+        RobotPos::unmarshal(&buf),
+        Err(Error::DecodeError { at: 8 })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_derive() {
+    use crate::states::GameType;
+
+    #[derive(dji_frame::Marshaler)]
+    #[cmd_id = 0x0203]
+    struct DerivedPos {
+        x: f32,
+        y: f32,
+        z: f32,
+    }
+
+    assert_eq!(DerivedPos::CMD_ID, RobotPos::CMD_ID);
+    assert_eq!(DerivedPos::MARSHALED_LEN, RobotPos::MARSHALED_LEN);
+
+    let (x, y, z) = (1.5, -2.25, 90.0);
+    let mut derived = [0u8; SIZE];
+    let mut written = [0u8; SIZE];
+    assert_eq!(DerivedPos { x, y, z }.marshal(&mut derived).unwrap(), SIZE);
+    assert_eq!(RobotPos { x, y, z }.marshal(&mut written).unwrap(), SIZE);
+    assert_eq!(derived, written);
+
+    let decoded = DerivedPos::unmarshal(&written).unwrap();
+    assert_eq!((decoded.x, decoded.y, decoded.z), (x, y, z));
+    assert!(matches!(
+        DerivedPos::unmarshal(&written[1..]),
+        Err(Error::InvalidDataLength { expected: SIZE })
+    ));
+    assert!(matches!(
+        DerivedPos { x, y, z }.marshal(&mut [0u8; 4]),
+        Err(Error::BufferTooSmall { need: SIZE })
+    ));
+
+    #[derive(dji_frame::Marshaler)]
+    #[cmd_id = 0x0F00]
+    struct Mixed {
+        #[from_repr]
+        game_type: GameType,
+        level: u8,
+        offset: i8,
+        armed: bool,
+        hp: u16,
+        stamp: u64,
+    }
+
+    let mixed = Mixed {
+        game_type: GameType::RMUL3V3,
+        level: 3,
+        offset: -2,
+        armed: true,
+        hp: 0x1234,
+        stamp: 1,
+    };
+
+    let mut buf = [0u8; 14];
+    assert_eq!(mixed.marshal(&mut buf).unwrap(), 14);
+    assert_eq!(buf, [4, 3, 0xFE, 1, 0x34, 0x12, 1, 0, 0, 0, 0, 0, 0, 0]);
+
+    let decoded = Mixed::unmarshal(&buf).unwrap();
+    assert_eq!(decoded.game_type, GameType::RMUL3V3);
+    assert_eq!(
+        (decoded.level, decoded.offset, decoded.armed),
+        (3, -2, true)
+    );
+    assert_eq!((decoded.hp, decoded.stamp), (0x1234, 1));
+
+    buf[0] = 0;
+    assert!(matches!(
+        Mixed::unmarshal(&buf),
+        Err(Error::DecodeError { at: 0 })
+    ));
+    buf[0] = 4;
+    buf[3] = 2;
+    assert!(matches!(
+        Mixed::unmarshal(&buf),
+        Err(Error::DecodeError { at: 3 })
+    ));
+}
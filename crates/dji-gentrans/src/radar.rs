@@ -0,0 +1,71 @@
+use crate::private::*;
+
+const SIZE: usize = 1;
+
+/// Server to Radar
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RadarInfo {
+    double_vulnerability_chances: u8, // 2 bits
+    double_vulnerability_active: bool,
+}
+
+impl RadarInfo {
+    /// Chances left to trigger double vulnerability (0~2)
+    pub const fn double_vulnerability_chances(&self) -> u8 {
+        self.double_vulnerability_chances
+    }
+
+    /// The enemy is currently under double vulnerability
+    pub const fn double_vulnerability_active(&self) -> bool {
+        self.double_vulnerability_active
+    }
+}
+
+impl Marshaler for RadarInfo {
+    const CMD_ID: u16 = 0x020E;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
+
+    fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
+        if dst.len() < SIZE {
+            return Err(Error::BufferTooSmall { need: SIZE });
+        }
+
+        dst[0] = (self.double_vulnerability_chances & 0x3)
+            | ((self.double_vulnerability_active as u8) << 2);
+
+        Ok(SIZE)
+    }
+
+    fn unmarshal(raw: &[u8]) -> Result<Self> {
+        if raw.len() != SIZE {
+            return Err(Error::InvalidDataLength { expected: SIZE });
+        }
+
+        let double_vulnerability_chances = raw[0] & 0x3;
+        let double_vulnerability_active = (raw[0] >> 2) & 0x1 != 0;
+
+        Ok(RadarInfo {
+            double_vulnerability_chances,
+            double_vulnerability_active,
+        })
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test() {
+    let radar = RadarInfo {
+        double_vulnerability_chances: 2,
+        double_vulnerability_active: true,
+    };
+
+    let mut buf = [0u8; SIZE];
+    let sz = radar.marshal(&mut buf).unwrap();
+    assert_eq!(sz, SIZE);
+    assert_eq!(buf[0], 0x6);
+
+    let radar2 = RadarInfo::unmarshal(&buf).unwrap();
+    assert_eq!(radar2.double_vulnerability_chances(), 2);
+    assert!(radar2.double_vulnerability_active());
+}
@@ -0,0 +1,157 @@
+use crate::private::*;
+
+use crate::hurt::HurtData;
+use crate::status::RobotStatus;
+use crate::warning::{Level, RefereeWarning};
+
+/// A value with the time it was received, in caller-defined ms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Stamped<T> {
+    value: T,
+    at_ms: u64,
+}
+
+impl<T: Copy> Stamped<T> {
+    pub const fn value(&self) -> T {
+        self.value
+    }
+
+    pub const fn at_ms(&self) -> u64 {
+        self.at_ms
+    }
+}
+
+///
+/// Aggregated view of recent damage.
+///
+/// Fed with decoded `RobotStatus`, `HurtData` and `RefereeWarning`
+/// messages, it keeps the latest HP, the last armor hit and the
+/// current penalty, each stamped with the time it arrived.
+///
+#[derive(Debug, Clone, Copy, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DamageReport {
+    hp: Option<Stamped<u16>>,
+    hit: Option<Stamped<HurtData>>,
+    penalty: Option<Stamped<Level>>,
+}
+
+impl DamageReport {
+    pub const fn new() -> Self {
+        Self {
+            hp: None,
+            hit: None,
+            penalty: None,
+        }
+    }
+
+    /// Latest HP, from `RobotStatus`.
+    pub const fn hp(&self) -> Option<Stamped<u16>> {
+        self.hp
+    }
+
+    /// Last armor hit and its reason, from `HurtData`.
+    pub const fn last_hit(&self) -> Option<Stamped<HurtData>> {
+        self.hit
+    }
+
+    /// Current penalty level, from `RefereeWarning`.
+    pub const fn penalty(&self) -> Option<Stamped<Level>> {
+        self.penalty
+    }
+
+    pub const fn feed_status(&mut self, status: &RobotStatus, now_ms: u64) {
+        self.hp = Some(Stamped {
+            value: status.current_hp(),
+            at_ms: now_ms,
+        });
+    }
+
+    pub const fn feed_hurt(&mut self, hurt: &HurtData, now_ms: u64) {
+        self.hit = Some(Stamped {
+            value: *hurt,
+            at_ms: now_ms,
+        });
+    }
+
+    pub const fn feed_warning(&mut self, warning: &RefereeWarning, now_ms: u64) {
+        self.penalty = Some(Stamped {
+            value: warning.level(),
+            at_ms: now_ms,
+        });
+    }
+
+    ///
+    /// Feed a received frame, decoding it if it is relevant.
+    ///
+    /// Returns `true` if the frame updated the report.
+    ///
+    /// # Errors
+    ///
+    /// Returns the unmarshal error of a relevant but malformed frame.
+    ///
+    pub fn feed(&mut self, frame: &RawFrame, now_ms: u64) -> Result<bool> {
+        let raw = frame.payload();
+        match frame.cmd_id() {
+            RobotStatus::CMD_ID => self.feed_status(&RobotStatus::unmarshal(raw)?, now_ms),
+            HurtData::CMD_ID => self.feed_hurt(&HurtData::unmarshal(raw)?, now_ms),
+            RefereeWarning::CMD_ID => self.feed_warning(&RefereeWarning::unmarshal(raw)?, now_ms),
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+fn feed<M: Marshaler>(report: &mut DamageReport, msg: &M, now_ms: u64) -> bool {
+    use dji_frame::{DjiValidator, Messager};
+
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buf = [0u8; 64];
+    let size = msger.pack(msg, &mut buf).unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+    report.feed(&frame, now_ms).unwrap()
+}
+
+#[cfg(test)]
+#[test]
+fn test() {
+    use crate::hurt::Reason;
+
+    let status = |hp| {
+        RobotStatus::builder()
+            .robot_id(1)
+            .robot_level(1)
+            .maximum_hp(200)
+            .current_hp(hp)
+            .build()
+            .unwrap()
+    };
+    let hurt = HurtData::unmarshal(&[0x02]).unwrap();
+    let warning = RefereeWarning::unmarshal(&[2, 1, 1]).unwrap();
+
+    let mut report = DamageReport::new();
+    assert!(report.hp().is_none());
+
+    assert!(feed(&mut report, &status(200), 100));
+    assert!(feed(&mut report, &hurt, 150));
+    assert!(feed(&mut report, &status(190), 200));
+    assert!(feed(&mut report, &warning, 300));
+
+    // Unrelated frames are ignored.
+    let pos = dji_frame::Raw::<0x0203, 12>::from_bytes([0; 12]);
+    assert!(!feed(&mut report, &pos, 400));
+
+    let hp = report.hp().unwrap();
+    assert_eq!((hp.value(), hp.at_ms()), (190, 200));
+
+    let hit = report.last_hit().unwrap();
+    assert_eq!(hit.at_ms(), 150);
+    assert_eq!(hit.value().armor_id(), 2);
+    assert_eq!(hit.value().deduction_reason(), Reason::HitByProjectile);
+
+    let penalty = report.penalty().unwrap();
+    assert_eq!((penalty.value(), penalty.at_ms()), (Level::YellowCard, 300));
+}
@@ -3,7 +3,7 @@ use crate::private::*;
 const SIZE: usize = 1;
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Winner {
     Draw = 0,
@@ -26,6 +26,7 @@ impl GameResult {
 
 impl Marshaler for GameResult {
     const CMD_ID: u16 = 0x0002;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
@@ -42,12 +43,8 @@ impl Marshaler for GameResult {
             return Err(Error::InvalidDataLength { expected: SIZE });
         }
 
-        let winner = match raw[0] {
-            0 => Winner::Draw,
-            1 => Winner::Red,
-            2 => Winner::Blue,
-
-            _ => return Err(Error::DecodeError { at: 0 }),
+        let Some(winner) = Winner::from_repr(raw[0]) else {
+            return Err(Error::DecodeError { at: 0 });
         };
 
         Ok(GameResult { winner })
@@ -68,3 +65,14 @@ fn test() {
     let decoded = GameResult::unmarshal(&buf[..SIZE]).unwrap();
     assert_eq!(decoded.winner, Winner::Blue);
 }
+
+#[cfg(test)]
+#[test]
+fn test_invalid_discriminant() {
+    for raw in [3, 0xFF] {
+        assert!(matches!(
+            GameResult::unmarshal(&[raw]),
+            Err(Error::DecodeError { at: 0 })
+        ));
+    }
+}
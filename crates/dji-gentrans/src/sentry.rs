@@ -0,0 +1,106 @@
+use crate::private::*;
+
+const SIZE: usize = 6;
+
+/// Server to Sentry
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SentryInfo {
+    sentry_info: u32,
+    sentry_info_2: u16,
+}
+
+impl SentryInfo {
+    /// Projectiles exchanged, excluding remote exchanges (bits 0-10)
+    pub const fn exchanged_ammo(&self) -> u16 {
+        (self.sentry_info & 0x7FF) as u16
+    }
+
+    /// Number of remote projectile exchanges (bits 11-14)
+    pub const fn remote_ammo_exchanges(&self) -> u8 {
+        ((self.sentry_info >> 11) & 0xF) as u8
+    }
+
+    /// Number of remote HP exchanges (bits 15-18)
+    pub const fn remote_hp_exchanges(&self) -> u8 {
+        ((self.sentry_info >> 15) & 0xF) as u8
+    }
+
+    /// Free resurrection can be confirmed (bit 19)
+    pub const fn can_free_revive(&self) -> bool {
+        (self.sentry_info & (1 << 19)) != 0
+    }
+
+    /// Instant resurrection can be exchanged (bit 20)
+    pub const fn can_instant_revive(&self) -> bool {
+        (self.sentry_info & (1 << 20)) != 0
+    }
+
+    /// Gold cost of an instant resurrection (bits 21-30)
+    pub const fn instant_revive_cost(&self) -> u16 {
+        ((self.sentry_info >> 21) & 0x3FF) as u16
+    }
+
+    /// Sentry is out of combat (bit 0)
+    pub const fn out_of_combat(&self) -> bool {
+        (self.sentry_info_2 & (1 << 0)) != 0
+    }
+
+    /// Team 17mm allowance still exchangeable (bits 1-11)
+    pub const fn team_ammo_remaining(&self) -> u16 {
+        (self.sentry_info_2 >> 1) & 0x7FF
+    }
+}
+
+impl Marshaler for SentryInfo {
+    const CMD_ID: u16 = 0x020D;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
+
+    fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
+        if dst.len() < SIZE {
+            return Err(Error::BufferTooSmall { need: SIZE });
+        }
+
+        le::write_u32_le(dst, 0, self.sentry_info);
+        le::write_u16_le(dst, 4, self.sentry_info_2);
+
+        Ok(SIZE)
+    }
+
+    fn unmarshal(raw: &[u8]) -> Result<Self> {
+        if raw.len() != SIZE {
+            return Err(Error::InvalidDataLength { expected: SIZE });
+        }
+
+        let sentry_info = le::read_u32_le(raw, 0);
+        let sentry_info_2 = le::read_u16_le(raw, 4);
+
+        Ok(SentryInfo {
+            sentry_info,
+            sentry_info_2,
+        })
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test() {
+    let info = SentryInfo {
+        sentry_info: 300 | (2 << 11) | (3 << 15) | (1 << 19) | (80 << 21),
+        sentry_info_2: 1 | (150 << 1),
+    };
+
+    let mut buf = [0u8; SIZE];
+    let sz = info.marshal(&mut buf).unwrap();
+    assert_eq!(sz, SIZE);
+
+    let info2 = SentryInfo::unmarshal(&buf).unwrap();
+    assert_eq!(info2.exchanged_ammo(), 300);
+    assert_eq!(info2.remote_ammo_exchanges(), 2);
+    assert_eq!(info2.remote_hp_exchanges(), 3);
+    assert!(info2.can_free_revive());
+    assert!(!info2.can_instant_revive());
+    assert_eq!(info2.instant_revive_cost(), 80);
+    assert!(info2.out_of_combat());
+    assert_eq!(info2.team_ammo_remaining(), 150);
+}
@@ -3,7 +3,7 @@ use crate::private::*;
 const SIZE: usize = 11;
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum GameType {
     RMUC = 1,
@@ -14,7 +14,7 @@ pub enum GameType {
 }
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum GameProgress {
     NotStarted = 0,
@@ -25,6 +25,22 @@ pub enum GameProgress {
     Calculating = 5,
 }
 
+impl TryFrom<u8> for GameType {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        Self::from_repr(value).ok_or(Error::DecodeError { at: 0 })
+    }
+}
+
+impl TryFrom<u8> for GameProgress {
+    type Error = Error;
+
+    fn try_from(value: u8) -> Result<Self> {
+        Self::from_repr(value).ok_or(Error::DecodeError { at: 0 })
+    }
+}
+
 /// Server to Robot
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -36,6 +52,21 @@ pub struct GameStatus {
 }
 
 impl GameStatus {
+    /// Build a status from raw discriminants, rejecting unknown values.
+    pub fn new(
+        game_type: u8,
+        game_progress: u8,
+        remaining_time_s: u16,
+        unix_timestamp: u64,
+    ) -> Result<Self> {
+        Ok(GameStatus {
+            game_type: GameType::try_from(game_type)?,
+            game_progress: GameProgress::try_from(game_progress)?,
+            remaining_time_s,
+            unix_timestamp,
+        })
+    }
+
     pub const fn game_type(&self) -> GameType {
         self.game_type
     }
@@ -51,10 +82,52 @@ impl GameStatus {
     pub const fn unix_timestamp(&self) -> u64 {
         self.unix_timestamp
     }
+
+    ///
+    /// Whether the match is running with at most `threshold_s` left.
+    ///
+    /// Always `false` outside `InProgress`: during `CountDown5s`
+    /// the remaining time is that of the countdown, and during
+    /// `Calculating` the match is already over.
+    ///
+    pub const fn is_endgame(&self, threshold_s: u16) -> bool {
+        matches!(self.game_progress, GameProgress::InProgress)
+            && self.remaining_time_s <= threshold_s
+    }
+
+    ///
+    /// Seconds elapsed since this status was stamped.
+    ///
+    /// Subtract it from `remaining_time_s` to extrapolate the
+    /// current phase between status updates.
+    ///
+    /// Returns `None` if `now_unix` is before the timestamp, or
+    /// the progress has no timed phase (before the countdown or
+    /// while `Calculating`).
+    ///
+    pub const fn stamp_age(&self, now_unix: u64) -> Option<u64> {
+        match self.game_progress {
+            GameProgress::CountDown5s | GameProgress::InProgress => {
+                now_unix.checked_sub(self.unix_timestamp)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Display for GameStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "GameStatus {{ type: {:?}, progress: {:?}, remaining: {} s, timestamp: {} }}",
+            self.game_type, self.game_progress, self.remaining_time_s, self.unix_timestamp
+        )
+    }
 }
 
 impl Marshaler for GameStatus {
     const CMD_ID: u16 = 0x0001;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
@@ -64,8 +137,8 @@ impl Marshaler for GameStatus {
         }
 
         dst[0] = (self.game_type as u8) & 0xF | ((self.game_progress as u8) & 0xF) << 4;
-        dst[1..3].copy_from_slice(&self.remaining_time_s.to_le_bytes());
-        dst[3..11].copy_from_slice(&self.unix_timestamp.to_le_bytes());
+        le::write_u16_le(dst, 1, self.remaining_time_s);
+        le::write_u64_le(dst, 3, self.unix_timestamp);
 
         Ok(SIZE)
     }
@@ -75,31 +148,11 @@ impl Marshaler for GameStatus {
             return Err(Error::InvalidDataLength { expected: SIZE });
         }
 
-        let game_type = match raw[0] & 0xF {
-            1 => GameType::RMUC,
-            2 => GameType::RMUT,
-            3 => GameType::RMUA,
-            4 => GameType::RMUL3V3,
-            5 => GameType::RMUL1V1,
-
-            _ => return Err(Error::DecodeError { at: 0 }),
-        };
-
-        let game_progress = match (raw[0] >> 4) & 0xF {
-            0 => GameProgress::NotStarted,
-            1 => GameProgress::PrePared,
-            2 => GameProgress::SelfCheck,
-            3 => GameProgress::CountDown5s,
-            4 => GameProgress::InProgress,
-            5 => GameProgress::Calculating,
+        let game_type = GameType::try_from(raw[0] & 0xF)?;
+        let game_progress = GameProgress::try_from((raw[0] >> 4) & 0xF)?;
 
-            _ => return Err(Error::DecodeError { at: 0 }),
-        };
-
-        let remaining_time_s = u16::from_le_bytes([raw[1], raw[2]]);
-        let unix_timestamp = u64::from_le_bytes([
-            raw[3], raw[4], raw[5], raw[6], raw[7], raw[8], raw[9], raw[10],
-        ]);
+        let remaining_time_s = le::read_u16_le(raw, 1);
+        let unix_timestamp = le::read_u64_le(raw, 3);
 
         Ok(GameStatus {
             game_type,
@@ -130,3 +183,81 @@ fn test() {
     assert_eq!(decoded.remaining_time_s(), 1234);
     assert_eq!(decoded.unix_timestamp(), 1672531199);
 }
+
+#[cfg(test)]
+#[test]
+fn test_display() {
+    let status = GameStatus {
+        game_type: GameType::RMUC,
+        game_progress: GameProgress::CountDown5s,
+        remaining_time_s: 5,
+        unix_timestamp: 0,
+    };
+
+    assert_eq!(
+        status.to_string(),
+        "GameStatus { type: RMUC, progress: CountDown5s, remaining: 5 s, timestamp: 0 }"
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_new() {
+    let status = GameStatus::new(1, 4, 420, 1672531199).unwrap();
+    assert_eq!(status.game_type(), GameType::RMUC);
+    assert_eq!(status.game_progress(), GameProgress::InProgress);
+    assert_eq!(status.remaining_time_s(), 420);
+
+    assert_eq!(GameType::try_from(5).unwrap(), GameType::RMUL1V1);
+    assert_eq!(GameProgress::try_from(0).unwrap(), GameProgress::NotStarted);
+
+    assert!(matches!(
+        GameStatus::new(1, 6, 0, 0),
+        Err(Error::DecodeError { .. })
+    ));
+    assert!(matches!(
+        GameStatus::new(0, 4, 0, 0),
+        Err(Error::DecodeError { .. })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_invalid_discriminant() {
+    for raw in [0, 6, 0xFF] {
+        assert!(GameType::try_from(raw).is_err());
+    }
+    for raw in [6, 7, 0xFF] {
+        assert!(GameProgress::try_from(raw).is_err());
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_endgame() {
+    let status = GameStatus::new(1, 4, 30, 1000).unwrap();
+    assert!(status.is_endgame(30));
+    assert!(!status.is_endgame(29));
+
+    // The countdown and the result phase are never the endgame.
+    assert!(!GameStatus::new(1, 3, 5, 1000).unwrap().is_endgame(30));
+    assert!(!GameStatus::new(1, 5, 0, 1000).unwrap().is_endgame(30));
+}
+
+#[cfg(test)]
+#[test]
+fn test_stamp_age() {
+    let status = GameStatus::new(1, 4, 420, 1000).unwrap();
+    assert_eq!(status.stamp_age(1000), Some(0));
+    assert_eq!(status.stamp_age(1012), Some(12));
+    assert_eq!(status.stamp_age(999), None);
+
+    assert_eq!(
+        GameStatus::new(1, 3, 5, 1000).unwrap().stamp_age(1002),
+        Some(2)
+    );
+    assert_eq!(
+        GameStatus::new(1, 5, 0, 1000).unwrap().stamp_age(1002),
+        None
+    );
+}
@@ -2,6 +2,39 @@ use crate::private::*;
 
 const SIZE: usize = 13;
 
+/// Highest robot level
+pub const MAX_LEVEL: u8 = 10;
+/// Highest maximum HP of any robot
+pub const MAX_HP: u16 = 1000;
+/// Highest barrel cooling value per second
+pub const MAX_HEAT_COOLING: u16 = 200;
+/// Highest barrel heat limit
+pub const MAX_SHOOTER_HEAT_LIMIT: u16 = 1000;
+/// Highest chassis power limit in W
+pub const MAX_CHASSIS_POWER_LIMIT: u16 = 220;
+
+/// Power output state of the main control module
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PowerOutputs {
+    pub gimbal: bool,
+    pub chassis: bool,
+    pub shooter: bool,
+}
+
+impl Display for PowerOutputs {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let state = |on: bool| if on { "on" } else { "off" };
+        write!(
+            f,
+            "gimbal: {}, chassis: {}, shooter: {}",
+            state(self.gimbal),
+            state(self.chassis),
+            state(self.shooter)
+        )
+    }
+}
+
 /// Main Ctrl Module to Robot
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
@@ -17,6 +50,22 @@ pub struct RobotStatus {
 }
 
 impl RobotStatus {
+    /// Start building a status, e.g. for simulation or forwarding.
+    pub const fn builder() -> RobotStatusBuilder {
+        RobotStatusBuilder {
+            status: RobotStatus {
+                robot_id: 0,
+                robot_level: 1,
+                current_hp: 0,
+                maximum_hp: 0,
+                heat_colling_down: 0,
+                shooter_heat_limit: 0,
+                chassis_power_limit: 0,
+                power_output: 0,
+            },
+        }
+    }
+
     pub const fn robot_id(&self) -> u8 {
         self.robot_id
     }
@@ -56,10 +105,149 @@ impl RobotStatus {
     pub const fn shooter_power_output(&self) -> bool {
         (self.power_output & (1 << 2)) != 0
     }
+
+    pub const fn power_outputs(&self) -> PowerOutputs {
+        PowerOutputs {
+            gimbal: self.gimbal_power_output(),
+            chassis: self.chassis_power_output(),
+            shooter: self.shooter_power_output(),
+        }
+    }
+
+    ///
+    /// Current HP as a fraction of the maximum HP.
+    ///
+    /// Returns 0.0 if the maximum HP is 0, rather than NaN.
+    ///
+    pub const fn hp_fraction(&self) -> f32 {
+        if self.maximum_hp == 0 {
+            return 0.;
+        }
+
+        self.current_hp as f32 / self.maximum_hp as f32
+    }
+
+    /// Whether the HP fraction is below `threshold`, e.g. 0.3 to retreat.
+    pub const fn is_critical(&self, threshold: f32) -> bool {
+        self.hp_fraction() < threshold
+    }
+}
+
+///
+/// Builder for `RobotStatus`.
+///
+/// Limits are clamped to their protocol maximum, the current HP is
+/// clamped to the maximum HP, and `build` rejects values that cannot
+/// describe a robot at all.
+///
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RobotStatusBuilder {
+    status: RobotStatus,
+}
+
+impl RobotStatusBuilder {
+    pub const fn robot_id(mut self, robot_id: u8) -> Self {
+        self.status.robot_id = robot_id;
+        self
+    }
+
+    pub const fn robot_level(mut self, level: u8) -> Self {
+        self.status.robot_level = if level > MAX_LEVEL { MAX_LEVEL } else { level };
+        self
+    }
+
+    pub const fn current_hp(mut self, hp: u16) -> Self {
+        self.status.current_hp = hp;
+        self
+    }
+
+    pub const fn maximum_hp(mut self, hp: u16) -> Self {
+        self.status.maximum_hp = if hp > MAX_HP { MAX_HP } else { hp };
+        self
+    }
+
+    pub const fn heat_colling_down(mut self, cooling: u16) -> Self {
+        self.status.heat_colling_down = if cooling > MAX_HEAT_COOLING {
+            MAX_HEAT_COOLING
+        } else {
+            cooling
+        };
+        self
+    }
+
+    pub const fn shooter_heat_limit(mut self, limit: u16) -> Self {
+        self.status.shooter_heat_limit = if limit > MAX_SHOOTER_HEAT_LIMIT {
+            MAX_SHOOTER_HEAT_LIMIT
+        } else {
+            limit
+        };
+        self
+    }
+
+    pub const fn chassis_power_limit(mut self, limit: u16) -> Self {
+        self.status.chassis_power_limit = if limit > MAX_CHASSIS_POWER_LIMIT {
+            MAX_CHASSIS_POWER_LIMIT
+        } else {
+            limit
+        };
+        self
+    }
+
+    pub const fn power_outputs(mut self, outputs: PowerOutputs) -> Self {
+        self.status.power_output = (outputs.gimbal as u8)
+            | ((outputs.chassis as u8) << 1)
+            | ((outputs.shooter as u8) << 2);
+        self
+    }
+
+    ///
+    /// Finish the status.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EncodeError` with the byte offset of the field if the robot
+    /// id is not a red (1-11) or blue (101-111) robot, or the level is 0.
+    ///
+    pub const fn build(self) -> Result<RobotStatus> {
+        let mut status = self.status;
+
+        if !matches!(status.robot_id, 1..=11 | 101..=111) {
+            return Err(Error::EncodeError { inner: 0 });
+        }
+
+        if status.robot_level == 0 {
+            return Err(Error::EncodeError { inner: 1 });
+        }
+
+        if status.current_hp > status.maximum_hp {
+            status.current_hp = status.maximum_hp;
+        }
+
+        Ok(status)
+    }
+}
+
+impl Display for RobotStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "RobotStatus {{ id: {}, level: {}, hp: {}/{}, cooling: {} /s, heat limit: {}, chassis limit: {} W, {} }}",
+            self.robot_id,
+            self.robot_level,
+            self.current_hp,
+            self.maximum_hp,
+            self.heat_colling_down,
+            self.shooter_heat_limit,
+            self.chassis_power_limit,
+            self.power_outputs()
+        )
+    }
 }
 
 impl Marshaler for RobotStatus {
     const CMD_ID: u16 = 0x0201;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
@@ -68,11 +256,11 @@ impl Marshaler for RobotStatus {
 
         dst[0] = self.robot_id;
         dst[1] = self.robot_level;
-        dst[2..4].copy_from_slice(&self.current_hp.to_le_bytes());
-        dst[4..6].copy_from_slice(&self.maximum_hp.to_le_bytes());
-        dst[6..8].copy_from_slice(&self.heat_colling_down.to_le_bytes());
-        dst[8..10].copy_from_slice(&self.shooter_heat_limit.to_le_bytes());
-        dst[10..12].copy_from_slice(&self.chassis_power_limit.to_le_bytes());
+        le::write_u16_le(dst, 2, self.current_hp);
+        le::write_u16_le(dst, 4, self.maximum_hp);
+        le::write_u16_le(dst, 6, self.heat_colling_down);
+        le::write_u16_le(dst, 8, self.shooter_heat_limit);
+        le::write_u16_le(dst, 10, self.chassis_power_limit);
         dst[12] = self.power_output;
 
         Ok(SIZE)
@@ -85,11 +273,11 @@ impl Marshaler for RobotStatus {
 
         let robot_id = raw[0];
         let robot_level = raw[1];
-        let current_hp = u16::from_le_bytes([raw[2], raw[3]]);
-        let maximum_hp = u16::from_le_bytes([raw[4], raw[5]]);
-        let heat_colling_down = u16::from_le_bytes([raw[6], raw[7]]);
-        let shooter_heat_limit = u16::from_le_bytes([raw[8], raw[9]]);
-        let chassis_power_limit = u16::from_le_bytes([raw[10], raw[11]]);
+        let current_hp = le::read_u16_le(raw, 2);
+        let maximum_hp = le::read_u16_le(raw, 4);
+        let heat_colling_down = le::read_u16_le(raw, 6);
+        let shooter_heat_limit = le::read_u16_le(raw, 8);
+        let chassis_power_limit = le::read_u16_le(raw, 10);
         let power_output = raw[12];
 
         Ok(RobotStatus {
@@ -131,7 +319,99 @@ fn test() {
     assert_eq!(decoded.heat_colling_down, 300);
     assert_eq!(decoded.shooter_heat_limit, 500);
     assert_eq!(decoded.chassis_power_limit, 800);
-    assert_eq!(decoded.gimbal_power_output(), true);
-    assert_eq!(decoded.chassis_power_output(), false);
-    assert_eq!(decoded.shooter_power_output(), true);
+    assert!(decoded.gimbal_power_output());
+    assert!(!decoded.chassis_power_output());
+    assert!(decoded.shooter_power_output());
+}
+
+#[cfg(test)]
+#[test]
+fn test_power_outputs() {
+    let mut buf = [0u8; SIZE];
+    buf[12] = 0b101;
+
+    let status = RobotStatus::unmarshal(&buf).unwrap();
+    let outputs = status.power_outputs();
+    assert_eq!(
+        outputs,
+        PowerOutputs {
+            gimbal: true,
+            chassis: false,
+            shooter: true,
+        }
+    );
+    assert_eq!(outputs.to_string(), "gimbal: on, chassis: off, shooter: on");
+}
+
+#[cfg(test)]
+#[test]
+fn test_builder() {
+    let outputs = PowerOutputs {
+        gimbal: true,
+        chassis: true,
+        shooter: false,
+    };
+
+    let status = RobotStatus::builder()
+        .robot_id(103)
+        .robot_level(12)
+        .maximum_hp(5000)
+        .current_hp(u16::MAX)
+        .heat_colling_down(40)
+        .shooter_heat_limit(u16::MAX)
+        .chassis_power_limit(1000)
+        .power_outputs(outputs)
+        .build()
+        .unwrap();
+
+    assert_eq!(status.robot_level(), MAX_LEVEL);
+    assert_eq!(status.maximum_hp(), MAX_HP);
+    assert_eq!(status.current_hp(), MAX_HP);
+    assert_eq!(status.heat_colling_down(), 40);
+    assert_eq!(status.shooter_heat_limit(), MAX_SHOOTER_HEAT_LIMIT);
+    assert_eq!(status.chassis_power_limit(), MAX_CHASSIS_POWER_LIMIT);
+
+    let mut buf = [0u8; SIZE];
+    status.marshal(&mut buf).unwrap();
+    let decoded = RobotStatus::unmarshal(&buf).unwrap();
+    assert_eq!(decoded.robot_id(), 103);
+    assert_eq!(decoded.maximum_hp(), MAX_HP);
+    assert_eq!(decoded.chassis_power_limit(), MAX_CHASSIS_POWER_LIMIT);
+    assert_eq!(decoded.power_outputs(), outputs);
+
+    let bad_id = RobotStatus::builder().robot_id(50).build();
+    assert!(matches!(bad_id, Err(Error::EncodeError { inner: 0 })));
+
+    let bad_level = RobotStatus::builder().robot_id(1).robot_level(0).build();
+    assert!(matches!(bad_level, Err(Error::EncodeError { inner: 1 })));
+}
+
+#[cfg(test)]
+#[test]
+fn test_hp_fraction() {
+    let status = RobotStatus::builder()
+        .robot_id(3)
+        .robot_level(1)
+        .maximum_hp(200)
+        .current_hp(50)
+        .build()
+        .unwrap();
+
+    assert_eq!(status.hp_fraction(), 0.25);
+    assert!(status.is_critical(0.3));
+    assert!(!status.is_critical(0.25));
+
+    let full = RobotStatus::builder()
+        .robot_id(3)
+        .robot_level(1)
+        .maximum_hp(200)
+        .current_hp(200)
+        .build()
+        .unwrap();
+    assert_eq!(full.hp_fraction(), 1.);
+
+    let unknown = RobotStatus::unmarshal(&[3, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+    assert_eq!(unknown.maximum_hp(), 0);
+    assert_eq!(unknown.hp_fraction(), 0.);
+    assert!(unknown.is_critical(0.3));
 }
@@ -3,7 +3,7 @@ use crate::private::*;
 const SIZE: usize = 3;
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Level {
     YellowCardBoth = 1,
@@ -37,6 +37,7 @@ impl RefereeWarning {
 
 impl Marshaler for RefereeWarning {
     const CMD_ID: u16 = 0x0104;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < SIZE {
@@ -55,13 +56,8 @@ impl Marshaler for RefereeWarning {
             return Err(Error::InvalidDataLength { expected: SIZE });
         }
 
-        let level = match raw[0] {
-            1 => Level::YellowCardBoth,
-            2 => Level::YellowCard,
-            3 => Level::RedCard,
-            4 => Level::Loss,
-
-            _ => return Err(Error::DecodeError { at: 0 }),
+        let Some(level) = Level::from_repr(raw[0]) else {
+            return Err(Error::DecodeError { at: 0 });
         };
 
         let robot_id = raw[1];
@@ -93,3 +89,14 @@ fn test() {
     assert_eq!(decoded.robot_id, 5);
     assert_eq!(decoded.count, 2);
 }
+
+#[cfg(test)]
+#[test]
+fn test_invalid_discriminant() {
+    for raw in [0, 5, 0xFF] {
+        assert!(matches!(
+            RefereeWarning::unmarshal(&[raw, 0, 0]),
+            Err(Error::DecodeError { at: 0 })
+        ));
+    }
+}
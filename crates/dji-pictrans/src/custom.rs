@@ -1,20 +1,144 @@
 use crate::private::*;
 
+const SIZE: usize = 30;
+
 /// Custom Robot Controller to Controlled Robot
 /// 30 Bytes Max
 /// frequency: 30Hz
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-pub struct Custom2Robot {}
+pub struct Custom2Robot {
+    data: [u8; SIZE],
+}
+
+impl Custom2Robot {
+    /// Payload size in bytes
+    pub const SIZE: usize = SIZE;
+
+    pub const fn new(data: [u8; SIZE]) -> Self {
+        Self { data }
+    }
+
+    pub const fn data(&self) -> &[u8; SIZE] {
+        &self.data
+    }
+}
 
 impl Marshaler for Custom2Robot {
     const CMD_ID: u16 = 0x0302;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
-        todo!()
+        if dst.len() < SIZE {
+            return Err(Error::BufferTooSmall { need: SIZE });
+        }
+
+        dst[..SIZE].copy_from_slice(&self.data);
+
+        Ok(SIZE)
     }
 
     fn unmarshal(raw: &[u8]) -> Result<Self> {
-        todo!()
+        if raw.len() != SIZE {
+            return Err(Error::InvalidDataLength { expected: SIZE });
+        }
+
+        let mut data = [0u8; SIZE];
+        data.copy_from_slice(raw);
+
+        Ok(Custom2Robot { data })
+    }
+}
+
+/// Borrowed view of a `Custom2Robot` payload, without the copy
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Custom2RobotRef<'a>(&'a [u8; SIZE]);
+
+impl<'a> Custom2RobotRef<'a> {
+    pub const fn data(&self) -> &'a [u8; SIZE] {
+        self.0
+    }
+
+    /// Copy into an owned `Custom2Robot` for storage
+    pub const fn to_owned(&self) -> Custom2Robot {
+        Custom2Robot::new(*self.0)
+    }
+}
+
+impl<'a> MarshalerRef<'a> for Custom2RobotRef<'a> {
+    fn unmarshal_ref(raw: &'a [u8]) -> Result<Self> {
+        raw.try_into()
+            .map(Self)
+            .map_err(|_| Error::InvalidDataLength { expected: SIZE })
+    }
+}
+
+impl<'a> TryFrom<&RawFrame<'a>> for Custom2RobotRef<'a> {
+    type Error = Error;
+
+    fn try_from(frame: &RawFrame<'a>) -> Result<Self> {
+        if frame.cmd_id() != Custom2Robot::CMD_ID {
+            return Err(Error::DecodeError { at: 0 });
+        }
+
+        Self::unmarshal_ref(frame.payload())
     }
 }
+
+#[cfg(test)]
+#[test]
+fn test() {
+    let mut data = [0u8; SIZE];
+    data.iter_mut().enumerate().for_each(|(i, x)| *x = i as u8);
+    let custom = Custom2Robot::new(data);
+
+    let mut buf = [0u8; SIZE + 10];
+    let sz = custom.marshal(&mut buf).unwrap();
+    assert_eq!(sz, SIZE);
+
+    let decoded = Custom2Robot::unmarshal(&buf[..SIZE]).unwrap();
+    assert_eq!(decoded.data(), &data);
+
+    assert!(matches!(
+        Custom2Robot::unmarshal(&buf[..5]),
+        Err(Error::InvalidDataLength { expected: SIZE })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_layout() {
+    // Pinned by the `commu` pictrans task
+    assert_eq!(Custom2Robot::CMD_ID, 0x0302);
+    assert_eq!(Custom2Robot::SIZE, 30);
+}
+
+#[cfg(test)]
+#[test]
+fn test_ref() {
+    use dji_frame::{DjiValidator, Messager};
+
+    let mut data = [0u8; SIZE];
+    data.iter_mut()
+        .enumerate()
+        .for_each(|(i, x)| *x = 2 * i as u8);
+
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buf = [0u8; 64];
+    let size = msger.pack(&Custom2Robot::new(data), &mut buf).unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+
+    let view = Custom2RobotRef::try_from(&frame).unwrap();
+    assert_eq!(view.data(), &data);
+    assert!(core::ptr::eq(
+        view.data().as_ptr(),
+        frame.payload().as_ptr()
+    ));
+    assert_eq!(view.to_owned().data(), &data);
+
+    assert!(matches!(
+        Custom2RobotRef::unmarshal_ref(&buf[..SIZE - 1]),
+        Err(Error::InvalidDataLength { expected: SIZE })
+    ));
+}
@@ -0,0 +1,189 @@
+use crate::private::Error;
+use crate::{Custom2Robot, KeyboardMouse, RemoteControl};
+
+use dji_frame::heapless::Vec;
+use dji_frame::{DjiValidator, Messager};
+
+dji_frame::dispatch! {
+    /// Any picture-transmission link message
+    #[derive(Debug, Clone, Copy)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub enum PicMessage {
+        Custom(Custom2Robot),
+        Remote(RemoteControl),
+        Keyboard(KeyboardMouse),
+    }
+
+    ///
+    /// Decode a frame received on the picture-transmission link.
+    ///
+    /// Returns `Ok(None)` for command IDs not defined in this crate,
+    /// and the payload error for a known command ID with a bad payload.
+    ///
+    pub fn dispatch;
+}
+
+///
+/// Input dropped by [`process_chunk`] instead of decoded.
+///
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Rejected<'a> {
+    /// Bytes drained without forming a valid frame
+    Garbage(&'a [u8], Error),
+    /// A valid frame whose command ID is not defined in this crate
+    Unknown(u16),
+    /// A known command ID with a bad payload
+    Invalid(u16, Error),
+}
+
+///
+/// Extract and decode every complete frame at the front of `buf`.
+///
+/// Decoded frames and garbage are drained; an incomplete trailing
+/// frame is kept for the next chunk. Everything dropped, garbage, unknown
+/// command IDs or bad payloads, is passed to `rejected` before it is
+/// drained. Stops early once `M` messages have been collected, leaving
+/// the rest in `buf`.
+///
+pub fn process_chunk<const N: usize, const M: usize>(
+    buf: &mut Vec<u8, N>,
+    mut rejected: impl FnMut(Rejected<'_>),
+) -> Vec<PicMessage, M> {
+    let msger: Messager<DjiValidator> = Messager::new(0);
+    let mut messages = Vec::new();
+
+    while !messages.is_full() {
+        let drain = match msger.unpack(buf) {
+            Ok((frame, size)) => {
+                match dispatch(&frame) {
+                    // Safety: `messages` is not full, so Unwrap is safe.
+                    Ok(Some(msg)) => messages.push(msg).unwrap(),
+                    Ok(None) => rejected(Rejected::Unknown(frame.cmd_id())),
+                    Err(e) => rejected(Rejected::Invalid(frame.cmd_id(), e)),
+                }
+                size
+            }
+
+            Err(e) => {
+                let skip = e.skip();
+                if skip > 0 {
+                    rejected(Rejected::Garbage(&buf[..skip], e));
+                }
+                skip
+            }
+        };
+
+        if drain == 0 {
+            break;
+        }
+        buf.drain(..drain);
+    }
+
+    messages
+}
+
+#[cfg(test)]
+#[test]
+fn test_dispatch() {
+    use crate::private::Marshaler;
+    use dji_frame::{DjiValidator, Messager};
+
+    let mut raw = [0u8; 12];
+    raw[0..2].copy_from_slice(&(-42i16).to_le_bytes());
+    raw[6] = 1;
+    let rc = RemoteControl::unmarshal(&raw).unwrap();
+
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut buf = [0u8; 64];
+    let size = msger.pack(&rc, &mut buf).unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+
+    match dispatch(&frame) {
+        Ok(Some(PicMessage::Remote(rc))) => {
+            assert_eq!(rc.mouse_vx(), -42);
+            assert!(rc.left_button_pressed());
+        }
+        other => panic!("Unexpected Dispatch: {other:?}"),
+    }
+
+    let size = msger
+        .pack(&dji_frame::Raw::<0x0001, 2>([0; 2]), &mut buf)
+        .unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+    assert!(matches!(dispatch(&frame), Ok(None)));
+
+    let size = msger
+        .pack(&dji_frame::Raw::<0x0304, 2>([0; 2]), &mut buf)
+        .unwrap();
+    let (frame, _) = msger.unpack(&buf[..size]).unwrap();
+    assert!(dispatch(&frame).is_err());
+}
+
+#[cfg(test)]
+#[test]
+fn test_process_chunk() {
+    use crate::private::Marshaler;
+
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut stream = [0u8; 128];
+    let mut len = msger
+        .pack(&RemoteControl::unmarshal(&[0; 12]).unwrap(), &mut stream)
+        .unwrap();
+    let split = len + 10;
+    len += msger
+        .pack(&Custom2Robot::new([7; 30]), &mut stream[len..])
+        .unwrap();
+    stream[len..len + 3].copy_from_slice(&[0x11, 0x22, 0xA5]);
+    len += 3;
+
+    let mut buf = Vec::<u8, 128>::new();
+    let mut garbage = Vec::<u8, 16>::new();
+    let mut collect = |r: Rejected<'_>| match r {
+        Rejected::Garbage(bytes, _) => garbage.extend_from_slice(bytes).unwrap(),
+        other => panic!("Unexpected Rejection: {other:?}"),
+    };
+
+    // The second frame is split across reads.
+    buf.extend_from_slice(&stream[..split]).unwrap();
+    let messages = process_chunk::<128, 4>(&mut buf, &mut collect);
+    assert!(matches!(messages[..], [PicMessage::Remote(_)]));
+    assert_eq!(&buf[..], &stream[split - 10..split]);
+
+    // Junk is dropped, a trailing SOF is kept.
+    buf.extend_from_slice(&stream[split..len]).unwrap();
+    let messages = process_chunk::<128, 4>(&mut buf, &mut collect);
+    match messages[..] {
+        [PicMessage::Custom(x)] => assert_eq!(x.data(), &[7; 30]),
+        _ => panic!("Unexpected Messages: {messages:?}"),
+    }
+    assert_eq!(&buf[..], &[0xA5]);
+    assert_eq!(&garbage[..], &[0x11, 0x22]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_process_chunk_rejected() {
+    let mut msger: Messager<DjiValidator> = Messager::new(0);
+    let mut stream = [0u8; 64];
+    let mut len = msger
+        .pack(&dji_frame::Raw::<0x0001, 2>([0; 2]), &mut stream)
+        .unwrap();
+    len += msger
+        .pack(&dji_frame::Raw::<0x0304, 2>([0; 2]), &mut stream[len..])
+        .unwrap();
+
+    let mut buf = Vec::<u8, 64>::new();
+    buf.extend_from_slice(&stream[..len]).unwrap();
+
+    let mut seen = Vec::<u16, 4>::new();
+    let messages = process_chunk::<64, 4>(&mut buf, |r| match r {
+        Rejected::Unknown(id) => seen.push(id).unwrap(),
+        Rejected::Invalid(id, _) => seen.push(id | 0x8000).unwrap(),
+        other => panic!("Unexpected Rejection: {other:?}"),
+    });
+
+    // Both frames are drained, and reported as unknown and invalid.
+    assert!(messages.is_empty() && buf.is_empty());
+    assert_eq!(&seen[..], &[0x0001, 0x8304]);
+}
@@ -0,0 +1,34 @@
+/// Keyboard key, valued by its bit in the key bitmap
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Key {
+    W = 0,
+    S = 1,
+    A = 2,
+    D = 3,
+    Shift = 4,
+    Ctrl = 5,
+    Q = 6,
+    E = 7,
+    R = 8,
+    F = 9,
+    G = 10,
+    Z = 11,
+    X = 12,
+    C = 13,
+    V = 14,
+    B = 15,
+}
+
+impl Key {
+    /// Mask of this key in the key bitmap
+    pub const fn mask(self) -> u16 {
+        1 << self as u8
+    }
+
+    /// Check whether this key is set in `bitmap`
+    pub const fn is_pressed(self, bitmap: u16) -> bool {
+        (bitmap & self.mask()) != 0
+    }
+}
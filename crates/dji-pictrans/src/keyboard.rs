@@ -0,0 +1,112 @@
+use crate::private::*;
+
+const SIZE: usize = 8;
+
+/// Custom Client to Robot
+/// frequency: 30Hz
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct KeyboardMouse {
+    keyboard_v: u16,
+    mouse_x: u16,     // 12 bits
+    left_button: u8,  // 4 bits
+    mouse_y: u16,     // 12 bits
+    right_button: u8, // 4 bits
+    _reserved: u16,
+}
+
+impl KeyboardMouse {
+    pub const fn pressed(&self, key: Key) -> bool {
+        key.is_pressed(self.keyboard_v)
+    }
+
+    /// Raw key bitmap, see `Key`
+    pub const fn keyboard(&self) -> u16 {
+        self.keyboard_v
+    }
+
+    pub const fn mouse_x(&self) -> u16 {
+        self.mouse_x
+    }
+
+    pub const fn mouse_y(&self) -> u16 {
+        self.mouse_y
+    }
+
+    pub const fn left_button_pressed(&self) -> bool {
+        self.left_button != 0
+    }
+
+    pub const fn right_button_pressed(&self) -> bool {
+        self.right_button != 0
+    }
+}
+
+impl Marshaler for KeyboardMouse {
+    const CMD_ID: u16 = 0x0306;
+    const MARSHALED_LEN: Option<usize> = Some(SIZE);
+
+    fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
+        if dst.len() < SIZE {
+            return Err(Error::BufferTooSmall { need: SIZE });
+        }
+
+        let x = (self.mouse_x & 0xFFF) | ((self.left_button as u16 & 0xF) << 12);
+        let y = (self.mouse_y & 0xFFF) | ((self.right_button as u16 & 0xF) << 12);
+
+        dst[0..2].copy_from_slice(&self.keyboard_v.to_le_bytes());
+        dst[2..4].copy_from_slice(&x.to_le_bytes());
+        dst[4..6].copy_from_slice(&y.to_le_bytes());
+        dst[6..8].copy_from_slice(&self._reserved.to_le_bytes());
+
+        Ok(SIZE)
+    }
+
+    fn unmarshal(raw: &[u8]) -> Result<Self> {
+        if raw.len() != SIZE {
+            return Err(Error::InvalidDataLength { expected: SIZE });
+        }
+
+        let keyboard_v = u16::from_le_bytes([raw[0], raw[1]]);
+        let x = u16::from_le_bytes([raw[2], raw[3]]);
+        let y = u16::from_le_bytes([raw[4], raw[5]]);
+        let _reserved = u16::from_le_bytes([raw[6], raw[7]]);
+
+        Ok(KeyboardMouse {
+            keyboard_v,
+            mouse_x: x & 0xFFF,
+            left_button: (x >> 12) as u8,
+            mouse_y: y & 0xFFF,
+            right_button: (y >> 12) as u8,
+            _reserved,
+        })
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test() {
+    let km = KeyboardMouse {
+        keyboard_v: Key::W.mask() | Key::Shift.mask() | Key::B.mask(),
+        mouse_x: 1920,
+        left_button: 1,
+        mouse_y: 1080,
+        right_button: 0,
+        _reserved: 0,
+    };
+
+    let mut buf = [0u8; SIZE + 4];
+    let sz = km.marshal(&mut buf).unwrap();
+    assert_eq!(sz, SIZE);
+    assert_eq!(&buf[2..4], &(1920u16 | 1 << 12).to_le_bytes());
+
+    let decoded = KeyboardMouse::unmarshal(&buf[..SIZE]).unwrap();
+    assert!(decoded.pressed(Key::W));
+    assert!(decoded.pressed(Key::Shift));
+    assert!(decoded.pressed(Key::B));
+    assert!(!decoded.pressed(Key::S));
+    assert_eq!(decoded.mouse_x(), 1920);
+    assert_eq!(decoded.mouse_y(), 1080);
+    assert!(decoded.left_button_pressed());
+    assert!(!decoded.right_button_pressed());
+}
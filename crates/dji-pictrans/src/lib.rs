@@ -1,6 +1,20 @@
 #![cfg_attr(not(test), no_std)]
 
-pub use custom::Custom2Robot;
+use dji_frame::Marshaler as _;
+
+/// Command IDs of all messages in this crate, checked for collisions.
+pub const CMD_IDS: &[u16] = &[
+    custom::Custom2Robot::CMD_ID,
+    remote::RemoteControl::CMD_ID,
+    keyboard::KeyboardMouse::CMD_ID,
+];
+
+const _: () = dji_frame::assert_unique_ids(CMD_IDS);
+
+pub use custom::{Custom2Robot, Custom2RobotRef};
+pub use dispatch::{PicMessage, Rejected, dispatch, process_chunk};
+pub use key::Key;
+pub use keyboard::KeyboardMouse;
 pub use remote::RemoteControl;
 
 /// 0x0302 - Custom to Robot
@@ -9,12 +23,20 @@ mod custom;
 /// 0x0304 - Remote Control
 mod remote;
 
+/// 0x0306 - Custom Client Keyboard Mouse
+mod keyboard;
+
+mod key;
+
+mod dispatch;
+
 mod private {
     #[allow(unused_imports)]
     #[cfg(feature = "defmt")]
     pub use ::defmt::{debug, error, info, trace, warn};
 
-    pub use dji_frame::{Error, Marshaler, Result};
+    pub use crate::key::Key;
+    pub use dji_frame::{Error, Marshaler, MarshalerRef, RawFrame, Result};
 }
 
 #[cfg(test)]
@@ -24,4 +46,5 @@ fn test_command_id() {
 
     assert_eq!(custom::Custom2Robot::CMD_ID, 0x0302);
     assert_eq!(remote::RemoteControl::CMD_ID, 0x0304);
+    assert_eq!(keyboard::KeyboardMouse::CMD_ID, 0x0306);
 }
@@ -37,73 +37,33 @@ impl RemoteControl {
 }
 
 impl RemoteControl {
-    pub const fn keyboard_w(&self) -> bool {
-        (self.keyboard_v & (1 << 0)) != 0
-    }
-
-    pub const fn keyboard_s(&self) -> bool {
-        (self.keyboard_v & (1 << 1)) != 0
-    }
-
-    pub const fn keyboard_a(&self) -> bool {
-        (self.keyboard_v & (1 << 2)) != 0
-    }
-
-    pub const fn keyboard_d(&self) -> bool {
-        (self.keyboard_v & (1 << 3)) != 0
-    }
-
-    pub const fn keyboard_shift(&self) -> bool {
-        (self.keyboard_v & (1 << 4)) != 0
-    }
-
-    pub const fn keyboard_ctrl(&self) -> bool {
-        (self.keyboard_v & (1 << 5)) != 0
-    }
-
-    pub const fn keyboard_q(&self) -> bool {
-        (self.keyboard_v & (1 << 6)) != 0
-    }
-
-    pub const fn keyboard_e(&self) -> bool {
-        (self.keyboard_v & (1 << 7)) != 0
-    }
-
-    pub const fn keyboard_r(&self) -> bool {
-        (self.keyboard_v & (1 << 8)) != 0
-    }
-
-    pub const fn keyboard_f(&self) -> bool {
-        (self.keyboard_v & (1 << 9)) != 0
-    }
-
-    pub const fn keyboard_g(&self) -> bool {
-        (self.keyboard_v & (1 << 10)) != 0
-    }
-
-    pub const fn keyboard_z(&self) -> bool {
-        (self.keyboard_v & (1 << 11)) != 0
-    }
-
-    pub const fn keyboard_x(&self) -> bool {
-        (self.keyboard_v & (1 << 12)) != 0
-    }
-
-    pub const fn keyboard_c(&self) -> bool {
-        (self.keyboard_v & (1 << 13)) != 0
-    }
-
-    pub const fn keyboard_v(&self) -> bool {
-        (self.keyboard_v & (1 << 14)) != 0
-    }
-
-    pub const fn keyboard_b(&self) -> bool {
-        (self.keyboard_v & (1 << 15)) != 0
-    }
+    pub const fn pressed(&self, key: Key) -> bool {
+        key.is_pressed(self.keyboard_v)
+    }
+
+    dji_frame::bitflags_accessors!(keyboard_v => {
+        pub keyboard_w: Key::W as u8,
+        pub keyboard_s: Key::S as u8,
+        pub keyboard_a: Key::A as u8,
+        pub keyboard_d: Key::D as u8,
+        pub keyboard_shift: Key::Shift as u8,
+        pub keyboard_ctrl: Key::Ctrl as u8,
+        pub keyboard_q: Key::Q as u8,
+        pub keyboard_e: Key::E as u8,
+        pub keyboard_r: Key::R as u8,
+        pub keyboard_f: Key::F as u8,
+        pub keyboard_g: Key::G as u8,
+        pub keyboard_z: Key::Z as u8,
+        pub keyboard_x: Key::X as u8,
+        pub keyboard_c: Key::C as u8,
+        pub keyboard_v: Key::V as u8,
+        pub keyboard_b: Key::B as u8,
+    });
 }
 
 impl Marshaler for RemoteControl {
     const CMD_ID: u16 = 0x0304;
+    const MARSHALED_LEN: Option<usize> = Some(12);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
         if dst.len() < 12 {
@@ -169,22 +129,61 @@ fn test() {
     assert_eq!(decoded.mouse_vx(), 100);
     assert_eq!(decoded.mouse_vy(), -100);
     assert_eq!(decoded.mouse_vz(), 50);
-    assert_eq!(decoded.left_button_pressed(), true);
-    assert_eq!(decoded.right_button_pressed(), false);
-    assert_eq!(decoded.keyboard_w(), false);
-    assert_eq!(decoded.keyboard_s(), true);
-    assert_eq!(decoded.keyboard_a(), false);
-    assert_eq!(decoded.keyboard_d(), true);
-    assert_eq!(decoded.keyboard_shift(), true);
-    assert_eq!(decoded.keyboard_ctrl(), false);
-    assert_eq!(decoded.keyboard_q(), false);
-    assert_eq!(decoded.keyboard_e(), false);
-    assert_eq!(decoded.keyboard_r(), false);
-    assert_eq!(decoded.keyboard_f(), false);
-    assert_eq!(decoded.keyboard_g(), false);
-    assert_eq!(decoded.keyboard_z(), false);
-    assert_eq!(decoded.keyboard_x(), false);
-    assert_eq!(decoded.keyboard_c(), false);
-    assert_eq!(decoded.keyboard_v(), false);
-    assert_eq!(decoded.keyboard_b(), false);
+    assert!(decoded.left_button_pressed());
+    assert!(!decoded.right_button_pressed());
+    assert!(!decoded.keyboard_w());
+    assert!(decoded.keyboard_s());
+    assert!(!decoded.keyboard_a());
+    assert!(decoded.keyboard_d());
+    assert!(decoded.keyboard_shift());
+    assert!(!decoded.keyboard_ctrl());
+    assert!(!decoded.keyboard_q());
+    assert!(!decoded.keyboard_e());
+    assert!(!decoded.keyboard_r());
+    assert!(!decoded.keyboard_f());
+    assert!(!decoded.keyboard_g());
+    assert!(!decoded.keyboard_z());
+    assert!(!decoded.keyboard_x());
+    assert!(!decoded.keyboard_c());
+    assert!(!decoded.keyboard_v());
+    assert!(!decoded.keyboard_b());
+}
+
+#[cfg(test)]
+#[test]
+fn test_keyboard_bits() {
+    for bitmap in (0..16).map(|bit| 1u16 << bit).chain([0, 0xFFFF, 0xA5C3]) {
+        let rc = RemoteControl {
+            mouse_x: 0,
+            mouse_y: 0,
+            mouse_z: 0,
+            left_button: false,
+            right_button: false,
+            keyboard_v: bitmap,
+            _reserved: 0,
+        };
+
+        let accessors = [
+            (rc.keyboard_w(), Key::W),
+            (rc.keyboard_s(), Key::S),
+            (rc.keyboard_a(), Key::A),
+            (rc.keyboard_d(), Key::D),
+            (rc.keyboard_shift(), Key::Shift),
+            (rc.keyboard_ctrl(), Key::Ctrl),
+            (rc.keyboard_q(), Key::Q),
+            (rc.keyboard_e(), Key::E),
+            (rc.keyboard_r(), Key::R),
+            (rc.keyboard_f(), Key::F),
+            (rc.keyboard_g(), Key::G),
+            (rc.keyboard_z(), Key::Z),
+            (rc.keyboard_x(), Key::X),
+            (rc.keyboard_c(), Key::C),
+            (rc.keyboard_v(), Key::V),
+            (rc.keyboard_b(), Key::B),
+        ];
+
+        for (pressed, key) in accessors {
+            assert_eq!(pressed, key.is_pressed(bitmap), "{key:?} in {bitmap:#06x}");
+        }
+    }
 }
@@ -1,15 +1,144 @@
 use crate::private::*;
 
-#[repr(u16)]
+/// Content IDs reserved for robot-to-robot custom data
+pub const CUSTOM_IDS: RangeInclusive<u16> = 0x0200..=0x02FF;
+
+/// Maximum data length of an interaction
+const MAX_DATA_LEN: usize = 112;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Command {
-    DeleteLayer = 0x0100,
-    DrawOneFigure = 0x0101,
-    DrawTwoFigures = 0x0102,
-    DrawFiveFigures = 0x0103,
-    DrawSevenFigures = 0x0104,
-    DrawCharacter = 0x0110,
+    DeleteLayer,
+    DrawOneFigure,
+    DrawTwoFigures,
+    DrawFiveFigures,
+    DrawSevenFigures,
+    DrawCharacter,
+    /// Robot-to-robot data with a content id in `CUSTOM_IDS`
+    Custom(u16),
+}
+
+impl Command {
+    /// Raw content id
+    pub const fn id(&self) -> u16 {
+        match self {
+            Self::DeleteLayer => 0x0100,
+            Self::DrawOneFigure => 0x0101,
+            Self::DrawTwoFigures => 0x0102,
+            Self::DrawFiveFigures => 0x0103,
+            Self::DrawSevenFigures => 0x0104,
+            Self::DrawCharacter => 0x0110,
+            Self::Custom(id) => *id,
+        }
+    }
+
+    /// Content id from its raw value, `None` if unknown.
+    pub const fn from_id(id: u16) -> Option<Self> {
+        match id {
+            0x0100 => Some(Self::DeleteLayer),
+            0x0101 => Some(Self::DrawOneFigure),
+            0x0102 => Some(Self::DrawTwoFigures),
+            0x0103 => Some(Self::DrawFiveFigures),
+            0x0104 => Some(Self::DrawSevenFigures),
+            0x0110 => Some(Self::DrawCharacter),
+            0x0200..=0x02FF => Some(Self::Custom(id)),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Size in bytes of the data this content id carries.
+    ///
+    /// Custom data has no fixed size, so this is its maximum.
+    ///
+    pub const fn data_len(&self) -> usize {
+        match self {
+            Self::DeleteLayer => 2,
+            Self::DrawOneFigure => 15,
+            Self::DrawTwoFigures => 30,
+            Self::DrawFiveFigures => 75,
+            Self::DrawSevenFigures => 105,
+            Self::DrawCharacter => 45,
+            Self::Custom(_) => MAX_DATA_LEN,
+        }
+    }
+
+    /// Number of figures carried by a figure drawing content id.
+    pub const fn figure_count(&self) -> Option<usize> {
+        match self {
+            Self::DrawOneFigure => Some(1),
+            Self::DrawTwoFigures => Some(2),
+            Self::DrawFiveFigures => Some(5),
+            Self::DrawSevenFigures => Some(7),
+            _ => None,
+        }
+    }
+
+    /// Check that `N` bytes of data fit this content id.
+    const fn validate<const N: usize>(&self) -> Result<()> {
+        if let Self::Custom(id) = self {
+            if *id < *CUSTOM_IDS.start() || *id > *CUSTOM_IDS.end() {
+                return Err(Error::EncodeError { inner: 0 });
+            }
+
+            if N > MAX_DATA_LEN {
+                return Err(Error::InputTooLarge { max: MAX_DATA_LEN });
+            }
+
+            return Ok(());
+        }
+
+        if self.data_len() != N {
+            return Err(Error::InvalidDataLength {
+                expected: self.data_len(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Robot ID as assigned by the referee system
+#[repr(u16)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RobotId {
+    RedHero = 1,
+    RedEngineer = 2,
+    RedStandard3 = 3,
+    RedStandard4 = 4,
+    RedStandard5 = 5,
+    RedAerial = 6,
+    RedSentry = 7,
+    BlueHero = 101,
+    BlueEngineer = 102,
+    BlueStandard3 = 103,
+    BlueStandard4 = 104,
+    BlueStandard5 = 105,
+    BlueAerial = 106,
+    BlueSentry = 107,
+}
+
+impl RobotId {
+    /// Raw robot ID
+    pub const fn id(self) -> u16 {
+        self as u16
+    }
+
+    /// ID of the operator client bound to this robot,
+    /// which is the receiver for UI uploads.
+    ///
+    /// Note: the sentry has no operator client.
+    pub const fn client_id(self) -> u16 {
+        0x0100 + self as u16
+    }
+}
+
+impl From<RobotId> for u16 {
+    fn from(id: RobotId) -> Self {
+        id.id()
+    }
 }
 
 pub trait AsCommand<const N: usize> {
@@ -27,32 +156,83 @@ pub struct Interaction<const N: usize> {
     data: [u8; N],
 }
 
+/// Interaction deleting a layer
+pub type DeleteLayerMsg = Interaction<2>;
+/// Interaction drawing one figure
+pub type DrawOne = Interaction<15>;
+/// Interaction drawing two figures
+pub type DrawTwo = Interaction<30>;
+/// Interaction drawing five figures
+pub type DrawFive = Interaction<75>;
+/// Interaction drawing seven figures
+pub type DrawSeven = Interaction<105>;
+/// Interaction drawing a character
+pub type DrawChar = Interaction<45>;
+
+// Each alias carries exactly the data of its content id.
+const _: () = {
+    assert!(Command::DeleteLayer.validate::<2>().is_ok());
+    assert!(Command::DrawOneFigure.validate::<15>().is_ok());
+    assert!(Command::DrawTwoFigures.validate::<30>().is_ok());
+    assert!(Command::DrawFiveFigures.validate::<75>().is_ok());
+    assert!(Command::DrawSevenFigures.validate::<105>().is_ok());
+    assert!(Command::DrawCharacter.validate::<45>().is_ok());
+    assert!(105 <= MAX_DATA_LEN);
+};
+
 impl<const N: usize> Interaction<N> {
-    pub fn new(sender: u16, receiver: u16, option: impl AsCommand<N>) -> Self {
+    pub fn new(
+        sender: impl Into<u16>,
+        receiver: impl Into<u16>,
+        option: impl AsCommand<N>,
+    ) -> Result<Self> {
         let cmd_id = option.as_command();
+        cmd_id.validate::<N>()?;
+
         let data = option.as_data();
-        Self {
+        Ok(Self {
             cmd_id,
-            sender,
-            receiver,
+            sender: sender.into(),
+            receiver: receiver.into(),
             data,
+        })
+    }
+
+    /// UI upload from `robot` to its own operator client.
+    pub fn to_client(robot: RobotId, option: impl AsCommand<N>) -> Result<Self> {
+        Self::new(robot, robot.client_id(), option)
+    }
+
+    /// Copy with `sender` and `receiver` exchanged, to echo it back.
+    pub const fn swapped(&self) -> Self {
+        Self {
+            sender: self.receiver,
+            receiver: self.sender,
+            ..*self
         }
     }
+
+    /// Readdress the interaction to `receiver`.
+    pub fn with_target(mut self, receiver: impl Into<u16>) -> Self {
+        self.receiver = receiver.into();
+        self
+    }
 }
 
 impl<const N: usize> Marshaler for Interaction<N> {
     const CMD_ID: u16 = 0x0301;
+    const MARSHALED_LEN: Option<usize> = Some(N + 6);
 
     fn marshal(&self, dst: &mut [u8]) -> Result<usize> {
-        if dst.len() < N + 6 {
-            return Err(Error::BufferTooSmall { need: N + 6 });
+        if N > MAX_DATA_LEN {
+            return Err(Error::InputTooLarge { max: MAX_DATA_LEN });
         }
 
-        if N > 112 {
-            return Err(Error::InputTooLarge { max: 112 });
+        if dst.len() < N + 6 {
+            return Err(Error::BufferTooSmall { need: N + 6 });
         }
 
-        let cmd_id = self.cmd_id as u16;
+        let cmd_id = self.cmd_id.id();
 
         dst[0..2].copy_from_slice(&cmd_id.to_le_bytes());
         dst[2..4].copy_from_slice(&self.sender.to_le_bytes());
@@ -63,25 +243,20 @@ impl<const N: usize> Marshaler for Interaction<N> {
     }
 
     fn unmarshal(raw: &[u8]) -> Result<Self> {
-        if raw.len() != N + 6 {
-            return Err(Error::InvalidDataLength {
-                expected: N + 6 - raw.len(),
-            });
+        if N > MAX_DATA_LEN {
+            return Err(Error::InputTooLarge { max: MAX_DATA_LEN });
         }
 
-        let cmd_id = match u16::from_le_bytes([raw[0], raw[1]]) {
-            0x0100 => Command::DeleteLayer,
-            0x0101 => Command::DrawOneFigure,
-            0x0102 => Command::DrawTwoFigures,
-            0x0103 => Command::DrawFiveFigures,
-            0x0104 => Command::DrawSevenFigures,
-            0x0110 => Command::DrawCharacter,
+        if raw.len() != N + 6 {
+            return Err(Error::InvalidDataLength { expected: N + 6 });
+        }
 
-            _ => {
-                return Err(Error::DecodeError { at: 1 });
-            }
+        let Some(cmd_id) = Command::from_id(u16::from_le_bytes([raw[0], raw[1]])) else {
+            return Err(Error::DecodeError { at: 1 });
         };
 
+        cmd_id.validate::<N>()?;
+
         let sender = u16::from_le_bytes([raw[2], raw[3]]);
         let receiver = u16::from_le_bytes([raw[4], raw[5]]);
         let mut data = [0u8; N];
@@ -99,20 +274,193 @@ impl<const N: usize> Marshaler for Interaction<N> {
 #[cfg(test)]
 #[test]
 fn test() {
-    let interaction: Interaction<4> = Interaction {
+    let interaction: Interaction<15> = Interaction {
         cmd_id: Command::DrawOneFigure,
         sender: 0x1234,
         receiver: 0x5678,
-        data: [1, 2, 3, 4],
+        data: [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
     };
 
-    let mut buf = [0u8; 10];
+    let mut buf = [0u8; 21];
     let size = interaction.marshal(&mut buf).unwrap();
-    assert_eq!(size, 10);
+    assert_eq!(size, 21);
 
-    let decoded = Interaction::<4>::unmarshal(&buf).unwrap();
+    let decoded = Interaction::<15>::unmarshal(&buf).unwrap();
     assert_eq!(decoded.cmd_id, interaction.cmd_id);
     assert_eq!(decoded.sender, interaction.sender);
     assert_eq!(decoded.receiver, interaction.receiver);
     assert_eq!(decoded.data, interaction.data);
 }
+
+#[cfg(test)]
+#[test]
+fn test_data_len() {
+    assert_eq!(Command::DeleteLayer.data_len(), 2);
+    assert_eq!(Command::DrawOneFigure.data_len(), 15);
+    assert_eq!(Command::DrawTwoFigures.data_len(), 30);
+    assert_eq!(Command::DrawFiveFigures.data_len(), 75);
+    assert_eq!(Command::DrawSevenFigures.data_len(), 105);
+    assert_eq!(Command::DrawCharacter.data_len(), 45);
+
+    assert!(Command::DeleteLayer.validate::<2>().is_ok());
+    assert!(Command::DrawOneFigure.validate::<15>().is_ok());
+    assert!(Command::DrawTwoFigures.validate::<30>().is_ok());
+    assert!(Command::DrawFiveFigures.validate::<75>().is_ok());
+    assert!(Command::DrawSevenFigures.validate::<105>().is_ok());
+    assert!(Command::DrawCharacter.validate::<45>().is_ok());
+}
+
+#[cfg(test)]
+#[test]
+fn test_data_len_mismatch() {
+    struct TwoFigures;
+
+    impl AsCommand<15> for TwoFigures {
+        fn as_command(&self) -> Command {
+            Command::DrawTwoFigures
+        }
+
+        fn as_data(&self) -> [u8; 15] {
+            [0; 15]
+        }
+    }
+
+    assert!(matches!(
+        Interaction::new(0x0001u16, 0x0101u16, TwoFigures),
+        Err(Error::InvalidDataLength { expected: 30 })
+    ));
+
+    // DrawTwoFigures content id with only one figure of data
+    let mut buf = [0u8; 21];
+    buf[0..2].copy_from_slice(&0x0102u16.to_le_bytes());
+    assert!(matches!(
+        Interaction::<15>::unmarshal(&buf),
+        Err(Error::InvalidDataLength { expected: 30 })
+    ));
+
+    // Payloads shorter or longer than the header and data
+    assert!(matches!(
+        Interaction::<15>::unmarshal(&buf[..20]),
+        Err(Error::InvalidDataLength { expected: 21 })
+    ));
+    assert!(matches!(
+        Interaction::<15>::unmarshal(&[0u8; 32]),
+        Err(Error::InvalidDataLength { expected: 21 })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_robot_id() {
+    assert_eq!(RobotId::RedHero.id(), 1);
+    assert_eq!(RobotId::RedHero.client_id(), 0x0101);
+    assert_eq!(RobotId::RedAerial.client_id(), 0x0106);
+    assert_eq!(RobotId::BlueHero.id(), 101);
+    assert_eq!(RobotId::BlueHero.client_id(), 0x0165);
+    assert_eq!(RobotId::BlueStandard3.client_id(), 0x0167);
+
+    let layer =
+        crate::delete_layer::DeleteLayer::new(crate::delete_layer::DeleteType::DeleteLayer, 1);
+    let interaction = Interaction::to_client(RobotId::BlueStandard4, layer).unwrap();
+    assert_eq!(interaction.sender, 104);
+    assert_eq!(interaction.receiver, 0x0168);
+}
+
+#[cfg(test)]
+#[test]
+fn test_custom_command() {
+    struct Custom([u8; 4]);
+
+    impl AsCommand<4> for Custom {
+        fn as_command(&self) -> Command {
+            Command::Custom(0x0233)
+        }
+
+        fn as_data(&self) -> [u8; 4] {
+            self.0
+        }
+    }
+
+    let interaction =
+        Interaction::new(RobotId::RedHero, RobotId::RedSentry, Custom([9, 8, 7, 6])).unwrap();
+
+    let mut buf = [0u8; 10];
+    assert_eq!(interaction.marshal(&mut buf).unwrap(), 10);
+    assert_eq!(&buf[0..2], &0x0233u16.to_le_bytes());
+
+    let decoded = Interaction::<4>::unmarshal(&buf).unwrap();
+    assert_eq!(decoded.cmd_id, Command::Custom(0x0233));
+    assert_eq!(decoded.data, [9, 8, 7, 6]);
+
+    // Outside both the UI and the inter-robot ranges
+    buf[0..2].copy_from_slice(&0x0300u16.to_le_bytes());
+    assert!(matches!(
+        Interaction::<4>::unmarshal(&buf),
+        Err(Error::DecodeError { at: 1 })
+    ));
+    assert_eq!(Command::from_id(0x0101), Some(Command::DrawOneFigure));
+    assert_eq!(Command::DrawCharacter.id(), 0x0110);
+}
+
+#[cfg(test)]
+#[test]
+fn test_too_large() {
+    let interaction: Interaction<113> = Interaction {
+        cmd_id: Command::Custom(0x0200),
+        sender: 1,
+        receiver: 2,
+        data: [0; 113],
+    };
+
+    // Reported even though the buffer is also too small
+    let mut buf = [0u8; 16];
+    assert!(matches!(
+        interaction.marshal(&mut buf),
+        Err(Error::InputTooLarge { max: 112 })
+    ));
+
+    let mut raw = [0u8; 119];
+    raw[0..2].copy_from_slice(&0x0200u16.to_le_bytes());
+    assert!(matches!(
+        Interaction::<113>::unmarshal(&raw),
+        Err(Error::InputTooLarge { max: 112 })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_swapped() {
+    let layer =
+        crate::delete_layer::DeleteLayer::new(crate::delete_layer::DeleteType::DeleteLayer, 1);
+    let interaction = Interaction::to_client(RobotId::RedHero, layer).unwrap();
+
+    let swapped = interaction.swapped();
+    assert_eq!((swapped.sender, swapped.receiver), (0x0101, 1));
+    assert_eq!(swapped.data, interaction.data);
+
+    let twice = swapped.swapped();
+    assert_eq!((twice.sender, twice.receiver), (1, 0x0101));
+
+    let echo = interaction.with_target(RobotId::RedHero);
+    assert_eq!((echo.sender, echo.receiver), (1, 1));
+}
+
+#[cfg(test)]
+#[test]
+fn test_aliases() {
+    assert_eq!(DeleteLayerMsg::MARSHALED_LEN, Some(6 + 2));
+    assert_eq!(DrawOne::MARSHALED_LEN, Some(6 + 15));
+    assert_eq!(DrawTwo::MARSHALED_LEN, Some(6 + 30));
+    assert_eq!(DrawFive::MARSHALED_LEN, Some(6 + 75));
+    assert_eq!(DrawSeven::MARSHALED_LEN, Some(6 + 105));
+    assert_eq!(DrawChar::MARSHALED_LEN, Some(6 + 45));
+
+    let layer =
+        crate::delete_layer::DeleteLayer::new(crate::delete_layer::DeleteType::DeleteLayer, 1);
+    let msg: DeleteLayerMsg = Interaction::to_client(RobotId::RedHero, layer).unwrap();
+    assert_eq!(msg.cmd_id, Command::DeleteLayer);
+
+    let figure = crate::draw_figure::IaFigure::new();
+    let msg: DrawOne = Interaction::to_client(RobotId::RedHero, figure).unwrap();
+    assert_eq!(msg.cmd_id, Command::DrawOneFigure);
+}
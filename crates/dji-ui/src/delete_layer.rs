@@ -21,6 +21,11 @@ impl DeleteLayer {
     pub fn new(delete_type: DeleteType, layer: u8) -> Self {
         Self { delete_type, layer }
     }
+
+    /// Delete every layer at once.
+    pub fn all() -> Self {
+        Self::new(DeleteType::DeleteAllLayers, 0)
+    }
 }
 
 impl AsCommand<2> for DeleteLayer {
@@ -29,7 +34,13 @@ impl AsCommand<2> for DeleteLayer {
     }
 
     fn as_data(&self) -> [u8; 2] {
-        [self.delete_type as u8, self.layer]
+        // The layer is meaningless when clearing everything.
+        let layer = match self.delete_type {
+            DeleteType::DeleteAllLayers => 0,
+            _ => self.layer,
+        };
+
+        [self.delete_type as u8, layer]
     }
 }
 
@@ -41,3 +52,12 @@ fn test() {
     assert_eq!(delete_layer.as_command(), Command::DeleteLayer);
     assert_eq!(delete_layer.as_data(), [1, 3]);
 }
+
+#[cfg(test)]
+#[test]
+fn test_delete_all() {
+    assert_eq!(DeleteLayer::all().as_data(), [2, 0]);
+
+    let stray_layer = DeleteLayer::new(DeleteType::DeleteAllLayers, 7);
+    assert_eq!(stray_layer.as_data(), [2, 0]);
+}
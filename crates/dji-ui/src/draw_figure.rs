@@ -1,7 +1,7 @@
 use crate::private::*;
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Operate {
     NoOperation = 0,
@@ -11,7 +11,7 @@ pub enum Operate {
 }
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum FigureType {
     Line = 0,
@@ -25,7 +25,7 @@ pub enum FigureType {
 }
 
 #[repr(u8)]
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(FromRepr, Debug, Clone, Copy, PartialEq)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Color {
     SelfColor = 0,
@@ -39,7 +39,20 @@ pub enum Color {
     White = 8,
 }
 
+///
 /// Robot to Client
+///
+/// The client keys figures by `name`. Send a figure once with
+/// `added()`, then resend the same name with `modified()` to update
+/// it; adding it again creates a duplicate. Use `deleted()` to remove it.
+///
+/// ```ignore
+/// let hp = IaFigure::line(figure_name!("hp"), start, end, 2, Color::Green, 1);
+/// send(hp.added());
+/// send(IaFigure::line(figure_name!("hp"), start, new_end, 2, Color::Green, 1).modified());
+/// send(hp.deleted());
+/// ```
+///
 #[derive(Debug, Clone, Copy)]
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct IaFigure {
@@ -58,6 +71,48 @@ pub struct IaFigure {
     details_e: u16,   // 11 bits
 }
 
+///
+/// Figure name from a string of at most 3 ASCII bytes.
+///
+/// The name is the key the client uses to modify or delete a
+/// figure. Shorter names are padded with zeros.
+///
+/// # Errors
+///
+/// Returns `InvalidDataLength` if `s` is longer than 3 bytes,
+/// or `EncodeError` with the offset of the first non-ASCII byte.
+///
+pub const fn name(s: &str) -> Result<[u8; 3]> {
+    let bytes = s.as_bytes();
+    if bytes.len() > 3 {
+        return Err(Error::InvalidDataLength { expected: 3 });
+    }
+
+    let mut name = [0u8; 3];
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii() {
+            return Err(Error::EncodeError { inner: i });
+        }
+        name[i] = bytes[i];
+        i += 1;
+    }
+
+    Ok(name)
+}
+
+/// Upper bound of the 9-bit angle fields in degrees
+const MAX_ANGLE: u16 = 360;
+/// Upper bound of the 11-bit coordinate fields
+const MAX_COORD: u16 = (1 << 11) - 1;
+/// Upper bound of the 10-bit width field
+const MAX_WIDTH: u16 = (1 << 10) - 1;
+
+/// Clamp `value` to `max`.
+const fn clamp(value: u16, max: u16) -> u16 {
+    if value > max { max } else { value }
+}
+
 impl IaFigure {
     pub const fn new() -> Self {
         Self {
@@ -76,28 +131,425 @@ impl IaFigure {
             details_e: 0,
         }
     }
+
+    /// Start a figure of `figure_type` to be added.
+    const fn with_type(
+        name: [u8; 3],
+        figure_type: FigureType,
+        width: u16,
+        color: Color,
+        layer: u8,
+    ) -> Self {
+        let mut figure = Self::new();
+        figure.name = name;
+        figure.operate_type = Operate::Add as u8;
+        figure.figure_type = figure_type as u8;
+        figure.layer = layer & 0xF;
+        figure.color = color as u8;
+        figure.width = clamp(width, MAX_WIDTH);
+        figure
+    }
+
+    /// Mark the figure to be added.
+    pub const fn added(self) -> Self {
+        self.with_operate(Operate::Add)
+    }
+
+    /// Mark the figure to replace the one with the same name.
+    pub const fn modified(self) -> Self {
+        self.with_operate(Operate::Modify)
+    }
+
+    /// Mark the figure with this name to be removed.
+    pub const fn deleted(self) -> Self {
+        self.with_operate(Operate::Delete)
+    }
+
+    const fn with_operate(mut self, operate: Operate) -> Self {
+        self.operate_type = operate as u8;
+        self
+    }
+
+    /// Store the start point and the end point in `details_d`/`details_e`.
+    const fn set_points(&mut self, start: (u16, u16), end: (u16, u16)) {
+        self.start_x = clamp(start.0, MAX_COORD);
+        self.start_y = clamp(start.1, MAX_COORD);
+        self.details_d = clamp(end.0, MAX_COORD);
+        self.details_e = clamp(end.1, MAX_COORD);
+    }
+
+    /// Straight line from `start` to `end`.
+    pub const fn line(
+        name: [u8; 3],
+        start: (u16, u16),
+        end: (u16, u16),
+        width: u16,
+        color: Color,
+        layer: u8,
+    ) -> Self {
+        let mut figure = Self::with_type(name, FigureType::Line, width, color, layer);
+        figure.set_points(start, end);
+        figure
+    }
+
+    /// Rectangle spanned by the opposite corners `corner0` and `corner1`.
+    pub const fn rectangle(
+        name: [u8; 3],
+        corner0: (u16, u16),
+        corner1: (u16, u16),
+        width: u16,
+        color: Color,
+        layer: u8,
+    ) -> Self {
+        let mut figure = Self::with_type(name, FigureType::Rectangle, width, color, layer);
+        figure.set_points(corner0, corner1);
+        figure
+    }
+
+    ///
+    /// Arc centered at `center` with semi-axes `rx` and `ry`,
+    /// drawn clockwise from `start_angle` to `end_angle` in degrees.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub const fn arc(
+        name: [u8; 3],
+        start_angle: u16,
+        end_angle: u16,
+        rx: u16,
+        ry: u16,
+        center: (u16, u16),
+        width: u16,
+        color: Color,
+        layer: u8,
+    ) -> Self {
+        let mut figure = Self::ellipse(name, rx, ry, center, width, color, layer);
+        figure.figure_type = FigureType::Arc as u8;
+        figure.details_a = clamp(start_angle, MAX_ANGLE);
+        figure.details_b = clamp(end_angle, MAX_ANGLE);
+        figure
+    }
+
+    /// Ellipse centered at `center` with semi-axes `rx` and `ry`.
+    pub const fn ellipse(
+        name: [u8; 3],
+        rx: u16,
+        ry: u16,
+        center: (u16, u16),
+        width: u16,
+        color: Color,
+        layer: u8,
+    ) -> Self {
+        let mut figure = Self::with_type(name, FigureType::Ellipse, width, color, layer);
+        figure.start_x = clamp(center.0, MAX_COORD);
+        figure.start_y = clamp(center.1, MAX_COORD);
+        figure.details_d = clamp(rx, MAX_COORD);
+        figure.details_e = clamp(ry, MAX_COORD);
+        figure
+    }
+}
+
+impl Default for IaFigure {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl AsCommand<15> for IaFigure {
     fn as_command(&self) -> Command {
-        Command::DeleteLayer
+        Command::DrawOneFigure
     }
 
     fn as_data(&self) -> [u8; 15] {
+        let operate1 = (self.operate_type as u32 & 0x7)
+            | ((self.figure_type as u32 & 0x7) << 3)
+            | ((self.layer as u32 & 0xF) << 6)
+            | ((self.color as u32 & 0xF) << 10)
+            | ((self.details_a as u32 & 0x1FF) << 14)
+            | ((self.details_b as u32 & 0x1FF) << 23);
+
+        let operate2 = (self.width as u32 & 0x3FF)
+            | ((self.start_x as u32 & 0x7FF) << 10)
+            | ((self.start_y as u32 & 0x7FF) << 21);
+
+        let operate3 = (self.details_c as u32 & 0x3FF)
+            | ((self.details_d as u32 & 0x7FF) << 10)
+            | ((self.details_e as u32 & 0x7FF) << 21);
+
         let mut data = [0u8; 15];
         data[0..3].copy_from_slice(&self.name);
-        // data[3..7].copy_from_slice(&self.operate1.to_le_bytes());
-        // data[7..11].copy_from_slice(&self.operate2.to_le_bytes());
-        // data[11..15].copy_from_slice(&self.operate3.to_le_bytes());
+        data[3..7].copy_from_slice(&operate1.to_le_bytes());
+        data[7..11].copy_from_slice(&operate2.to_le_bytes());
+        data[11..15].copy_from_slice(&operate3.to_le_bytes());
         data
     }
 }
 
-// #[cfg(test)]
-// #[test]
-// fn test() {
-//     let delete_layer = DeleteLayer::new(DeleteType::DeleteLayer, 3);
+impl IaFigure {
+    ///
+    /// Figure from its 15 bytes of data, the inverse of `as_data`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DecodeError` at the first byte of the word holding an
+    /// unknown operation or color.
+    ///
+    pub const fn from_data(data: &[u8; 15]) -> Result<Self> {
+        const fn word(data: &[u8; 15], i: usize) -> u32 {
+            u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]])
+        }
+        let (operate1, operate2, operate3) = (word(data, 3), word(data, 7), word(data, 11));
+
+        let operate_type = (operate1 & 0x7) as u8;
+        let color = ((operate1 >> 10) & 0xF) as u8;
+        if Operate::from_repr(operate_type).is_none() || Color::from_repr(color).is_none() {
+            return Err(Error::DecodeError { at: 3 });
+        }
+
+        Ok(Self {
+            name: [data[0], data[1], data[2]],
+            operate_type,
+            figure_type: ((operate1 >> 3) & 0x7) as u8,
+            layer: ((operate1 >> 6) & 0xF) as u8,
+            color,
+            details_a: ((operate1 >> 14) & 0x1FF) as u16,
+            details_b: ((operate1 >> 23) & 0x1FF) as u16,
+            width: (operate2 & 0x3FF) as u16,
+            start_x: ((operate2 >> 10) & 0x7FF) as u16,
+            start_y: ((operate2 >> 21) & 0x7FF) as u16,
+            details_c: (operate3 & 0x3FF) as u16,
+            details_d: ((operate3 >> 10) & 0x7FF) as u16,
+            details_e: ((operate3 >> 21) & 0x7FF) as u16,
+        })
+    }
+}
+
+///
+/// Figures of an inbound figure drawing interaction.
+///
+/// `raw` is the whole interaction, header included. Its content id
+/// gives the number of figures, which the data must match exactly.
+///
+/// # Errors
+///
+/// Returns `DecodeError` at 1 for a content id that does not draw
+/// figures, `InvalidDataLength` with the expected length if the data
+/// does not hold exactly that many figures, or the error of the first
+/// figure that fails to decode, offset into `raw`.
+///
+pub fn decode_figures(raw: &[u8]) -> Result<heapless::Vec<IaFigure, 7>> {
+    const HEADER: usize = 6;
+
+    if raw.len() < HEADER {
+        return Err(Error::InvalidDataLength { expected: HEADER });
+    }
+
+    let count = Command::from_id(u16::from_le_bytes([raw[0], raw[1]]))
+        .and_then(|cmd| cmd.figure_count())
+        .ok_or(Error::DecodeError { at: 1 })?;
+
+    let expected = HEADER + count * 15;
+    if raw.len() != expected {
+        return Err(Error::InvalidDataLength { expected });
+    }
+
+    let mut figures = heapless::Vec::new();
+    let (chunks, _) = raw[HEADER..].as_chunks::<15>();
+    for (i, data) in chunks.iter().enumerate() {
+        let figure = IaFigure::from_data(data).map_err(|e| match e {
+            Error::DecodeError { at } => Error::DecodeError {
+                at: HEADER + i * 15 + at,
+            },
+            e => e,
+        })?;
+
+        // Safety: at most 7 figures per content id, so Unwrap is safe.
+        figures.push(figure).unwrap();
+    }
+
+    Ok(figures)
+}
+
+#[cfg(test)]
+fn words(data: &[u8; 15]) -> [u32; 3] {
+    let word = |i: usize| u32::from_le_bytes(data[i..i + 4].try_into().unwrap());
+    [word(3), word(7), word(11)]
+}
+
+#[cfg(test)]
+#[test]
+fn test_arc() {
+    let arc = IaFigure::arc(*b"arc", 30, 390, 100, 50, (960, 540), 4, Color::Cyan, 2);
+    let data = arc.as_data();
+    let [op1, op2, op3] = words(&data);
+
+    assert_eq!(arc.as_command(), Command::DrawOneFigure);
+    assert_eq!(&data[0..3], b"arc");
+
+    assert_eq!(op1 & 0x7, Operate::Add as u32);
+    assert_eq!((op1 >> 3) & 0x7, FigureType::Arc as u32);
+    assert_eq!((op1 >> 6) & 0xF, 2);
+    assert_eq!((op1 >> 10) & 0xF, Color::Cyan as u32);
+    assert_eq!((op1 >> 14) & 0x1FF, 30);
+    assert_eq!((op1 >> 23) & 0x1FF, 360); // clamped
+
+    assert_eq!(op2 & 0x3FF, 4);
+    assert_eq!((op2 >> 10) & 0x7FF, 960);
+    assert_eq!((op2 >> 21) & 0x7FF, 540);
+
+    assert_eq!(op3 & 0x3FF, 0);
+    assert_eq!((op3 >> 10) & 0x7FF, 100);
+    assert_eq!((op3 >> 21) & 0x7FF, 50);
+}
+
+#[cfg(test)]
+#[test]
+fn test_ellipse() {
+    let ellipse = IaFigure::ellipse(*b"ell", 200, 80, (100, 2000), 2, Color::Green, 1);
+    let data = ellipse.as_data();
+
+    assert_eq!(&data[0..3], b"ell");
+    assert_eq!(
+        words(&data),
+        [
+            1 | (3 << 3) | (1 << 6) | (2 << 10),
+            2 | (100 << 10) | (2000 << 21),
+            (200 << 10) | (80 << 21),
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_from_repr() {
+    assert_eq!(Operate::from_repr(3), Some(Operate::Delete));
+    assert_eq!(FigureType::from_repr(7), Some(FigureType::Character));
+    assert_eq!(Color::from_repr(8), Some(Color::White));
+
+    assert_eq!(Operate::from_repr(4), None);
+    assert_eq!(FigureType::from_repr(8), None);
+    assert_eq!(Color::from_repr(9), None);
+}
 
-//     assert_eq!(delete_layer.as_command(), Command::DeleteLayer);
-//     assert_eq!(delete_layer.as_data(), [1, 3]);
-// }
+#[cfg(test)]
+#[test]
+fn test_line() {
+    let line = IaFigure::line(*b"ln0", (0, 0), (1920, 1080), 3, Color::White, 0);
+
+    #[rustfmt::skip]
+    assert_eq!(
+        line.as_data(),
+        [
+            b'l', b'n', b'0',
+            0x01, 0x20, 0x00, 0x00, // add, line, layer 0, white
+            0x03, 0x00, 0x00, 0x00, // width 3 at (0, 0)
+            0x00, 0x00, 0x1E, 0x87, // end at (1920, 1080)
+        ]
+    );
+}
+
+#[cfg(test)]
+#[test]
+fn test_rectangle() {
+    let rect = IaFigure::rectangle(*b"box", (100, 200), (4000, 300), 1, Color::Yellow, 5);
+    let [op1, op2, op3] = words(&rect.as_data());
+
+    assert_eq!((op1 >> 3) & 0x7, FigureType::Rectangle as u32);
+    assert_eq!((op1 >> 6) & 0xF, 5);
+    assert_eq!((op2 >> 10) & 0x7FF, 100);
+    assert_eq!((op2 >> 21) & 0x7FF, 200);
+    assert_eq!((op3 >> 10) & 0x7FF, MAX_COORD as u32); // clamped
+    assert_eq!((op3 >> 21) & 0x7FF, 300);
+}
+
+#[cfg(test)]
+#[test]
+fn test_name() {
+    assert_eq!(name("hp").unwrap(), *b"hp\0");
+    assert_eq!(name("ab1").unwrap(), *b"ab1");
+    assert!(matches!(
+        name("ammo"),
+        Err(Error::InvalidDataLength { expected: 3 })
+    ));
+    assert!(matches!(
+        name("h\u{e9}"),
+        Err(Error::EncodeError { inner: 1 })
+    ));
+
+    const HP: [u8; 3] = crate::figure_name!("hp");
+    assert_eq!(HP, *b"hp\0");
+}
+
+#[cfg(test)]
+#[test]
+fn test_operate() {
+    let figure = IaFigure::ellipse(*b"tgt", 10, 10, (500, 500), 1, Color::Pink, 3);
+    let operate = |f: IaFigure| f.as_data()[3] & 0x7;
+
+    assert_eq!(operate(figure), Operate::Add as u8);
+    assert_eq!(operate(figure.modified()), Operate::Modify as u8);
+    assert_eq!(operate(figure.deleted()), Operate::Delete as u8);
+    assert_eq!(operate(figure.deleted().added()), Operate::Add as u8);
+
+    // Only the operate field changes.
+    let (a, b) = (figure.as_data(), figure.modified().as_data());
+    assert_eq!(a[3] & !0x7, b[3] & !0x7);
+    assert_eq!(a[4..], b[4..]);
+}
+
+#[cfg(test)]
+#[test]
+fn test_from_data() {
+    let arc = IaFigure::arc(*b"arc", 30, 90, 100, 50, (960, 540), 4, Color::Cyan, 2).modified();
+    let decoded = IaFigure::from_data(&arc.as_data()).unwrap();
+    assert_eq!(decoded.as_data(), arc.as_data());
+
+    let mut data = arc.as_data();
+    data[3] |= 0x7; // operation 7
+    assert!(matches!(
+        IaFigure::from_data(&data),
+        Err(Error::DecodeError { at: 3 })
+    ));
+}
+
+#[cfg(test)]
+#[test]
+fn test_decode_figures() {
+    let line = IaFigure::line(*b"ln0", (0, 0), (1920, 1080), 3, Color::White, 0);
+    let rect = IaFigure::rectangle(*b"box", (100, 200), (400, 300), 1, Color::Yellow, 5);
+
+    let mut raw = [0u8; 6 + 30];
+    raw[0..2].copy_from_slice(&Command::DrawTwoFigures.id().to_le_bytes());
+    raw[6..21].copy_from_slice(&line.as_data());
+    raw[21..36].copy_from_slice(&rect.as_data());
+
+    let figures = decode_figures(&raw).unwrap();
+    assert_eq!(figures.len(), 2);
+    assert_eq!(figures[0].as_data(), line.as_data());
+    assert_eq!(figures[1].as_data(), rect.as_data());
+
+    // Two figures announced, one sent, or one too many bytes.
+    assert!(matches!(
+        decode_figures(&raw[..21]),
+        Err(Error::InvalidDataLength { expected: 36 })
+    ));
+    let mut long = [0u8; 37];
+    long[..36].copy_from_slice(&raw);
+    assert!(matches!(
+        decode_figures(&long),
+        Err(Error::InvalidDataLength { expected: 36 })
+    ));
+
+    // The bad color of the second figure is reported where it is.
+    raw[21 + 4] |= 0x3C;
+    assert!(matches!(
+        decode_figures(&raw),
+        Err(Error::DecodeError { at: 24 })
+    ));
+
+    // Not a figure drawing content id.
+    raw[0..2].copy_from_slice(&Command::DeleteLayer.id().to_le_bytes());
+    assert!(matches!(
+        decode_figures(&raw),
+        Err(Error::DecodeError { at: 1 })
+    ));
+}
@@ -0,0 +1,225 @@
+//!
+//! Dirty-tracking HUD compositor.
+//!
+//! The referee link is slow, so redrawing the whole HUD every tick
+//! wastes most of its bandwidth. A `Hud` keeps a fixed set of figure
+//! slots, remembers what the client already shows, and on `flush`
+//! writes only the `Add`/`Modify`/`Delete` interactions needed to
+//! bring the client up to date.
+//!
+
+use crate::draw_figure::IaFigure;
+use crate::private::*;
+use crate::{Interaction, RobotId};
+use dji_frame::{FrameWriter, Validator};
+
+/// State of one figure slot.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+struct Slot {
+    /// Figure the client currently shows.
+    shown: Option<IaFigure>,
+    /// Figure the client should show.
+    wanted: Option<IaFigure>,
+}
+
+/// Name of a figure, the key the client matches on.
+fn name_of(figure: &IaFigure) -> [u8; 3] {
+    let data = figure.as_data();
+    [data[0], data[1], data[2]]
+}
+
+/// Whether two figures draw the same, ignoring the operation.
+fn same(a: &IaFigure, b: &IaFigure) -> bool {
+    a.added().as_data() == b.added().as_data()
+}
+
+///
+/// HUD of `N` figure slots drawn on the client of `robot`.
+///
+/// # Example
+///
+/// ```ignore
+/// let mut hud = Hud::<4>::new(RobotId::RedStandard3);
+/// loop {
+///     hud.set(0, IaFigure::line(figure_name!("hp"), start, end, 2, Color::Green, 1));
+///
+///     let mut writer = FrameWriter::new(&mut msger, &mut buffer);
+///     hud.flush(&mut writer)?;
+///     uart.write(writer.finish()).await?;
+/// }
+/// ```
+///
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Hud<const N: usize> {
+    robot: RobotId,
+    slots: [Slot; N],
+}
+
+impl<const N: usize> Hud<N> {
+    /// Create an empty HUD for the client of `robot`.
+    pub const fn new(robot: RobotId) -> Self {
+        Self {
+            robot,
+            slots: [Slot {
+                shown: None,
+                wanted: None,
+            }; N],
+        }
+    }
+
+    ///
+    /// Show `figure` in slot `index`.
+    ///
+    /// The operation of `figure` is ignored; `flush` picks it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below `N`.
+    ///
+    pub fn set(&mut self, index: usize, figure: IaFigure) {
+        self.slots[index].wanted = Some(figure);
+    }
+
+    ///
+    /// Remove the figure in slot `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is not below `N`.
+    ///
+    pub fn clear(&mut self, index: usize) {
+        self.slots[index].wanted = None;
+    }
+
+    ///
+    /// Forget what the client shows, so the next `flush` adds
+    /// every figure again.
+    ///
+    /// Use it after the client restarted or a layer was deleted.
+    ///
+    pub fn invalidate(&mut self) {
+        for slot in &mut self.slots {
+            slot.shown = None;
+        }
+    }
+
+    /// Check whether a `flush` would write anything.
+    pub fn is_dirty(&self) -> bool {
+        self.slots
+            .iter()
+            .any(|slot| match (&slot.shown, &slot.wanted) {
+                (Some(shown), Some(wanted)) => !same(shown, wanted),
+                (None, None) => false,
+                _ => true,
+            })
+    }
+
+    ///
+    /// Write the interactions for every changed slot.
+    ///
+    /// A figure renamed in place is deleted and added again, since
+    /// the client only modifies figures with the same name.
+    ///
+    /// Returns the number of interactions written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `FrameWriter::write`, typically once its
+    /// buffer is full. Slots written so far are committed, so the
+    /// next `flush` continues with the rest.
+    ///
+    pub fn flush<V: Validator>(&mut self, writer: &mut FrameWriter<'_, V>) -> Result<usize> {
+        let robot = self.robot;
+        let mut count = 0;
+
+        for slot in &mut self.slots {
+            if let (Some(shown), Some(wanted)) = (&slot.shown, &slot.wanted)
+                && name_of(shown) != name_of(wanted)
+            {
+                writer.write(&Interaction::to_client(robot, shown.deleted())?)?;
+                slot.shown = None;
+                count += 1;
+            }
+
+            let figure = match (&slot.shown, &slot.wanted) {
+                (None, Some(wanted)) => wanted.added(),
+                (Some(shown), Some(wanted)) if !same(shown, wanted) => wanted.modified(),
+                (Some(shown), None) => shown.deleted(),
+                _ => continue,
+            };
+
+            writer.write(&Interaction::to_client(robot, figure)?)?;
+            slot.shown = slot.wanted;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_hud() {
+    use crate::draw_figure::{Color, Operate};
+    use dji_frame::{DjiValidator, Messager};
+
+    /// Flush `hud` and get the operation of every written figure.
+    fn flush(hud: &mut Hud<3>) -> Vec<(Operate, [u8; 3])> {
+        let mut msger = Messager::<DjiValidator>::new(0);
+        let mut buffer = [0u8; 256];
+        let mut writer = FrameWriter::new(&mut msger, &mut buffer);
+        let count = hud.flush(&mut writer).unwrap();
+        let mut raw = writer.finish();
+
+        let msger = Messager::<DjiValidator>::new(0);
+        let mut ops = Vec::new();
+        while !raw.is_empty() {
+            let (frame, size) = msger.unpack(raw).unwrap();
+            let data = &frame.payload()[6..];
+            let operate = Operate::from_repr(data[3] & 0x7).unwrap();
+            ops.push((operate, [data[0], data[1], data[2]]));
+            raw = &raw[size..];
+        }
+
+        assert_eq!(ops.len(), count);
+        ops
+    }
+
+    let line = |name: &[u8; 3], x: u16| IaFigure::line(*name, (0, 0), (x, 0), 2, Color::Green, 1);
+
+    let mut hud = Hud::<3>::new(RobotId::RedStandard3);
+    assert!(!hud.is_dirty());
+
+    hud.set(0, line(b"hp\0", 100));
+    hud.set(1, line(b"amo", 200));
+    assert_eq!(
+        flush(&mut hud),
+        [(Operate::Add, *b"hp\0"), (Operate::Add, *b"amo")]
+    );
+    assert!(!hud.is_dirty());
+    assert!(flush(&mut hud).is_empty());
+
+    // Setting an unchanged figure writes nothing.
+    hud.set(1, line(b"amo", 200).modified());
+    assert!(flush(&mut hud).is_empty());
+
+    // Changing one figure writes exactly one `Modify`.
+    hud.set(0, line(b"hp\0", 150));
+    assert_eq!(flush(&mut hud), [(Operate::Modify, *b"hp\0")]);
+
+    hud.clear(1);
+    hud.set(0, line(b"hq\0", 150));
+    assert_eq!(
+        flush(&mut hud),
+        [
+            (Operate::Delete, *b"hp\0"),
+            (Operate::Add, *b"hq\0"),
+            (Operate::Delete, *b"amo")
+        ]
+    );
+
+    hud.invalidate();
+    assert_eq!(flush(&mut hud), [(Operate::Add, *b"hq\0")]);
+}
@@ -1,11 +1,45 @@
 #![cfg_attr(not(test), no_std)]
 
+use dji_frame::Marshaler as _;
+
+/// Command IDs of all messages in this crate, checked for collisions.
+pub const CMD_IDS: &[u16] = &[common::Interaction::<0>::CMD_ID];
+
+const _: () = dji_frame::assert_unique_ids(CMD_IDS);
+
 pub use common::*;
 
 mod common;
 
 pub mod delete_layer;
 pub mod draw_figure;
+pub mod hud;
+
+///
+/// Figure name validated at compile time.
+///
+/// Expands to the `[u8; 3]` name, and fails the build if the
+/// string is longer than 3 bytes or not ASCII.
+///
+/// ```
+/// const HP: [u8; 3] = dji_ui::figure_name!("hp");
+/// ```
+///
+/// ```compile_fail
+/// const AMMO: [u8; 3] = dji_ui::figure_name!("ammo");
+/// ```
+///
+#[macro_export]
+macro_rules! figure_name {
+    ($s:expr) => {
+        const {
+            match $crate::draw_figure::name($s) {
+                Ok(name) => name,
+                Err(_) => panic!("Figure name must be at most 3 ASCII bytes"),
+            }
+        }
+    };
+}
 
 mod private {
     #[allow(unused_imports)]
@@ -13,7 +47,9 @@ mod private {
     pub use ::defmt::{debug, error, info, trace, warn};
 
     pub use crate::common::{AsCommand, Command};
+    pub use core::ops::RangeInclusive;
     pub use dji_frame::{Error, Marshaler, Result};
+    pub use strum::FromRepr;
 }
 
 #[cfg(test)]
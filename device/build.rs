@@ -15,6 +15,11 @@ fn main() -> std::io::Result<()> {
 
     let package = env!("CARGO_PKG_NAME");
 
+    // Host test builds are not linked for, nor flashed to, the board.
+    if !var("TARGET").unwrap_or_default().starts_with("thumb") {
+        return Ok(());
+    }
+
     // Output the build.map file   : This is useful for analysis.
     cargo_emit::rustc_link_arg!(format!("-Map={}/build.map", package));
 
@@ -0,0 +1,131 @@
+//!
+//! # Gimbal Controller
+//!
+//! Inputs:
+//! - `IMU`: gimbal attitude in degrees, defaults to level until the IMU lands
+//! - `DMotor`: yaw motor feedback (velocity in RPM)
+//!
+//! Output:
+//! - GM6020 current frame (yaw = id 1, pitch = id 2) posted to `CAN1_TX`
+//!
+
+use super::pid::Pid;
+use crate::sync::{blocking_mutex::raw, signal::Signal};
+use crate::tasks::bxcan::{CAN1_TX, CtrlGroup, DMotor, DjiCtrl, DjiMotor, RAW_CURRENT_MAX};
+
+/// GM6020 Current Command Range
+pub const CURRENT_MAX: f32 = RAW_CURRENT_MAX as f32;
+
+///
+/// # Gimbal Attitude
+///
+/// Angles in degrees.
+///
+#[derive(defmt::Format, Debug, Default, Clone, Copy)]
+pub struct Attitude {
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+/// Latest attitude published by the IMU task
+pub static IMU: Signal<raw::CriticalSectionRawMutex, Attitude> = Signal::new();
+
+/// GM6020 current control for ids 1-4
+struct GimbalCtrl;
+
+impl DjiCtrl for GimbalCtrl {
+    const GROUP: CtrlGroup = CtrlGroup::Gm6020Low;
+}
+
+///
+/// # Control Mixing
+///
+/// Map the yaw and pitch outputs to the two motor currents,
+/// saturating each to the GM6020 command range.
+///
+pub const fn mix(yaw: f32, pitch: f32) -> (i16, i16) {
+    const fn sat(x: f32) -> i16 {
+        x.clamp(-CURRENT_MAX, CURRENT_MAX) as i16
+    }
+    (sat(yaw), sat(pitch))
+}
+
+pub struct Gimbal {
+    attitude: Attitude,
+    yaw_angle: Pid,
+    yaw_speed: Pid,
+    pitch_angle: Pid,
+}
+
+impl Gimbal {
+    pub const fn new() -> Self {
+        Self {
+            attitude: Attitude { yaw: 0., pitch: 0. },
+            yaw_angle: Pid::new(8., 0., 0., 0., 300.),
+            yaw_speed: Pid::new(60., 0.5, 0., 5000., CURRENT_MAX),
+            pitch_angle: Pid::new(400., 2., 0., 5000., CURRENT_MAX),
+        }
+    }
+
+    ///
+    /// # Control Step
+    ///
+    /// Track `target` and post the resulting current frame.
+    ///
+    pub async fn step(&mut self, target: Attitude) {
+        if let Some(attitude) = IMU.try_take() {
+            self.attitude = attitude;
+        }
+
+        let yaw_rate = self.yaw_angle.update(target.yaw, self.attitude.yaw);
+        let yaw = self.yaw_speed.update(yaw_rate, DMotor::get().vel());
+        let pitch = self.pitch_angle.update(target.pitch, self.attitude.pitch);
+
+        let (yaw, pitch) = mix(yaw, pitch);
+        CAN1_TX.send(GimbalCtrl::set_cur((yaw, pitch, 0, 0))).await;
+    }
+
+    ///
+    /// # Reset
+    ///
+    /// Clear all loop states.
+    ///
+    pub fn reset(&mut self) {
+        self.yaw_angle.reset();
+        self.yaw_speed.reset();
+        self.pitch_angle.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hal::can::Id;
+
+    const MAX: i16 = RAW_CURRENT_MAX;
+
+    #[test]
+    fn test_mix() {
+        // Yaw and pitch pass through in range, and saturate apart past it.
+        assert_eq!(mix(0., 0.), (0, 0));
+        assert_eq!(mix(1200.5, -300.), (1200, -300));
+        assert_eq!(mix(CURRENT_MAX, -CURRENT_MAX), (MAX, -MAX));
+        assert_eq!(mix(1e6, 5.), (MAX, 5));
+        assert_eq!(mix(-7., -1e6), (-7, -MAX));
+        assert_eq!(mix(f32::INFINITY, f32::NEG_INFINITY), (MAX, -MAX));
+    }
+
+    #[test]
+    fn test_mix_frame() {
+        // Yaw drives id 1 and pitch id 2 of the low GM6020 group.
+        let (yaw, pitch) = mix(1e6, -300.);
+        let frame = GimbalCtrl::set_cur((yaw, pitch, 0, 0));
+        let low = CtrlGroup::Gm6020Low.canid();
+        assert!(matches!(frame.id(), Id::Standard(id) if id.as_raw() == low));
+
+        let mut data = [0; 8];
+        data[..2].copy_from_slice(&MAX.to_be_bytes());
+        data[2..4].copy_from_slice(&(-300i16).to_be_bytes());
+        assert_eq!(frame.data(), &data);
+    }
+}
@@ -1,12 +1,26 @@
 use crate::system::*;
+use crate::tasks::buzzer::{PLAY, Song};
+
+mod gimbal;
+mod pid;
+
+pub use gimbal::Attitude;
 
 #[embassy_executor::task]
 pub async fn main() {
     let mut t = utils::init_ticker!(1);
+    let mut gimbal = gimbal::Gimbal::new();
 
+    Device::wait_all(WATCH_LIST, &mut t).await;
     SysMode::Normal.set();
+    PLAY.signal(Song::STARTUP);
 
     loop {
+        match SysMode::get() {
+            SysMode::Normal => gimbal.step(Attitude::default()).await,
+            _ => gimbal.reset(),
+        }
+
         t.next().await
     }
 }
@@ -0,0 +1,62 @@
+//!
+//! # PID Controller
+//!
+
+///
+/// # Positional PID
+///
+/// Both the integral term and the output are clamped
+/// to avoid windup and out-of-range commands.
+///
+pub struct Pid {
+    kp: f32,
+    ki: f32,
+    kd: f32,
+    /// Integral Term Limit
+    i_limit: f32,
+    /// Output Limit
+    out_limit: f32,
+
+    integral: f32,
+    last_err: f32,
+}
+
+impl Pid {
+    pub const fn new(kp: f32, ki: f32, kd: f32, i_limit: f32, out_limit: f32) -> Self {
+        Self {
+            kp,
+            ki,
+            kd,
+            i_limit,
+            out_limit,
+            integral: 0.,
+            last_err: 0.,
+        }
+    }
+
+    ///
+    /// # Update
+    ///
+    /// Run one step with the given target and measurement.
+    ///
+    pub fn update(&mut self, target: f32, measure: f32) -> f32 {
+        let err = target - measure;
+
+        self.integral = (self.integral + self.ki * err).clamp(-self.i_limit, self.i_limit);
+        let derivative = err - self.last_err;
+        self.last_err = err;
+
+        let out = self.kp * err + self.integral + self.kd * derivative;
+        out.clamp(-self.out_limit, self.out_limit)
+    }
+
+    ///
+    /// # Reset
+    ///
+    /// Clear the accumulated state, e.g. when the loop is re-enabled.
+    ///
+    pub fn reset(&mut self) {
+        self.integral = 0.;
+        self.last_err = 0.;
+    }
+}
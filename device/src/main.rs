@@ -1,5 +1,7 @@
-#![no_std]
-#![no_main]
+#![cfg_attr(target_os = "none", no_std)]
+#![cfg_attr(target_os = "none", no_main)]
+// Host builds only run the tests, without `entry`.
+#![cfg_attr(not(target_os = "none"), allow(dead_code))]
 
 use utils::prelude::*;
 
@@ -8,10 +10,17 @@ mod system;
 
 mod tasks {
     pub mod blinky;
+    pub mod buzzer;
     pub mod bxcan;
     pub mod health;
+    pub mod key;
 }
 
+/// Host builds only run the tests, and are never flashed.
+#[cfg(not(target_os = "none"))]
+fn main() {}
+
+#[cfg(target_os = "none")]
 #[embassy_executor::main]
 async fn entry(s: embassy_executor::Spawner) {
     let (_c, p) = utils::sys_init();
@@ -24,6 +33,10 @@ async fn entry(s: embassy_executor::Spawner) {
 
     s.must_spawn(tasks::blinky::task(r.blinky));
 
+    s.must_spawn(tasks::buzzer::task(r.buzzer));
+
+    s.must_spawn(tasks::key::task(r.key));
+
     s.must_spawn(tasks::bxcan::task(s.make_send(), r.can));
 
     s.must_spawn(controller::main());
@@ -8,7 +8,7 @@ const LIST_SIZE: usize = WATCH_LIST.len();
 type Pair = (&'static Device, &'static HeartBeat);
 
 static PAIRS: Option<[Pair; LIST_SIZE]> = const {
-    static STATE: [HeartBeat; LIST_SIZE] = unsafe { core::mem::zeroed() };
+    static STATE: [HeartBeat; LIST_SIZE] = [const { HeartBeat::new() }; LIST_SIZE];
     match LIST_SIZE {
         0 => None,
         _ => {
@@ -75,7 +75,7 @@ impl Device {
     pub fn feed(&self) {
         match self.heartbeat() {
             Some(x) => {
-                x.feed(Self::max_ttl());
+                x.feed(Self::max_ttl(), &Self::FLAP_GUARD);
             }
             None => panic!("Invalid Address: {:?}", self),
         }
@@ -123,6 +123,38 @@ impl Device {
         }
     }
 
+    ///
+    /// # Wait for All Devices to be Online
+    ///
+    /// Resolves once every device in `devices` is online at the same tick.
+    ///
+    pub async fn wait_all(devices: &[Device], t: &mut Ticker) {
+        wait_all_by(devices, Device::check, t).await
+    }
+
+    ///
+    /// # Wait for Any Device to be Online
+    ///
+    /// Resolves once at least one device in `devices` is online.
+    /// An empty list resolves immediately.
+    ///
+    pub async fn wait_any(devices: &[Device], t: &mut Ticker) {
+        wait_any_by(devices, Device::check, t).await
+    }
+
+    ///
+    /// # Wait for Online/Offline Edge
+    ///
+    /// Resolves when the device next goes online or offline,
+    /// without polling.
+    ///
+    pub async fn wait_edge(&self) -> DeviceEdge {
+        match self.heartbeat() {
+            Some(x) => x.wait_edge().await,
+            None => panic!("Invalid Address: {:?}", self),
+        }
+    }
+
     ///
     /// # Tick Heartbeat
     ///
@@ -133,12 +165,26 @@ impl Device {
     ///
     pub fn tick(&self) -> bool {
         match self.heartbeat() {
-            Some(x) => x.tick(),
+            Some(x) => x.tick(&Self::FLAP_GUARD),
             None => panic!("Invalid Address: {:?}", self),
         }
     }
 }
 
+/// Tick `t` until `online` holds for every item
+async fn wait_all_by<T>(items: &[T], online: impl Fn(&T) -> bool, t: &mut Ticker) {
+    while !items.iter().all(&online) {
+        t.next().await
+    }
+}
+
+/// Tick `t` until `online` holds for any item, or there are none
+async fn wait_any_by<T>(items: &[T], online: impl Fn(&T) -> bool, t: &mut Ticker) {
+    while !items.is_empty() && !items.iter().any(&online) {
+        t.next().await
+    }
+}
+
 pub struct Display<'t> {
     inner: &'t Device,
 }
@@ -159,3 +205,76 @@ impl<'t> defmt::Format for Display<'t> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ef::join::join;
+    use time::{Duration, Instant, Timer};
+    use utils::mock_time::block_on;
+
+    const GUARD: FlapGuard = FlapGuard {
+        misses: 1,
+        feeds: 1,
+    };
+
+    /// Feed `heart` online after `ms`
+    async fn feed_after(heart: &HeartBeat, ms: u64) {
+        Timer::after_millis(ms).await;
+        heart.feed(100, &GUARD);
+    }
+
+    /// Run `wait` next to the feeds, returning when it resolved
+    fn elapsed(wait: impl Future<Output = ()>, feeds: impl Future) -> Duration {
+        let start = Instant::now();
+        block_on(join(
+            async {
+                wait.await;
+                start.elapsed()
+            },
+            feeds,
+        ))
+        .0
+    }
+
+    #[test]
+    fn test_wait_all() {
+        let hearts = [HeartBeat::new(), HeartBeat::new()];
+        let mut t = Ticker::every(Duration::from_millis(1));
+
+        // Resolves on the first tick after the last device is online.
+        let feeds = join(feed_after(&hearts[0], 2), feed_after(&hearts[1], 5));
+        let wait = wait_all_by(&hearts, HeartBeat::check, &mut t);
+        let took = elapsed(wait, feeds);
+        assert!(took >= Duration::from_millis(5) && took <= Duration::from_millis(6));
+
+        // And at once if they already are.
+        let wait = wait_all_by(&hearts, HeartBeat::check, &mut t);
+        assert_eq!(elapsed(wait, async {}), Duration::from_ticks(0));
+    }
+
+    #[test]
+    fn test_wait_any() {
+        let hearts = [HeartBeat::new(), HeartBeat::new()];
+        let mut t = Ticker::every(Duration::from_millis(1));
+
+        // Resolves on the first tick after the first device is online.
+        let wait = wait_any_by(&hearts, HeartBeat::check, &mut t);
+        let took = elapsed(wait, feed_after(&hearts[1], 3));
+        assert!(took >= Duration::from_millis(3) && took <= Duration::from_millis(4));
+        assert!(!hearts[0].check());
+
+        let none: [HeartBeat; 0] = [];
+        let wait = wait_any_by(&none, HeartBeat::check, &mut t);
+        assert_eq!(elapsed(wait, async {}), Duration::from_ticks(0));
+    }
+
+    #[test]
+    fn test_from_can_id() {
+        use crate::tasks::bxcan::{DMotor, DjiMotor};
+
+        // Every motor feeds its heartbeat.
+        assert_eq!(Device::from_can_id(DMotor::MSTID), Some(Device::DMotor));
+        assert_eq!(Device::from_can_id(0x0201), None);
+    }
+}
@@ -4,23 +4,77 @@
 
 use super::private::*;
 
+///
+/// # Device Edge
+///
+/// Online status transition of a device.
+///
+#[derive(defmt::Format, Debug, Clone, Copy, PartialEq)]
+pub enum DeviceEdge {
+    Online,
+    Offline,
+}
+
+///
+/// # Flap Guard
+///
+/// Hysteresis of the online/offline decision.
+///
+/// - `misses`: consecutive ticks past the TTL before going offline.
+/// - `feeds`: consecutive feeds before coming back online.
+///
+#[derive(defmt::Format, Debug, Clone, Copy)]
+pub struct FlapGuard {
+    pub misses: u8,
+    pub feeds: u8,
+}
+
 ///
 /// # Heartbeat Structure
 ///
 pub struct HeartBeat {
     online: AtomicBool,
     ttl: AtomicI8,
+    /// Consecutive misses while online, or feeds while offline.
+    streak: AtomicU8,
+    edge: Signal<CriticalSectionRawMutex, DeviceEdge>,
 }
 
 impl HeartBeat {
+    ///
+    /// # New Heartbeat
+    ///
+    /// Create an offline heartbeat.
+    ///
+    pub const fn new() -> Self {
+        Self {
+            online: AtomicBool::new(false),
+            ttl: AtomicI8::new(0),
+            streak: AtomicU8::new(0),
+            edge: Signal::new(),
+        }
+    }
+
     ///
     ///  # Feed Heartbeat
     ///
-    /// Set the device as online and reset its TTL (Time-To-Live) counter.
+    /// Reset the TTL (Time-To-Live) counter.
     ///
-    pub fn feed(&self, ttl: i8) {
-        self.online.store(true, Order);
+    /// An offline device is set online once fed `guard.feeds` times
+    /// in a row, without a missed tick in between.
+    ///
+    pub fn feed(&self, ttl: i8, guard: &FlapGuard) {
         self.ttl.store(ttl, Order);
+        if self.check() {
+            self.streak.store(0, Order);
+            return;
+        }
+
+        if self.streak.fetch_add(1, Order) + 1 >= guard.feeds {
+            self.streak.store(0, Order);
+            self.online.store(true, Order);
+            self.edge.signal(DeviceEdge::Online);
+        }
     }
 
     ///
@@ -29,8 +83,9 @@ impl HeartBeat {
     /// Set the device as offline and reset its TTL (Time-To-Live) counter to zero.
     ///
     pub fn kill(&self) {
-        self.online.store(false, Order);
         self.ttl.store(0, Order);
+        self.streak.store(0, Order);
+        self.go_offline();
     }
 
     ///
@@ -56,16 +111,131 @@ impl HeartBeat {
     ///
     /// Decrement the TTL counter.
     ///
-    /// If the counter reaches zero, mark the device as offline.
+    /// Once the counter has run out for `guard.misses` ticks in a row,
+    /// mark the device as offline. Returns `true` while it is online.
     ///
-    pub fn tick(&self) -> bool {
+    pub fn tick(&self, guard: &FlapGuard) -> bool {
         let prev = self.ttl.fetch_sub(1, Order);
-        if prev < 1 {
-            self.ttl.store(0, Order);
-            self.online.store(false, Order);
+        if prev >= 1 {
+            if self.check() {
+                self.streak.store(0, Order);
+            }
+            return self.check();
+        }
+
+        self.ttl.store(0, Order);
+        if !self.check() {
+            // A miss breaks the run of feeds.
+            self.streak.store(0, Order);
+            return false; // Offline
+        }
+
+        if self.streak.fetch_add(1, Order) + 1 >= guard.misses {
+            self.streak.store(0, Order);
+            self.go_offline();
             return false; // Offline
         }
 
         true // Still Online
     }
+
+    ///
+    /// # Wait for Edge
+    ///
+    /// Resolves on the next online/offline transition.
+    ///
+    /// Only the latest edge is kept, so transitions that happen
+    /// before the waiter runs collapse into the most recent one.
+    ///
+    pub async fn wait_edge(&self) -> DeviceEdge {
+        self.edge.wait().await
+    }
+
+    /// Mark offline, signalling the edge if it was online.
+    fn go_offline(&self) {
+        if self.online.swap(false, Order) {
+            self.edge.signal(DeviceEdge::Offline);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUARD: FlapGuard = FlapGuard {
+        misses: 2,
+        feeds: 3,
+    };
+
+    /// A heartbeat fed online, with a TTL of one tick
+    fn online() -> HeartBeat {
+        let heart = HeartBeat::new();
+        (0..GUARD.feeds).for_each(|_| heart.feed(1, &GUARD));
+        assert!(heart.check());
+        heart
+    }
+
+    #[test]
+    fn test_single_miss() {
+        let heart = online();
+
+        // The TTL runs out, then one tick misses.
+        assert!(heart.tick(&GUARD) && heart.tick(&GUARD));
+        assert!(heart.check());
+
+        // A feed in between starts the count again.
+        heart.feed(1, &GUARD);
+        assert!(heart.tick(&GUARD) && heart.tick(&GUARD));
+        assert!(heart.check());
+    }
+
+    #[test]
+    fn test_sustained_misses() {
+        let heart = online();
+
+        assert!(heart.tick(&GUARD));
+        assert!(heart.tick(&GUARD)); // first miss
+        assert!(!heart.tick(&GUARD)); // second miss
+        assert!(!heart.check() && heart.ttl() == 0);
+    }
+
+    #[test]
+    fn test_miss_resets_feeds() {
+        let heart = HeartBeat::new();
+
+        // Two feeds, then a miss before the third
+        heart.feed(1, &GUARD);
+        heart.feed(1, &GUARD);
+        assert!(!heart.tick(&GUARD) && !heart.tick(&GUARD));
+
+        heart.feed(1, &GUARD);
+        assert!(!heart.check());
+
+        // A full run of feeds brings it online.
+        heart.feed(1, &GUARD);
+        heart.feed(1, &GUARD);
+        assert!(heart.check());
+    }
+
+    #[test]
+    fn test_wait_edge() {
+        use utils::mock_time::block_on;
+
+        let heart = HeartBeat::new();
+
+        (0..GUARD.feeds).for_each(|_| heart.feed(1, &GUARD));
+        assert_eq!(block_on(heart.wait_edge()), DeviceEdge::Online);
+
+        // Feeds while online signal nothing more.
+        (0..3).for_each(|_| heart.feed(1, &GUARD));
+        assert!(heart.edge.try_take().is_none());
+
+        (0..GUARD.misses + 1).for_each(|_| _ = heart.tick(&GUARD));
+        assert_eq!(block_on(heart.wait_edge()), DeviceEdge::Offline);
+
+        // Neither do misses while offline.
+        (0..3).for_each(|_| _ = heart.tick(&GUARD));
+        assert!(heart.edge.try_take().is_none());
+    }
 }
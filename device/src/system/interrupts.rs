@@ -10,7 +10,9 @@ bind_interrupts! {
 
         CAN1_TX => hal::can::TxInterruptHandler<peripherals::CAN1>;
         CAN1_RX0 => hal::can::Rx0InterruptHandler<peripherals::CAN1>;
-        CAN1_RX1 => hal::can::Rx1InterruptHandler<peripherals::CAN1>;
+        // FIFO 1 is drained first, apart from the buffered FIFO 0.
+        CAN1_RX1 => crate::tasks::bxcan::Fifo1InterruptHandler,
+            hal::can::Rx1InterruptHandler<peripherals::CAN1>;
         CAN1_SCE => hal::can::SceInterruptHandler<peripherals::CAN1>;
 
         CAN2_TX => hal::can::TxInterruptHandler<peripherals::CAN2>;
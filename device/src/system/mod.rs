@@ -12,6 +12,8 @@
 #[derive(defmt::Format, Debug, PartialEq)]
 pub enum Device {
     Placeholder = 0x0000,
+    /// Gimbal GM6020, fed by its CAN feedback
+    DMotor = 0x0205,
 }
 
 ///
@@ -19,14 +21,34 @@ pub enum Device {
 ///
 pub const WATCH_LIST: &[Device] = &[
     // Device::Placeholder,
+    Device::DMotor,
 ];
 
+impl Device {
+    ///
+    /// # Device from CAN ID
+    ///
+    /// Map a standard CAN feedback ID to the device it feeds.
+    ///
+    pub const fn from_can_id(id: u16) -> Option<Device> {
+        match id {
+            0x0205 => Some(Device::DMotor),
+            _ => None,
+        }
+    }
+}
+
 /// Settings for Heartbeat Monitoring
 impl Device {
     /// Health Check Interval in ms
     pub(self) const HEALTH_MS: u8 = 100;
     /// Device Expiration Time in ms
     pub(self) const EXPIRE_MS: u16 = 500;
+    /// Missed ticks before offline, and feeds before online again
+    pub(self) const FLAP_GUARD: FlapGuard = FlapGuard {
+        misses: 2,
+        feeds: 3,
+    };
 }
 
 mod devices;
@@ -35,6 +57,7 @@ mod interrupts;
 mod resources;
 mod status;
 
+pub use heartbeat::{DeviceEdge, FlapGuard};
 pub use interrupts::Irqs;
 pub use resources::*;
 pub use status::SysMode;
@@ -44,13 +67,15 @@ mod private {
     pub use assign_resources::assign_resources;
     pub use utils::{atomic, prelude::*, strum::FromRepr};
 
-    pub use super::heartbeat::HeartBeat;
+    pub use super::heartbeat::{DeviceEdge, FlapGuard, HeartBeat};
     pub use super::{Device, WATCH_LIST};
 
+    pub use sync::{blocking_mutex::raw::CriticalSectionRawMutex, signal::Signal};
+
     pub use hal::bind_interrupts;
     pub use hal::{Peri, peripherals};
     pub use time::Ticker;
 
     pub use atomic::Ordering::Relaxed as Order;
-    pub use atomic::{AtomicBool, AtomicI8};
+    pub use atomic::{AtomicBool, AtomicI8, AtomicU8};
 }
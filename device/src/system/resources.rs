@@ -85,6 +85,11 @@ assign_resources! {
         // todo: fix this
     }
 
+    /// for `Key` task, the user key (active low).
+    key: KeySrc {
+        key: PA0,
+    }
+
     buzzer: BuzzerSrc {
         tim_p: TIM4,
         ch3: PD14,
@@ -0,0 +1,188 @@
+//!
+//! # Buzzer Task
+//!
+//! Plays a `Song` on the TIM4 CH3 buzzer whenever one is posted to `PLAY`.
+//!
+//! On a board routing the buzzer to a pin without a timer channel, play
+//! each note with `utils::soft_pwm::tone` on a plain `Output` instead.
+//!
+
+use crate::{hal, system::*};
+
+use crate::sync::{blocking_mutex::raw, signal::Signal};
+use hal::{gpio::OutputType, time::hz, timer};
+use timer::low_level::CountingMode::EdgeAlignedUp;
+use timer::simple_pwm::{PwmPin, SimplePwm};
+
+/// Silence between consecutive notes, in ms
+const GAP_MS: u64 = 10;
+
+///
+/// # Musical Note
+///
+/// One octave from C5 to C6, plus a rest.
+///
+#[allow(dead_code)]
+#[derive(defmt::Format, Debug, Clone, Copy, PartialEq)]
+pub enum Note {
+    Rest,
+    C5,
+    D5,
+    E5,
+    F5,
+    G5,
+    A5,
+    B5,
+    C6,
+}
+
+impl Note {
+    /// Frequency in Hz, 0 for a rest
+    pub const fn hz(self) -> u32 {
+        match self {
+            Self::Rest => 0,
+            Self::C5 => 523,
+            Self::D5 => 587,
+            Self::E5 => 659,
+            Self::F5 => 698,
+            Self::G5 => 784,
+            Self::A5 => 880,
+            Self::B5 => 988,
+            Self::C6 => 1047,
+        }
+    }
+}
+
+///
+/// # Song
+///
+/// Note and duration (ms) pairs, played in order.
+///
+#[derive(defmt::Format, Debug, Clone, Copy)]
+pub struct Song(pub &'static [(Note, u16)]);
+
+impl Song {
+    /// Rising jingle played once the system is up
+    pub const STARTUP: Song = Song(&[
+        (Note::C5, 100),
+        (Note::E5, 100),
+        (Note::G5, 100),
+        (Note::C6, 200),
+    ]);
+
+    /// Low repeated tone signalling an error
+    pub const ERROR: Song = Song(&[(Note::C5, 300), (Note::Rest, 100), (Note::C5, 300)]);
+
+    /// Total playing time in ms, including the gaps between notes
+    #[allow(dead_code)]
+    pub const fn duration_ms(&self) -> u32 {
+        let mut total = 0;
+        let mut i = 0;
+        while i < self.0.len() {
+            total += self.0[i].1 as u32 + GAP_MS as u32;
+            i += 1;
+        }
+        total
+    }
+}
+
+/// Song requested for playback
+pub static PLAY: Signal<raw::CriticalSectionRawMutex, Song> = Signal::new();
+
+#[embassy_executor::task]
+pub async fn task(p: BuzzerSrc) -> ! {
+    let pin = PwmPin::new(p.ch3, OutputType::PushPull);
+    let mut pwm = SimplePwm::new(
+        p.tim_p,
+        None,
+        None,
+        Some(pin),
+        None,
+        hz(1000),
+        EdgeAlignedUp,
+    );
+    pwm.ch3().enable();
+    pwm.ch3().set_duty_cycle_fully_off();
+
+    loop {
+        let song = PLAY.wait().await;
+
+        play(song, |tone| match tone {
+            Some(freq) => {
+                pwm.set_frequency(hz(freq));
+                pwm.ch3().set_duty_cycle_percent(50);
+            }
+            None => pwm.ch3().set_duty_cycle_fully_off(),
+        })
+        .await;
+    }
+}
+
+///
+/// # Play a Song
+///
+/// Calls `tone` with the frequency of each note, and with `None` for
+/// the silence after it.
+///
+async fn play(song: Song, mut tone: impl FnMut(Option<u32>)) {
+    for &(note, ms) in song.0 {
+        if note != Note::Rest {
+            tone(Some(note.hz()));
+        }
+        utils::T::after_millis(ms as u64).await;
+
+        tone(None);
+        utils::T::after_millis(GAP_MS).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::mock_time::block_on;
+    use utils::prelude::time::Instant;
+
+    #[test]
+    fn test_note_hz() {
+        assert_eq!(Note::Rest.hz(), 0);
+        assert_eq!(Note::A5.hz(), 880);
+
+        // The scale rises, up to the C an octave above.
+        let scale = [
+            Note::C5,
+            Note::D5,
+            Note::E5,
+            Note::F5,
+            Note::G5,
+            Note::A5,
+            Note::B5,
+            Note::C6,
+        ];
+        assert!(scale.windows(2).all(|x| x[0].hz() < x[1].hz()));
+        assert_eq!(Note::C6.hz().abs_diff(2 * Note::C5.hz()), 1);
+    }
+
+    #[test]
+    fn test_duration() {
+        assert_eq!(Song::STARTUP.duration_ms(), 540);
+        assert_eq!(Song::ERROR.duration_ms(), 730);
+        assert_eq!(Song(&[]).duration_ms(), 0);
+    }
+
+    #[test]
+    fn test_play() {
+        let start = Instant::now();
+        let mut tones = vec![];
+        block_on(play(Song::ERROR, |tone| {
+            tones.push((start.elapsed().as_millis(), tone))
+        }));
+
+        // A rest stays silent, and every note is followed by a gap.
+        let c5 = Some(Note::C5.hz());
+        assert_eq!(
+            tones,
+            [(0, c5), (300, None), (410, None), (420, c5), (720, None)]
+        );
+        assert_eq!(start.elapsed().as_millis(), 730);
+    }
+}
@@ -4,12 +4,55 @@
 
 use super::private::*;
 
+/// Full-Scale Current Command in Counts
+pub const RAW_CURRENT_MAX: i16 = 16384;
+
+///
+/// # Feedback Fields
+///
+/// Split a stored feedback word into the raw position, velocity,
+/// torque current, and temperature. The word holds the CAN payload
+/// little-endian, while each field is sent big-endian.
+///
+pub const fn feedback_fields(raw: u64) -> (u16, i16, i16, u8) {
+    let pos = (raw & 0xFFFF) as u16;
+    let vel = ((raw >> 16) & 0xFFFF) as i16;
+    let tor = ((raw >> 32) & 0xFFFF) as i16;
+    let temp = ((raw >> 48) & 0xFF) as u8;
+    (pos.swap_bytes(), vel.swap_bytes(), tor.swap_bytes(), temp)
+}
+
+///
+/// # Temperature Headroom
+///
+/// Degrees left below `limit`, negative once it is exceeded.
+///
+pub const fn temp_headroom(temp: u8, limit: u8) -> i16 {
+    limit as i16 - temp as i16
+}
+
+///
+/// # Motor Calibration
+///
+/// Mechanical zero of a motor, kept in a `MemCell` placed in the
+/// `.uninit` section. That is plain SRAM, so the zero survives resets
+/// but not a power loss; until calibrated again, `DjiMotor::DEFAULT_ZERO`
+/// is used.
+///
+#[repr(C)]
+#[derive(defmt::Format, Debug, Default, Clone, Copy)]
+pub struct MotorCalibration {
+    /// Raw position in degrees reported at the mechanical zero
+    pub zero_offset_deg: f32,
+}
+
 ///
 /// # DJI M3508 Motor
 ///
 /// - Output Shaft No-Load Speed: 482rpm
 /// - Output Torque Constant: 0.3Nm/A
 /// - Reduction Ratio: 3591:187
+/// - Current Command Range: ±20A
 /// - Maximum Allowable Winding Temperature: 125℃
 ///
 /// # DJI GM6020 Motor
@@ -17,6 +60,7 @@ use super::private::*;
 /// - Output Shaft No-Load Speed: 320rpm
 /// - Output Torque Constant: 0.741Nm/A
 /// - Reduction Ratio: 1:1
+/// - Current Command Range: ±3A
 /// - Maximum Allowable Winding Temperature: 125℃
 ///
 /// **Viewed from the Shaft End, the Motor Rotates CCW**
@@ -29,6 +73,12 @@ pub trait DjiMotor {
     const TORQUE_CONSTANT: f32;
     /// Reduction Ratio (from Motor to Output Shaft)
     const REDUCTION_RATIO: f32;
+    /// Current in A at `RAW_CURRENT_MAX` Counts
+    const MAX_CURRENT: f32;
+    /// Maximum Allowable Winding Temperature in Celsius
+    const TEMP_LIMIT: u8 = 125;
+    /// Raw position in degrees at the mechanical zero, until calibrated
+    const DEFAULT_ZERO: f32 = 0.;
 
     /// Get the raw 64-bit data from the motor
     fn get_raw(&self) -> u64;
@@ -36,33 +86,123 @@ pub trait DjiMotor {
     /// Update the motor data from a byte slice
     fn update(&self, src: &Frame) -> bool;
 
+    /// Persistent calibration of this motor
+    fn calibration(&self) -> &'static MemCell<MotorCalibration>;
+
     /// Position in Degrees
     fn pos(&self) -> f32 {
-        let pos = (self.get_raw() & 0xFFFF) as u16;
-        pos.swap_bytes() as f32 * const { 360. / 8192. }
+        let (pos, ..) = feedback_fields(self.get_raw());
+        pos as f32 * const { 360. / 8192. }
     }
 
     /// Velocity in RPM
     fn vel(&self) -> f32 {
-        let vel = ((self.get_raw() >> 16) & 0xFFFF) as i16;
-        vel.swap_bytes() as f32 * const { 1. / Self::REDUCTION_RATIO }
+        let (_, vel, ..) = feedback_fields(self.get_raw());
+        vel as f32 * const { 1. / Self::REDUCTION_RATIO }
     }
 
     /// Torque in Nm
     fn tor(&self) -> f32 {
-        let tor = ((self.get_raw() >> 32) & 0xFFFF) as i16;
-        tor.swap_bytes() as f32 * const { 20. / 16384. * Self::TORQUE_CONSTANT }
+        let (_, _, tor, _) = feedback_fields(self.get_raw());
+        tor as f32 * const { Self::MAX_CURRENT / RAW_CURRENT_MAX as f32 * Self::TORQUE_CONSTANT }
+    }
+
+    ///
+    /// # Calibrate Zero
+    ///
+    /// Store the current position as the mechanical zero.
+    ///
+    fn calibrate_now(&self) {
+        let calib = MotorCalibration {
+            zero_offset_deg: self.pos(),
+        };
+
+        // Safety: the cell holds a single word, only written here and read volatilely.
+        unsafe { self.calibration().init(calib) };
+    }
+
+    /// Stored zero offset in degrees, `DEFAULT_ZERO` if not calibrated
+    fn zero_offset(&self) -> f32 {
+        // Safety: see `calibrate_now`.
+        match unsafe { self.calibration().get() } {
+            Some(x) => unsafe { x.read_volatile() }.zero_offset_deg,
+            None => Self::DEFAULT_ZERO,
+        }
+    }
+
+    /// Position in Degrees from the calibrated zero, in [0, 360)
+    fn pos_calibrated(&self) -> f32 {
+        let pos = (self.pos() - self.zero_offset()) % 360.;
+        if pos < 0. { pos + 360. } else { pos }
+    }
+
+    ///
+    /// # Current to Counts
+    ///
+    /// Convert a current in A to the raw command for `DjiCtrl::set_cur`,
+    /// clamped to `±RAW_CURRENT_MAX`.
+    ///
+    fn amps_to_raw(amps: f32) -> i16 {
+        const MAX: f32 = RAW_CURRENT_MAX as f32;
+        (amps * const { MAX / Self::MAX_CURRENT }).clamp(-MAX, MAX) as i16
+    }
+
+    /// Convert a raw current command back to A
+    fn raw_to_amps(raw: i16) -> f32 {
+        raw as f32 * const { Self::MAX_CURRENT / RAW_CURRENT_MAX as f32 }
     }
 
     /// Temperature in Celsius
     fn temp(&self) -> u8 {
-        ((self.get_raw() >> 48) & 0xFF) as u8
+        let (.., temp) = feedback_fields(self.get_raw());
+        temp
+    }
+
+    /// Degrees left below `TEMP_LIMIT`, negative once exceeded
+    fn temp_headroom(&self) -> i16 {
+        temp_headroom(self.temp(), Self::TEMP_LIMIT)
+    }
+
+    /// Whether the winding has reached `TEMP_LIMIT`
+    fn is_overheating(&self) -> bool {
+        self.temp_headroom() <= 0
+    }
+}
+
+///
+/// # Control Group
+///
+/// Each control frame drives four motors of one kind, selected by
+/// their ID switch: ids 1-4 form the low group, ids 5-8 the high one.
+/// The GM6020 groups use the current-control frames.
+///
+#[derive(defmt::Format, Debug, Clone, Copy, PartialEq)]
+pub enum CtrlGroup {
+    /// M3508 (C620) ids 1-4
+    M3508Low,
+    /// M3508 (C620) ids 5-8
+    M3508High,
+    /// GM6020 ids 1-4
+    Gm6020Low,
+    /// GM6020 ids 5-7
+    Gm6020High,
+}
+
+impl CtrlGroup {
+    /// Control Command ID of this group
+    pub const fn canid(self) -> u16 {
+        match self {
+            Self::M3508Low => 0x200,
+            Self::M3508High => 0x1FF,
+            Self::Gm6020Low => 0x1FE,
+            Self::Gm6020High => 0x2FE,
+        }
     }
 }
 
 pub trait DjiCtrl {
-    /// Control Command ID
-    const CANID: u16;
+    /// Motors driven by this controller
+    const GROUP: CtrlGroup;
 
     /// Set the current for four motors (A, B, C, D)
     fn set_cur(current: (i16, i16, i16, i16)) -> Frame {
@@ -72,13 +212,13 @@ pub trait DjiCtrl {
         let crt_4 = current.3.to_be_bytes();
 
         Frame::new_standard(
-            Self::CANID,
+            Self::GROUP.canid(),
             &[
                 // Safety: all slices are of length 2
-                crt_1[0], crt_1[1], // Motor A, for id 1 (+4)
-                crt_2[0], crt_2[1], // Motor B, for id 2 (+4)
-                crt_3[0], crt_3[1], // Motor C, for id 3 (+4)
-                crt_4[0], crt_4[1], // Motor D, for id 4 (+4)
+                crt_1[0], crt_1[1], // Motor A, for id 1 (+4 in the high group)
+                crt_2[0], crt_2[1], // Motor B, for id 2 (+4 in the high group)
+                crt_3[0], crt_3[1], // Motor C, for id 3 (+4 in the high group)
+                crt_4[0], crt_4[1], // Motor D, for id 4 (+4 in the high group)
             ],
         )
         .unwrap()
@@ -87,15 +227,15 @@ pub trait DjiCtrl {
 
 #[macro_export]
 macro_rules! dji_motor {
-    ($name:ident, $mstid:expr, 3508) => {
-        $crate::dji_motor!($name, $mstid, 0.3, 3591.0 / 187.0);
+    ($name:ident, $mstid:expr, 3508 $(, zero = $zero:expr)?) => {
+        $crate::dji_motor!($name, $mstid, 0.3, 3591.0 / 187.0, 20.0 $(, zero = $zero)?);
     };
 
-    ($name:ident, $mstid:expr, 6020) => {
-        $crate::dji_motor!($name, $mstid, 0.741, 1.0);
+    ($name:ident, $mstid:expr, 6020 $(, zero = $zero:expr)?) => {
+        $crate::dji_motor!($name, $mstid, 0.741, 1.0, 3.0 $(, zero = $zero)?);
     };
 
-    ($name:ident, $mstid:expr, $torque:expr, $reduction:expr) => {
+    ($name:ident, $mstid:expr, $torque:expr, $reduction:expr, $current:expr $(, zero = $zero:expr)?) => {
         #[non_exhaustive]
         pub struct $name(AtomicU64);
 
@@ -112,6 +252,8 @@ macro_rules! dji_motor {
 
             const TORQUE_CONSTANT: f32 = $torque;
             const REDUCTION_RATIO: f32 = $reduction;
+            const MAX_CURRENT: f32 = $current;
+            $(const DEFAULT_ZERO: f32 = $zero;)?
 
             fn get_raw(&self) -> u64 {
                 self.0.load(Order)
@@ -132,6 +274,12 @@ macro_rules! dji_motor {
                 self.0.store(raw, Order);
                 true
             }
+
+            fn calibration(&self) -> &'static MemCell<MotorCalibration> {
+                #[unsafe(link_section = ".uninit.CALIBRATION")]
+                static CALIBRATION: MemCell<MotorCalibration> = MemCell::uninit();
+                &CALIBRATION
+            }
         }
 
         impl defmt::Format for $name {
@@ -149,3 +297,162 @@ macro_rules! dji_motor {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hal::can::Id;
+
+    crate::dji_motor!(Chassis, 0x201, 3508);
+    crate::dji_motor!(Gimbal, 0x205, 6020);
+
+    /// Feedback frame of `id` with the raw torque current `tor`
+    fn feedback(id: u16, tor: i16) -> Frame {
+        let t = tor.to_be_bytes();
+        Frame::new_standard(id, &[0x10, 0x00, 0x00, 0x00, t[0], t[1], 40, 0]).unwrap()
+    }
+
+    /// 180°, -100 RPM, 2048 torque counts and 40°C, big-endian fields
+    const KNOWN: [u8; 8] = [0x10, 0x00, 0xFF, 0x9C, 0x08, 0x00, 0x28, 0x00];
+
+    #[test]
+    fn test_feedback_fields() {
+        let (pos, vel, tor, temp) = feedback_fields(u64::from_le_bytes(KNOWN));
+        assert_eq!((pos, vel, tor, temp), (4096, -100, 2048, 40));
+    }
+
+    #[test]
+    fn test_feedback_3508() {
+        let motor = Chassis(AtomicU64::new(0));
+        assert!(motor.update(&Frame::new_standard(0x201, &KNOWN).unwrap()));
+
+        assert_eq!(motor.pos(), 180.);
+        // Signed, and scaled down to the output shaft
+        assert!((motor.vel() + 100. * 187. / 3591.).abs() < 1e-4);
+        assert!((motor.tor() - 2048. / 16384. * 20. * 0.3).abs() < 1e-4);
+        assert_eq!(motor.temp(), 40);
+    }
+
+    #[test]
+    fn test_feedback_6020() {
+        let motor = Gimbal(AtomicU64::new(0));
+        assert!(motor.update(&Frame::new_standard(0x205, &KNOWN).unwrap()));
+
+        assert_eq!(motor.pos(), 180.);
+        // Direct drive, the motor speed as is
+        assert_eq!(motor.vel(), -100.);
+        assert!((motor.tor() - 2048. / 16384. * 3. * 0.741).abs() < 1e-4);
+        assert_eq!(motor.temp(), 40);
+
+        // Short frames are rejected and leave the state as it was.
+        assert!(!motor.update(&Frame::new_standard(0x205, &KNOWN[..6]).unwrap()));
+        assert_eq!(motor.get_raw(), u64::from_le_bytes(KNOWN));
+    }
+
+    /// Headroom and overheating of `motor` fed a frame at `temp` °C
+    fn thermal<M: DjiMotor>(motor: &M, temp: u8) -> (i16, bool) {
+        let frame = [0x10, 0x00, 0x00, 0x00, 0x00, 0x00, temp, 0];
+        assert!(motor.update(&Frame::new_standard(M::MSTID, &frame).unwrap()));
+        (motor.temp_headroom(), motor.is_overheating())
+    }
+
+    #[test]
+    fn test_overheating() {
+        let chassis = Chassis(AtomicU64::new(0));
+        let gimbal = Gimbal(AtomicU64::new(0));
+
+        // Both types share the 125°C winding limit.
+        assert_eq!(thermal(&chassis, 124), (1, false));
+        assert_eq!(thermal(&chassis, 125), (0, true));
+        assert_eq!(thermal(&gimbal, 124), (1, false));
+        assert_eq!(thermal(&gimbal, 126), (-1, true));
+
+        assert_eq!(thermal(&gimbal, 0), (125, false));
+        assert_eq!(thermal(&chassis, 255), (-130, true));
+    }
+
+    /// Id and payload of the frame `set_cur` builds for `G`
+    fn set_cur<G: DjiCtrl>(current: (i16, i16, i16, i16)) -> (u16, [u8; 8]) {
+        let frame = G::set_cur(current);
+        let Id::Standard(id) = frame.id() else {
+            panic!("Extended control frame: {:?}", frame);
+        };
+        (id.as_raw(), frame.data().try_into().unwrap())
+    }
+
+    #[test]
+    fn test_ctrl_groups() {
+        macro_rules! ctrl {
+            ($name:ident, $group:ident) => {
+                struct $name;
+                impl DjiCtrl for $name {
+                    const GROUP: CtrlGroup = CtrlGroup::$group;
+                }
+            };
+        }
+        ctrl!(M3508Low, M3508Low);
+        ctrl!(M3508High, M3508High);
+        ctrl!(Gm6020Low, Gm6020Low);
+        ctrl!(Gm6020High, Gm6020High);
+
+        // Frame ids from the C620 and GM6020 manuals
+        let cur = (1, 2, 3, 4);
+        assert_eq!(set_cur::<M3508Low>(cur).0, 0x200);
+        assert_eq!(set_cur::<M3508High>(cur).0, 0x1FF);
+        assert_eq!(set_cur::<Gm6020Low>(cur).0, 0x1FE);
+        assert_eq!(set_cur::<Gm6020High>(cur).0, 0x2FE);
+
+        // Each current big-endian, motor A first
+        let (_, data) = set_cur::<Gm6020Low>((0x0102, -2, 0, RAW_CURRENT_MAX));
+        assert_eq!(data, [0x01, 0x02, 0xFF, 0xFE, 0, 0, 0x40, 0x00]);
+    }
+
+    #[test]
+    fn test_calibration() {
+        crate::dji_motor!(Calibrated, 0x206, 6020, zero = 90.);
+
+        // Safety: the cell is only used by this test.
+        unsafe { Calibrated::get().calibration().invalidate() };
+        let motor = Calibrated::get();
+
+        // About 170°, raw 3868
+        let raw = (170. / 360. * 8192.) as u16;
+        let p = raw.to_be_bytes();
+        let frame = Frame::new_standard(0x206, &[p[0], p[1], 0, 0, 0, 0, 40, 0]).unwrap();
+        assert!(motor.update(&frame));
+        let pos = motor.pos();
+        assert!((pos - 170.).abs() < 0.05);
+
+        // The default zero until calibrated
+        assert!((motor.pos_calibrated() - (pos - 90.)).abs() < 1e-3);
+
+        motor.calibrate_now();
+        assert_eq!(motor.zero_offset(), pos);
+        assert_eq!(motor.pos_calibrated(), 0.);
+    }
+
+    #[test]
+    fn test_current_scale() {
+        assert_eq!(Chassis::amps_to_raw(0.), 0);
+        assert_eq!(Chassis::amps_to_raw(20.), RAW_CURRENT_MAX);
+        assert_eq!(Chassis::amps_to_raw(-10.), -RAW_CURRENT_MAX / 2);
+        assert_eq!(Gimbal::amps_to_raw(3.), RAW_CURRENT_MAX);
+        assert_eq!(Gimbal::raw_to_amps(-RAW_CURRENT_MAX), -3.);
+
+        // Over-range commands saturate.
+        assert_eq!(Chassis::amps_to_raw(25.), RAW_CURRENT_MAX);
+        assert_eq!(Gimbal::amps_to_raw(-100.), -RAW_CURRENT_MAX);
+    }
+
+    #[test]
+    fn test_torque_scale() {
+        // Full-scale current times the torque constant, per motor
+        let chassis = Chassis(AtomicU64::new(0));
+        assert!(chassis.update(&feedback(0x201, RAW_CURRENT_MAX)));
+        assert!((chassis.tor() - 20. * 0.3).abs() < 1e-4);
+
+        let gimbal = Gimbal(AtomicU64::new(0));
+        assert!(gimbal.update(&feedback(0x205, -RAW_CURRENT_MAX / 2)));
+        assert!((gimbal.tor() + 1.5 * 0.741).abs() < 1e-4);
+    }
+}
@@ -29,9 +29,11 @@
 //!
 
 use super::private::*;
+use crate::hal::can::Id;
+use crate::time::{Duration, with_timeout};
 
 #[repr(u8)]
-#[derive(defmt::Format, Debug, PartialEq)]
+#[derive(defmt::Format, Debug, PartialEq, Eq, Clone, Copy)]
 pub enum DaMiaoState {
     Disabled = 0x0,
     Enabled = 0x1,
@@ -47,6 +49,71 @@ pub enum DaMiaoState {
     IncorrectID = 0xFF,
 }
 
+impl DaMiaoState {
+    ///
+    /// # Fault Severity
+    ///
+    /// Higher values are more critical, so a supervisor can
+    /// pick the worst state across several motors with `max()`.
+    ///
+    pub const fn severity(&self) -> u8 {
+        match self {
+            Self::Enabled => 0,
+            Self::Disabled => 1,
+            Self::ConnectionLost => 2,
+            Self::UnderVoltage => 3,
+            Self::OverLoad => 4,
+            Self::OverTempROT => 5,
+            Self::OverTempMOS => 6,
+            Self::OverVoltage => 7,
+            Self::OverCurrent => 8,
+            Self::IncorrectID => 9,
+        }
+    }
+}
+
+impl DaMiaoState {
+    ///
+    /// # From Error Code
+    ///
+    /// Decode the state nibble of a feedback frame, unknown codes
+    /// are reported as `IncorrectID`.
+    ///
+    pub const fn from_code(err: u8) -> Self {
+        match err {
+            0x0 => Self::Disabled,
+            0x1 => Self::Enabled,
+            0x8 => Self::OverVoltage,
+            0x9 => Self::UnderVoltage,
+            0xA => Self::OverCurrent,
+            0xB => Self::OverTempMOS,
+            0xC => Self::OverTempROT,
+            0xD => Self::ConnectionLost,
+            0xE => Self::OverLoad,
+            _ => Self::IncorrectID,
+        }
+    }
+}
+
+impl From<u8> for DaMiaoState {
+    fn from(err: u8) -> Self {
+        Self::from_code(err)
+    }
+}
+
+// Faults outrank a lost link, and unknown codes are the worst.
+impl PartialOrd for DaMiaoState {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DaMiaoState {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.severity().cmp(&other.severity())
+    }
+}
+
 pub trait DaMiaoConfig {
     /// Motor Master ID
     const MSTID: u16;
@@ -63,8 +130,18 @@ pub trait DaMiaoConfig {
     /// Minimum Position in rad
     const MIN_POS: f32 = -PI;
 
+    ///
     /// Assertion to ensure valid position range
-    const __: () = assert!(Self::MAX_POS > Self::MIN_POS);
+    ///
+    /// The range must be non-empty and representable within `±P_MAX`.
+    /// Evaluated for every motor declared with `damiao!`, and when
+    /// `set_pv` is instantiated, so a bad config fails to compile.
+    ///
+    const __: () = {
+        assert!(Self::MAX_POS > Self::MIN_POS, "MAX_POS must exceed MIN_POS");
+        assert!(Self::MAX_POS <= Self::P_MAX, "MAX_POS exceeds P_MAX");
+        assert!(Self::MIN_POS >= -Self::P_MAX, "MIN_POS below -P_MAX");
+    };
 }
 
 pub trait DaMiaoMotor: DaMiaoConfig {
@@ -82,18 +159,7 @@ pub trait DaMiaoMotor: DaMiaoConfig {
     /// Motor Error Status
     fn sta(&self) -> DaMiaoState {
         let err = ((self.get_raw() >> 4) & 0x0F) as u8;
-        match err {
-            0x0 => DaMiaoState::Disabled,
-            0x1 => DaMiaoState::Enabled,
-            0x8 => DaMiaoState::OverVoltage,
-            0x9 => DaMiaoState::UnderVoltage,
-            0xA => DaMiaoState::OverCurrent,
-            0xB => DaMiaoState::OverTempMOS,
-            0xC => DaMiaoState::OverTempROT,
-            0xD => DaMiaoState::ConnectionLost,
-            0xE => DaMiaoState::OverLoad,
-            _ => DaMiaoState::IncorrectID,
-        }
+        DaMiaoState::from(err)
     }
 
     /// Position in rad
@@ -129,16 +195,105 @@ pub trait DaMiaoMotor: DaMiaoConfig {
     }
 }
 
+/// Feedback request for the motor with `canid`, on the broadcast ID
+fn feedback_request(canid: u16) -> Frame {
+    let canid_l = (canid & 0xFF) as u8;
+    let canid_h = ((canid >> 8) & 0x7) as u8;
+    Frame::new_standard(
+        0x7FF, // Broadcast ID
+        &[canid_l, canid_h, 0xCC, 0],
+    )
+    .expect("Invalid CAN ID!")
+}
+
+///
+/// # Batch Feedback Request
+///
+/// Build the feedback requests for several motors.
+///
+/// A request addresses exactly one motor, so this yields one frame
+/// per ID. IDs beyond the first 8 are ignored.
+///
+pub fn request_feedback_batch(ids: &[u16]) -> Vec<Frame, 8> {
+    ids.iter().take(8).map(|&id| feedback_request(id)).collect()
+}
+
+/// Enable or disable commands sent by a confirmed sequence before giving up
+pub const CONFIRM_ATTEMPTS: u8 = 3;
+
+/// Next step of a confirmed sequence after feedback with `seen`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Verdict {
+    /// The motor reached the wanted state.
+    Done,
+    /// The command may have been missed, send it again.
+    Retry,
+    /// A fault, which a retry does not clear.
+    Fail,
+}
+
+const fn verdict(seen: DaMiaoState, want: DaMiaoState) -> Verdict {
+    if seen as u8 == want as u8 {
+        return Verdict::Done;
+    }
+
+    match seen {
+        DaMiaoState::Enabled | DaMiaoState::Disabled => Verdict::Retry,
+        _ => Verdict::Fail,
+    }
+}
+
+///
+/// # Confirmed Command
+///
+/// Send `cmd` with `tx` until a feedback frame from `rx` reports the
+/// `want` state, waiting up to `timeout` for it after each attempt.
+///
+/// Frames from other IDs sent to `rx` are skipped.
+///
+async fn confirm<M: DaMiaoMotor>(
+    motor: &M,
+    cmd: Frame,
+    want: DaMiaoState,
+    tx: impl AsyncFn(Frame),
+    mut rx: impl AsyncFnMut() -> Frame,
+    timeout: Duration,
+) -> Result<(), DaMiaoState> {
+    // Reported if the motor never answers.
+    let mut last = DaMiaoState::ConnectionLost;
+
+    for _ in 0..CONFIRM_ATTEMPTS {
+        tx(cmd).await;
+
+        let feedback = async {
+            loop {
+                let f = rx().await;
+                let from_motor = matches!(f.id(), Id::Standard(id) if id.as_raw() == M::MSTID);
+                if from_motor && motor.update(&f) {
+                    return motor.sta();
+                }
+            }
+        };
+
+        let Ok(seen) = with_timeout(timeout, feedback).await else {
+            continue;
+        };
+
+        last = seen;
+        match verdict(seen, want) {
+            Verdict::Done => return Ok(()),
+            Verdict::Retry => {}
+            Verdict::Fail => return Err(seen),
+        }
+    }
+
+    Err(last)
+}
+
 pub trait DaMiaoCtrl: DaMiaoConfig {
     /// Get Motor Feedback Frame
     fn get_fb(&self) -> Frame {
-        let canid_l = (Self::CANID & 0xFF) as u8;
-        let canid_h = ((Self::CANID >> 8) & 0x7) as u8;
-        Frame::new_standard(
-            0x7FF, // Broadcast ID
-            &[canid_l, canid_h, 0xCC, 0],
-        )
-        .expect("Invalid CAN ID!")
+        feedback_request(Self::CANID)
     }
 
     /// Enable the motor with pv mode
@@ -159,6 +314,54 @@ pub trait DaMiaoCtrl: DaMiaoConfig {
         .expect("Invalid CAN ID!")
     }
 
+    ///
+    /// # Confirmed Enable
+    ///
+    /// Enable the motor with pv mode, sending with `tx`, and wait for
+    /// feedback from `rx` reporting `Enabled`. Retried up to
+    /// `CONFIRM_ATTEMPTS` times, each waiting up to `timeout`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the last state reported, `ConnectionLost` if there was
+    /// no feedback at all. A fault is returned at once; clear it with
+    /// `clr_err` first.
+    ///
+    /// # Example
+    /// ```
+    /// let motor = XMotor::get();
+    /// let tx = async |f| CAN1_TX.send(f).await;
+    /// let rx = async || FEEDBACK.receive().await;
+    /// motor.enable_confirmed(tx, rx, Duration::from_millis(10)).await?;
+    /// ```
+    ///
+    #[allow(async_fn_in_trait)]
+    async fn enable_confirmed(
+        &self,
+        tx: impl AsyncFn(Frame),
+        rx: impl AsyncFnMut() -> Frame,
+        timeout: Duration,
+    ) -> Result<(), DaMiaoState>
+    where
+        Self: DaMiaoMotor + Sized,
+    {
+        confirm(self, self.enable(), DaMiaoState::Enabled, tx, rx, timeout).await
+    }
+
+    /// Disable the motor from pv mode, confirmed as `enable_confirmed`.
+    #[allow(async_fn_in_trait)]
+    async fn disable_confirmed(
+        &self,
+        tx: impl AsyncFn(Frame),
+        rx: impl AsyncFnMut() -> Frame,
+        timeout: Duration,
+    ) -> Result<(), DaMiaoState>
+    where
+        Self: DaMiaoMotor + Sized,
+    {
+        confirm(self, self.disable(), DaMiaoState::Disabled, tx, rx, timeout).await
+    }
+
     /// Clear Error with pv mode
     fn clr_err(&self) -> Frame {
         Frame::new_standard(
@@ -170,6 +373,9 @@ pub trait DaMiaoCtrl: DaMiaoConfig {
 
     /// Set Position (rad) and Velocity (rad/s)
     fn set_pv(&self, p: f32, v: f32) -> Frame {
+        // Force the position range check for this config.
+        let () = Self::__;
+
         let p = p.clamp(Self::MIN_POS, Self::MAX_POS);
         let pos = p.to_le_bytes();
         let vel = v.abs().to_le_bytes();
@@ -238,6 +444,9 @@ macro_rules! damiao {
 
         impl DaMiaoCtrl for $name {}
 
+        // Check the config even if `set_pv` is never called.
+        const _: () = <$name as DaMiaoConfig>::__;
+
         impl DaMiaoMotor for $name {
             fn get_raw(&self) -> u64 {
                 self.0.load(Order)
@@ -274,3 +483,172 @@ macro_rules! damiao {
 
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use utils::mock_time::block_on;
+    use utils::prelude::time::Instant;
+
+    crate::damiao!(TestMotor);
+
+    impl DaMiaoConfig for TestMotor {
+        const MSTID: u16 = 0x11;
+        const CANID: u16 = 0x01;
+        const P_MAX: f32 = 12.5;
+        const V_MAX: f32 = 30.;
+        const T_MAX: f32 = 10.;
+    }
+
+    const TIMEOUT: Duration = Duration::from_millis(10);
+
+    /// Feedback frame from `mstid` reporting `state`
+    fn feedback(mstid: u16, state: DaMiaoState) -> Frame {
+        let head = (state as u8) << 4 | TestMotor::CANID as u8;
+        Frame::new_standard(mstid, &[head, 0x80, 0, 0x80, 0, 0, 25, 25]).unwrap()
+    }
+
+    ///
+    /// Run `enable_confirmed` against scripted feedback.
+    ///
+    /// `replies[n]` is received after the `n`th command; once it runs
+    /// out, nothing more arrives. Returns the result, the commands
+    /// sent, and the time it took.
+    ///
+    fn run(replies: &[&[Frame]]) -> (Result<(), DaMiaoState>, usize, Duration) {
+        let motor = TestMotor(AtomicU64::new(0));
+        let sent = Cell::new(0);
+        let queue = RefCell::new(VecDeque::new());
+
+        let tx = async |f: Frame| {
+            assert_eq!(f.data(), motor.enable().data());
+            let n = sent.get();
+            let script = replies.get(n).copied().unwrap_or(&[]);
+            queue.replace(script.iter().copied().collect());
+            sent.set(n + 1);
+        };
+        let rx = async || {
+            let next = queue.borrow_mut().pop_front();
+            match next {
+                Some(f) => f,
+                None => core::future::pending().await,
+            }
+        };
+
+        let start = Instant::now();
+        let result = block_on(motor.enable_confirmed(tx, rx, TIMEOUT));
+        (result, sent.get(), start.elapsed())
+    }
+
+    #[test]
+    fn test_state_order() {
+        use DaMiaoState as S;
+
+        // Faults outrank a lost link, and unknown codes are the worst.
+        assert!(S::OverCurrent > S::ConnectionLost);
+        assert!(S::ConnectionLost > S::Disabled);
+        assert!(S::Disabled > S::Enabled);
+
+        let states = [S::Enabled, S::OverLoad, S::IncorrectID, S::Disabled];
+        assert_eq!(states.iter().max(), Some(&S::IncorrectID));
+        assert_eq!(states.iter().min(), Some(&S::Enabled));
+    }
+
+    #[test]
+    fn test_state_codes() {
+        let motor = TestMotor(AtomicU64::new(0));
+
+        // Defined codes round-trip, reserved ones do not.
+        for code in 0x0..=0xF {
+            let state = DaMiaoState::from(code);
+            match code {
+                0x2..=0x7 | 0xF => assert_eq!(state, DaMiaoState::IncorrectID),
+                _ => assert_eq!(state as u8, code),
+            }
+            assert!(state <= DaMiaoState::IncorrectID);
+
+            // And the same state is read back from a feedback frame.
+            assert!(motor.update(&feedback(TestMotor::MSTID, state)));
+            assert_eq!(motor.sta(), state);
+        }
+    }
+
+    #[test]
+    fn test_verdict() {
+        use DaMiaoState::*;
+
+        // Faults end the sequence; the other of enabled and disabled is retried.
+        assert!(matches!(verdict(Enabled, Enabled), Verdict::Done));
+        assert!(matches!(verdict(Disabled, Disabled), Verdict::Done));
+        assert!(matches!(verdict(Disabled, Enabled), Verdict::Retry));
+        assert!(matches!(verdict(Enabled, Disabled), Verdict::Retry));
+        assert!(matches!(verdict(OverVoltage, Enabled), Verdict::Fail));
+        assert!(matches!(verdict(ConnectionLost, Enabled), Verdict::Fail));
+    }
+
+    #[test]
+    fn test_request_feedback_batch() {
+        let frames = request_feedback_batch(&[0x01, 0x02, 0x103]);
+        assert_eq!(frames.len(), 3);
+
+        // One request per motor, on the broadcast ID: CANID low, high, 0xCC
+        let payloads: [[u8; 4]; 3] = [[0x01, 0, 0xCC, 0], [0x02, 0, 0xCC, 0], [0x03, 1, 0xCC, 0]];
+        for (f, payload) in frames.iter().zip(payloads) {
+            assert!(matches!(f.id(), Id::Standard(id) if id.as_raw() == 0x7FF));
+            assert_eq!(f.data(), &payload);
+        }
+
+        // IDs beyond the first 8 are ignored.
+        let ids: [u16; 10] = core::array::from_fn(|i| i as u16);
+        assert_eq!(request_feedback_batch(&ids).len(), 8);
+        assert!(request_feedback_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_confirm_first_attempt() {
+        let other = feedback(0x12, DaMiaoState::OverCurrent);
+        let enabled = feedback(TestMotor::MSTID, DaMiaoState::Enabled);
+
+        // Frames of other motors are skipped.
+        let (result, sent, took) = run(&[&[other, enabled]]);
+        assert_eq!((result, sent), (Ok(()), 1));
+        assert_eq!(took, Duration::from_ticks(0));
+    }
+
+    #[test]
+    fn test_confirm_retry() {
+        let disabled = feedback(TestMotor::MSTID, DaMiaoState::Disabled);
+        let enabled = feedback(TestMotor::MSTID, DaMiaoState::Enabled);
+
+        // Still disabled, then no answer, then enabled on the last attempt
+        let (result, sent, took) = run(&[&[disabled], &[], &[enabled]]);
+        assert_eq!((result, sent), (Ok(()), CONFIRM_ATTEMPTS as usize));
+        assert_eq!(took, TIMEOUT);
+
+        // Still disabled after every attempt
+        let (result, sent, _) = run(&[&[disabled], &[disabled], &[disabled]]);
+        assert_eq!((result, sent), (Err(DaMiaoState::Disabled), 3));
+    }
+
+    #[test]
+    fn test_confirm_fault() {
+        let fault = feedback(TestMotor::MSTID, DaMiaoState::OverVoltage);
+
+        // A fault is not retried.
+        let (result, sent, _) = run(&[&[fault]]);
+        assert_eq!((result, sent), (Err(DaMiaoState::OverVoltage), 1));
+    }
+
+    #[test]
+    fn test_confirm_timeout() {
+        let other = feedback(0x12, DaMiaoState::Enabled);
+
+        // No feedback from this motor at all
+        let (result, sent, took) = run(&[&[other], &[other], &[other]]);
+        assert_eq!(result, Err(DaMiaoState::ConnectionLost));
+        assert_eq!(sent, CONFIRM_ATTEMPTS as usize);
+        assert_eq!(took, TIMEOUT * CONFIRM_ATTEMPTS as u32);
+    }
+}
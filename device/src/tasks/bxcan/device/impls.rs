@@ -1,4 +1,38 @@
+//!
+//! # Motor Declarations
+//!
+//! Two motors sharing a feedback ID would silently overwrite each
+//! other's state, so every declared motor must also be listed in
+//! `FEEDBACK_IDS`. A duplicate ID fails the build.
+//!
+
 use super::private::*;
 use crate::dji_motor;
 
-dji_motor!(DMotor, 0x205, 6020);
+// Mounted with the gimbal level at 170° raw, until calibrated
+dji_motor!(DMotor, 0x205, 6020, zero = 170.);
+
+/// Feedback IDs of all declared motors, checked for collisions.
+const FEEDBACK_IDS: &[u16] = &[
+    DMotor::MSTID,
+    // XMotor::MSTID,
+];
+
+const _: () = assert_unique(FEEDBACK_IDS);
+
+///
+/// # Assert Unique IDs
+///
+/// Panics (at compile time when used in a `const`) if any ID repeats.
+///
+pub(super) const fn assert_unique(ids: &[u16]) {
+    let mut i = 0;
+    while i < ids.len() {
+        let mut j = i + 1;
+        while j < ids.len() {
+            assert!(ids[i] != ids[j], "Duplicate Motor Feedback ID");
+            j += 1;
+        }
+        i += 1;
+    }
+}
@@ -2,6 +2,8 @@
 //! # Device Modules
 //!
 
+#![allow(dead_code)]
+
 pub use dajiang::*;
 pub use damiao::*;
 pub use impls::*;
@@ -15,5 +17,7 @@ mod private {
     pub use crate::hal::can::Frame;
     pub use Ordering::Relaxed as Order;
     pub use core::f32::consts::*;
+    pub use utils::MemCell;
     pub use utils::atomic::{AtomicU64, Ordering};
+    pub use utils::heapless::Vec;
 }
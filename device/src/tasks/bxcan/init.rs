@@ -1,28 +1,170 @@
 use crate::{hal::can, system::*};
 
 use utils::{
-    MemCell,
+    MemCellArray,
     atomic::{AtomicBool, Ordering},
 };
 
-use can::{BufferedCan, Can, Fifo, RxBuf, TxBuf, filter::Mask32};
+use can::{BufferedCan, Can, ExtendedId, Fifo, RxBuf, StandardId, TxBuf, filter::Mask32};
 
 const TX_BUF_SIZE: usize = 25;
 const RX_BUF_SIZE: usize = 10;
 
-static CAN1_TX_BUF: MemCell<TxBuf<TX_BUF_SIZE>> = MemCell::uninit();
-static CAN1_RX_BUF: MemCell<RxBuf<RX_BUF_SIZE>> = MemCell::uninit();
-static CAN2_TX_BUF: MemCell<TxBuf<TX_BUF_SIZE>> = MemCell::uninit();
-static CAN2_RX_BUF: MemCell<RxBuf<RX_BUF_SIZE>> = MemCell::uninit();
+/// Filter Banks Shared by CAN1 and CAN2
+const FILTER_BANKS: usize = 28;
 
-static UNBUFED_CAN1: MemCell<Can<'static>> = MemCell::uninit();
-static UNBUFED_CAN2: MemCell<Can<'static>> = MemCell::uninit();
+///
+/// # Filter Specification
+///
+/// An ID and mask pair routed to a receive FIFO.
+/// Mask bits set to 1 must match the ID; IDs are truncated
+/// to 11 (standard) or 29 (extended) bits.
+///
+#[derive(Debug, Clone, Copy)]
+pub enum FilterSpec {
+    AcceptAll(Fifo),
+    Standard { id: u16, mask: u16, fifo: Fifo },
+    Extended { id: u32, mask: u32, fifo: Fifo },
+}
+
+impl FilterSpec {
+    /// FIFO receiving the matched frames
+    pub const fn fifo(&self) -> Fifo {
+        match *self {
+            Self::AcceptAll(fifo) => fifo,
+            Self::Standard { fifo, .. } | Self::Extended { fifo, .. } => fifo,
+        }
+    }
+
+    ///
+    /// # Match a Frame ID
+    ///
+    /// Whether a frame with the raw `id` passes this filter,
+    /// the same way the hardware compares it.
+    ///
+    pub const fn accepts(&self, id: u32, extended: bool) -> bool {
+        match *self {
+            Self::AcceptAll(_) => true,
+            Self::Standard { id: sid, mask, .. } => {
+                !extended && (id ^ sid as u32) & mask as u32 & 0x7FF == 0
+            }
+            Self::Extended { id: eid, mask, .. } => {
+                extended && (id ^ eid) & mask & 0x1FFF_FFFF == 0
+            }
+        }
+    }
+
+    /// Translate into a 32-bit mask filter
+    pub fn mask32(&self) -> Mask32 {
+        // safe: values are masked to the valid ID width
+        let sid = |x: u16| StandardId::new(x & StandardId::MAX.as_raw()).unwrap();
+        let eid = |x: u32| ExtendedId::new(x & ExtendedId::MAX.as_raw()).unwrap();
+
+        match *self {
+            Self::AcceptAll(_) => Mask32::accept_all(),
+            Self::Standard { id, mask, .. } => Mask32::frames_with_std_id(sid(id), sid(mask)),
+            Self::Extended { id, mask, .. } => Mask32::frames_with_ext_id(eid(id), eid(mask)),
+        }
+    }
+}
+
+///
+/// # CAN Bus Configuration
+///
+/// Defaults to 1Mbps, automatic retransmission, and
+/// accepting all frames into FIFO 0.
+///
+#[derive(Debug, Clone, Copy)]
+pub struct CanConfig {
+    pub bitrate: u32,
+    pub filters: &'static [FilterSpec],
+    pub automatic_retransmit: bool,
+}
+
+impl CanConfig {
+    pub const fn new() -> Self {
+        Self {
+            bitrate: 1_000_000,
+            filters: &[FilterSpec::AcceptAll(Fifo::Fifo0)],
+            automatic_retransmit: true,
+        }
+    }
+
+    pub const fn bitrate(mut self, bitrate: u32) -> Self {
+        self.bitrate = bitrate;
+        self
+    }
+
+    pub const fn filters(mut self, filters: &'static [FilterSpec]) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    pub const fn automatic_retransmit(mut self, enabled: bool) -> Self {
+        self.automatic_retransmit = enabled;
+        self
+    }
+
+    ///
+    /// # Route a Frame ID
+    ///
+    /// FIFO a frame with the raw `id` is stored in, `None` if no
+    /// filter accepts it. Lower banks take priority, as in hardware,
+    /// so list specific filters before catch-all ones.
+    ///
+    pub const fn route(&self, id: u32, extended: bool) -> Option<Fifo> {
+        let mut i = 0;
+        while i < self.filters.len() {
+            if self.filters[i].accepts(id, extended) {
+                return Some(self.filters[i].fifo());
+            }
+            i += 1;
+        }
+        None
+    }
+}
+
+// Routing follows bank order and respects the ID kind and width.
+const _: () = {
+    const CFG: CanConfig = CanConfig::new().filters(&[
+        FilterSpec::Standard {
+            id: 0x200,
+            mask: 0x7F0,
+            fifo: Fifo::Fifo0,
+        },
+        FilterSpec::Extended {
+            id: 0x11,
+            mask: 0xFF,
+            fifo: Fifo::Fifo0,
+        },
+        FilterSpec::Standard {
+            id: 0x100,
+            mask: 0x700,
+            fifo: Fifo::Fifo1,
+        },
+    ]);
+    assert!(matches!(CFG.route(0x205, false), Some(Fifo::Fifo0)));
+    assert!(matches!(CFG.route(0x1_0211, true), Some(Fifo::Fifo0)));
+    assert!(matches!(CFG.route(0x1AB, false), Some(Fifo::Fifo1)));
+    assert!(CFG.route(0x205, true).is_none() && CFG.route(0x300, false).is_none());
+
+    const ALL: CanConfig = CanConfig::new();
+    assert!(matches!(ALL.route(0x1FFF_FFFF, true), Some(Fifo::Fifo0)));
+};
 
-static BUFFERED_CAN1: MemCell<BufferedCan<'static, TX_BUF_SIZE, RX_BUF_SIZE>> = MemCell::uninit();
-static BUFFERED_CAN2: MemCell<BufferedCan<'static, TX_BUF_SIZE, RX_BUF_SIZE>> = MemCell::uninit();
+// Cell 0 is for CAN1, cell 1 is for CAN2.
+static TX_BUF: MemCellArray<TxBuf<TX_BUF_SIZE>, 2> = MemCellArray::uninit();
+static RX_BUF: MemCellArray<RxBuf<RX_BUF_SIZE>, 2> = MemCellArray::uninit();
+
+static UNBUFED_CAN: MemCellArray<Can<'static>, 2> = MemCellArray::uninit();
+
+static BUFFERED_CAN: MemCellArray<BufferedCan<'static, TX_BUF_SIZE, RX_BUF_SIZE>, 2> =
+    MemCellArray::uninit();
 
 pub(super) async fn bxcan_init(
     p: CanSrc,
+    cfg1: &CanConfig,
+    cfg2: &CanConfig,
 ) -> (
     &'static BufferedCan<'static, TX_BUF_SIZE, RX_BUF_SIZE>,
     &'static BufferedCan<'static, TX_BUF_SIZE, RX_BUF_SIZE>,
@@ -30,23 +172,36 @@ pub(super) async fn bxcan_init(
     let mut can1 = Can::new(p.can1_p, p.can1_rx, p.can1_tx, Irqs);
     let mut can2 = Can::new(p.can2_p, p.can2_rx, p.can2_tx, Irqs);
 
-    // Only master can(1) has filters.
-    can1.modify_filters()
-        .enable_bank(0, Fifo::Fifo0, Mask32::accept_all())
-        .enable_bank(1, Fifo::Fifo1, Mask32::accept_all())
-        .set_split(1);
+    let split = cfg1.filters.len();
+    assert!(
+        split + cfg2.filters.len() <= FILTER_BANKS,
+        "Too Many CAN Filters"
+    );
+
+    // Only master can(1) has filters, banks after the split belong to can(2).
+    let mut filters = can1.modify_filters();
+    for (i, spec) in cfg1.filters.iter().enumerate() {
+        filters.enable_bank(i as u8, spec.fifo(), spec.mask32());
+    }
+    filters.set_split(split as u8);
+
+    let mut slave = filters.slave_filters();
+    for (i, spec) in cfg2.filters.iter().enumerate() {
+        slave.enable_bank((split + i) as u8, spec.fifo(), spec.mask32());
+    }
+    drop(filters);
 
     can1.modify_config()
         // .set_loopback(true)
         .set_silent(false)
-        .set_bitrate(1_000_000)
-        .set_automatic_retransmit(true);
+        .set_bitrate(cfg1.bitrate)
+        .set_automatic_retransmit(cfg1.automatic_retransmit);
 
     can2.modify_config()
         // .set_loopback(true)
         .set_silent(false)
-        .set_bitrate(1_000_000)
-        .set_automatic_retransmit(true);
+        .set_bitrate(cfg2.bitrate)
+        .set_automatic_retransmit(cfg2.automatic_retransmit);
 
     (can1.enable().await, can2.enable().await);
 
@@ -67,32 +222,84 @@ unsafe fn can_buffer_init(
         panic!("Can Buffers Have Already Been Taken");
     }
 
-    // Safety: Static Can Instances are only initialized here.
-    let can1 = unsafe { &mut *UNBUFED_CAN1.init(can1) };
-    // Safety: Static Can Instances are only initialized here.
-    let can2 = unsafe { &mut *UNBUFED_CAN2.init(can2) };
+    // Safety: Static cells are only initialized here, once per index.
+    unsafe {
+        let can1 = &mut *UNBUFED_CAN.init(0, can1);
+        let can2 = &mut *UNBUFED_CAN.init(1, can2);
 
-    // Safety: Static buffers are only initialized here.
-    let can1_buffer = unsafe {
-        (
-            &mut *CAN1_TX_BUF.init(TxBuf::new()),
-            &mut *CAN1_RX_BUF.init(RxBuf::new()),
-        )
-    };
+        let can1_buffer = (
+            &mut *TX_BUF.init(0, TxBuf::new()),
+            &mut *RX_BUF.init(0, RxBuf::new()),
+        );
+        let can2_buffer = (
+            &mut *TX_BUF.init(1, TxBuf::new()),
+            &mut *RX_BUF.init(1, RxBuf::new()),
+        );
 
-    // Safety: Static buffers are only initialized here.
-    let can2_buffer = unsafe {
         (
-            &mut *CAN2_TX_BUF.init(TxBuf::new()),
-            &mut *CAN2_RX_BUF.init(RxBuf::new()),
+            &*BUFFERED_CAN.init(0, can1.buffered(can1_buffer.0, can1_buffer.1)),
+            &*BUFFERED_CAN.init(1, can2.buffered(can2_buffer.0, can2_buffer.1)),
         )
-    };
+    }
+}
 
-    // Safety: Static BufferedCan Instances are only initialized here.
-    unsafe {
-        (
-            &*BUFFERED_CAN1.init(can1.buffered(can1_buffer.0, can1_buffer.1)),
-            &*BUFFERED_CAN2.init(can2.buffered(can2_buffer.0, can2_buffer.1)),
-        )
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Register values of a mask, by `Debug` as they are private
+    fn regs(mask: Mask32) -> String {
+        format!("{mask:?}")
+    }
+
+    #[test]
+    fn test_mask32() {
+        let sid = |x| StandardId::new(x).unwrap();
+        let eid = |x| ExtendedId::new(x).unwrap();
+        let fifo = Fifo::Fifo0;
+
+        let std = FilterSpec::Standard {
+            id: 0x205,
+            mask: 0x7F0,
+            fifo,
+        };
+        let want = Mask32::frames_with_std_id(sid(0x205), sid(0x7F0));
+        assert_eq!(regs(std.mask32()), regs(want));
+
+        let ext = FilterSpec::Extended {
+            id: 0x205,
+            mask: 0x7F0,
+            fifo,
+        };
+        let want = Mask32::frames_with_ext_id(eid(0x205), eid(0x7F0));
+        assert_eq!(regs(ext.mask32()), regs(want));
+        assert_ne!(regs(ext.mask32()), regs(std.mask32()));
+
+        let all = FilterSpec::AcceptAll(Fifo::Fifo1);
+        assert_eq!(regs(all.mask32()), regs(Mask32::accept_all()));
+    }
+
+    #[test]
+    fn test_mask32_truncates() {
+        let sid = |x| StandardId::new(x).unwrap();
+        let eid = |x| ExtendedId::new(x).unwrap();
+        let fifo = Fifo::Fifo0;
+
+        // Bits past the ID width are dropped, not rejected.
+        let std = FilterSpec::Standard {
+            id: 0xF205,
+            mask: 0xFFFF,
+            fifo,
+        };
+        let want = Mask32::frames_with_std_id(sid(0x205), sid(0x7FF));
+        assert_eq!(regs(std.mask32()), regs(want));
+
+        let ext = FilterSpec::Extended {
+            id: 0xE000_0011,
+            mask: u32::MAX,
+            fifo,
+        };
+        let want = Mask32::frames_with_ext_id(eid(0x11), eid(0x1FFF_FFFF));
+        assert_eq!(regs(ext.mask32()), regs(want));
     }
 }
@@ -2,21 +2,62 @@
 //! # BxCAN Task
 //!
 
-use crate::system::*;
+use crate::{hal::can::Fifo, system::*};
 
 mod device;
 mod init;
 mod msger;
 
+use init::{CanConfig, FilterSpec};
+
+///
+/// # CAN1 Settings
+///
+/// Motor feedback (0x200 - 0x20F) goes to FIFO 0 and everything else
+/// to FIFO 1. Each FIFO has its own receive path: FIFO 0 fills the
+/// `BufferedCan` receive buffer, and `Fifo1InterruptHandler` drains
+/// FIFO 1 into a separate queue with its own task. A burst of other
+/// traffic can then neither fill the buffer motor feedback is read
+/// from, nor hold up its handling.
+///
+const CAN1_CONFIG: CanConfig = CanConfig::new().filters(&[
+    FilterSpec::Standard {
+        id: 0x200,
+        mask: 0x7F0,
+        fifo: Fifo::Fifo0,
+    },
+    FilterSpec::AcceptAll(Fifo::Fifo1),
+]);
+/// CAN2 Settings
+const CAN2_CONFIG: CanConfig = CanConfig::new().filters(&[FilterSpec::AcceptAll(Fifo::Fifo1)]);
+
+// Motor feedback takes the high priority path on CAN1.
+const _: () = {
+    assert!(matches!(
+        CAN1_CONFIG.route(DMotor::MSTID as u32, false),
+        Some(Fifo::Fifo0)
+    ));
+    assert!(matches!(
+        CAN1_CONFIG.route(0x0100, false),
+        Some(Fifo::Fifo1)
+    ));
+    assert!(matches!(CAN1_CONFIG.route(0x0205, true), Some(Fifo::Fifo1)));
+};
+
+pub use device::{CtrlGroup, DMotor, DjiCtrl, DjiMotor, RAW_CURRENT_MAX};
+pub use msger::CAN1_TX;
+pub use msger::can1_rcv::Fifo1InterruptHandler;
+
 #[embassy_executor::task]
 pub async fn task(s: embassy_executor::SendSpawner, p: CanSrc) {
-    let (can1, can2) = init::bxcan_init(p).await;
+    let (can1, can2) = init::bxcan_init(p, &CAN1_CONFIG, &CAN2_CONFIG).await;
 
     let (can1_tx, can1_rx) = (can1.writer(), can1.reader());
     let (can2_tx, can2_rx) = (can2.writer(), can2.reader());
 
-    // s.must_spawn(msger::can1_snd::sender(can1_tx));
+    s.must_spawn(msger::can1_snd::sender(can1_tx));
     s.must_spawn(msger::can1_rcv::receiver(can1_rx));
+    s.must_spawn(msger::can1_rcv::fifo1_receiver());
 
     // s.must_spawn(msger::can2_snd::sender(can2_tx));
     s.must_spawn(msger::can2_rcv::receiver(can2_rx));
@@ -1,4 +1,7 @@
 use super::private::*;
+use crate::hal::interrupt::typelevel::{CAN1_RX1, Handler};
+use crate::sync::{blocking_mutex::raw, channel::Channel};
+use crate::system::Device;
 
 #[inline]
 fn wrap_angle(radians: f32) -> f32 {
@@ -10,41 +13,192 @@ fn wrap_angle(radians: f32) -> f32 {
     x - PI
 }
 
+/// Extended ID and the feedback handler for it
+type ExtHandler = (u32, fn(&Frame) -> bool);
+
+///
+/// # Extended-ID Dispatch Table
+///
+/// Feedback handlers keyed by the 29-bit CAN ID.
+/// Each handler returns `false` if the frame could not be parsed.
+///
+/// Empty on purpose: every motor on this robot reports on a standard
+/// ID. Register a motor here once one is set to extended-ID feedback.
+///
+const EXTENDED: &[ExtHandler] = &[
+    // (0x0000_0011, |f| XMotor::get().update(f)),
+    #[cfg(test)]
+    (tests::XMOTOR_ID, |f| tests::XMotor::get().update(f)),
+];
+
+///
+/// # FIFO 1 Queue
+///
+/// Frames read from FIFO 1 by `Fifo1InterruptHandler`, apart from
+/// the `BufferedCan` receive buffer that FIFO 0 fills.
+///
+static FIFO1: Channel<raw::CriticalSectionRawMutex, Frame, 8> = Channel::new();
+
+///
+/// # CAN1 FIFO 1 Interrupt Handler
+///
+/// Drains FIFO 1 into its own queue for `fifo1_receiver`. Bind it
+/// ahead of the HAL `Rx1InterruptHandler`, which then finds FIFO 1
+/// empty, so only FIFO 0 frames reach the shared receive buffer.
+///
+pub struct Fifo1InterruptHandler;
+
+impl Handler<CAN1_RX1> for Fifo1InterruptHandler {
+    unsafe fn on_interrupt() {
+        while let Some(f) = read_fifo1() {
+            if FIFO1.try_send(f).is_err() {
+                defmt::warn!("CAN1 FIFO1 queue full, dropped: {:?}", f);
+            }
+        }
+    }
+}
+
+/// Pop the oldest frame from the CAN1 FIFO 1 mailboxes
+fn read_fifo1() -> Option<Frame> {
+    use crate::hal::can::{ExtendedId, StandardId, frame::Header};
+    use crate::hal::pac::{CAN1, can::vals};
+
+    let rfr = CAN1.rfr(1);
+    if rfr.read().fmp() == 0 {
+        return None;
+    }
+
+    let rx = CAN1.rx(1);
+    let (rir, rdtr) = (rx.rir().read(), rx.rdtr().read());
+
+    // safe: values are masked to the valid ID width
+    let id: Id = match rir.ide() {
+        vals::Ide::STANDARD => StandardId::new(rir.stid() & 0x7FF).unwrap().into(),
+        vals::Ide::EXTENDED => {
+            let id = (rir.stid() as u32 & 0x7FF) << 18 | rir.exid() & 0x3_FFFF;
+            ExtendedId::new(id).unwrap().into()
+        }
+    };
+
+    let mut data = [0; 8];
+    data[..4].copy_from_slice(&rx.rdlr().read().0.to_le_bytes());
+    data[4..].copy_from_slice(&rx.rdhr().read().0.to_le_bytes());
+
+    let header = Header::new(id, rdtr.dlc().min(8), rir.rtr() == vals::Rtr::REMOTE);
+
+    // Release the mailbox for the next frame.
+    rfr.modify(|w| w.set_rfom(true));
+
+    Frame::new(header, &data).ok()
+}
+
+///
+/// # CAN1 Receiver
+///
+/// Handles the FIFO 0 (motor feedback) frames of the receive buffer.
+///
 #[embassy_executor::task]
 pub async fn receiver(can: BufferedCanReceiver) -> ! {
-    let dmotor = DMotor::get();
-    // let mut angle = Angle::new(0f32.to_radians());
-
     loop {
         match can.receive().await.map(|x| x.frame) {
-            Ok(f) => match f.id() {
-                Id::Standard(id) => match id.as_raw() {
-                    DMotor::MSTID => {
-                        if dmotor.update(&f) {
-                            let pos = dmotor.pos();
-                            // angle.update(pos.to_radians());
-                            // defmt::info!("{} => {:?}", pos, angle);
-
-                            let angle = wrap_angle((pos - 170.).to_radians());
-                            defmt::info!("{}° =>: {}°", pos, angle.to_degrees());
-                        } else {
-                            defmt::warn!("Failed to parse DMotor frame: {:?}", f);
-                        }
-                    }
+            Ok(f) => dispatch(&f),
+            Err(e) => defmt::warn!("CAN Error: {}", e),
+        }
+    }
+}
 
-                    _ => {
-                        defmt::info!("Received S frame: {:?}", f);
-                    }
-                },
+/// Handles the FIFO 1 frames queued by `Fifo1InterruptHandler`
+#[embassy_executor::task]
+pub async fn fifo1_receiver() -> ! {
+    loop {
+        dispatch(&FIFO1.receive().await);
+    }
+}
 
-                Id::Extended(id) => match *id {
-                    _ => {
-                        defmt::info!("Received E frame: {:?}", f);
+/// Feed the sending device and update its state
+fn dispatch(f: &Frame) {
+    match f.id() {
+        Id::Standard(id) => {
+            let id = id.as_raw();
+            if let Some(device) = Device::from_can_id(id) {
+                device.feed();
+            }
+
+            match id {
+                DMotor::MSTID => {
+                    let dmotor = DMotor::get();
+                    if dmotor.update(f) {
+                        let pos = dmotor.pos();
+                        // angle.update(pos.to_radians());
+                        // defmt::info!("{} => {:?}", pos, angle);
+
+                        let angle = wrap_angle(dmotor.pos_calibrated().to_radians());
+                        utils::trace_motor!("{}° =>: {}°", pos, angle.to_degrees());
+                    } else {
+                        defmt::warn!("Failed to parse DMotor frame: {:?}", f);
                     }
-                },
-            },
+                }
 
-            Err(e) => defmt::warn!("CAN Error: {}", e),
+                _ => {
+                    defmt::info!("Received S frame: {:?}", f);
+                }
+            }
         }
+
+        Id::Extended(id) => match EXTENDED.iter().find(|x| x.0 == id.as_raw()) {
+            Some((_, update)) => {
+                if !update(f) {
+                    defmt::warn!("Failed to parse E frame: {:?}", f);
+                }
+            }
+
+            None => {
+                defmt::info!("Received E frame: {:?}", f);
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::bxcan::device::{DaMiaoConfig, DaMiaoCtrl, DaMiaoMotor, DaMiaoState};
+    use utils::atomic::AtomicU64;
+    use utils::atomic::Ordering::Relaxed as Order;
+
+    pub const XMOTOR_ID: u32 = 0x0123_4567;
+
+    crate::damiao!(XMotor);
+
+    impl DaMiaoConfig for XMotor {
+        const MSTID: u16 = 0x11;
+        const CANID: u16 = 0x01;
+        const P_MAX: f32 = 12.5;
+        const V_MAX: f32 = 30.;
+        const T_MAX: f32 = 10.;
+    }
+
+    #[test]
+    fn test_extended_dispatch() {
+        let enabled = [0x11, 0x80, 0, 0x80, 0, 0, 25, 25];
+
+        // Unregistered IDs are only logged.
+        dispatch(&Frame::new_extended(XMOTOR_ID + 1, &enabled).unwrap());
+        assert_eq!(XMotor::get().get_raw(), 0);
+
+        dispatch(&Frame::new_extended(XMOTOR_ID, &enabled).unwrap());
+        assert_eq!(XMotor::get().get_raw(), u64::from_le_bytes(enabled));
+        assert_eq!(XMotor::get().sta(), DaMiaoState::Enabled);
+    }
+
+    #[test]
+    fn test_dispatch_feeds_device() {
+        let feedback = Frame::new_standard(DMotor::MSTID, &[0x10, 0, 0, 0, 0, 0, 40, 0]).unwrap();
+
+        // Online after the flap guard's run of feeds
+        Device::DMotor.kill();
+        (0..3).for_each(|_| dispatch(&feedback));
+        assert!(Device::DMotor.check());
+        assert_eq!(DMotor::get().temp(), 40);
     }
 }
@@ -1,21 +1,246 @@
 use super::private::*;
+use crate::sync::{blocking_mutex::raw::CriticalSectionRawMutex, channel::Channel};
+use utils::heapless::{LinearMap, Vec};
+use utils::prelude::time::{Duration, Instant, with_deadline};
+use utils::rate::{KeepAlive, RateLimiter};
+
+///
+/// Shortest interval between two equal frames of the same id.
+///
+/// Only a repeat of the last payload is dropped: a batch of requests
+/// on one id, or a command right after an enable, always goes out.
+/// Well below the 1 ms control period, so a late tick followed by an
+/// on-time one is never dropped, while a burst of repeats still is.
+///
+const MIN_INTERVAL: Duration = Duration::from_micros(200);
+
+/// Control period of the gimbal loop, see `controller::main`
+const CONTROL_PERIOD: Duration = Duration::from_millis(1);
+
+// Under a quarter of the period, in whole timer ticks.
+const _: () = assert!(MIN_INTERVAL.as_ticks() * 4 < CONTROL_PERIOD.as_ticks());
+
+/// Ids rate limited at once, frames of more ids are never dropped
+const LIMITED_IDS: usize = 8;
+
+///
+/// Idle interval before the control frames are sent again.
+///
+/// Half the motor timeout, so one lost keep-alive is tolerated.
+/// `None` sends only what is posted to `CAN1_TX`.
+///
+const KEEP_ALIVE: Option<Duration> = Some(Duration::from_millis(50));
+
+///
+/// Control frame ids kept alive once posted.
+///
+/// The keep-alive is a zero command: a stale setpoint is never
+/// repeated, and requests, `clr_err` or feedback polls never are.
+///
+const KEEP_ALIVE_IDS: &[u16] = &[CtrlGroup::Gm6020Low.canid()];
+
+/// Zero command of a kept alive control id
+fn zero_command(id: u16) -> Frame {
+    // Safety: the ids are standard, and 8 bytes fit in a frame, so Unwrap is safe.
+    Frame::new_standard(id, &[0; 8]).unwrap()
+}
+
+/// Rate limit of one id, with its raw id if it is in `KEEP_ALIVE_IDS`
+struct Slot {
+    limit: RateLimiter,
+    last: Frame,
+    alive: Option<u16>,
+}
+
+impl Slot {
+    /// Whether `frame` is not a repeat of the last one within `MIN_INTERVAL`
+    fn allow(&mut self, frame: &Frame) -> bool {
+        // A new payload is never redundant, and restarts the interval.
+        if self.last.data() != frame.data() {
+            self.limit.reset();
+        }
+
+        self.last = *frame;
+        self.limit.allow()
+    }
+}
+
+///
+/// # CAN1 Send State
+///
+/// Drops repeated frames and keeps the control ids alive.
+///
+struct Sender {
+    slots: LinearMap<Id, Slot, LIMITED_IDS>,
+    idle: Option<KeepAlive>,
+}
+
+impl Sender {
+    fn new(now: Instant) -> Self {
+        Self {
+            slots: LinearMap::new(),
+            idle: KEEP_ALIVE.map(|interval| KeepAlive::new(interval, now)),
+        }
+    }
+
+    /// Next frame posted to `posts`, `None` once the keep-alive is due
+    async fn next<const N: usize>(
+        &mut self,
+        posts: &Channel<CriticalSectionRawMutex, Frame, N>,
+    ) -> Option<Frame> {
+        let posted = match &self.idle {
+            Some(idle) => with_deadline(idle.deadline(), posts.receive()).await.ok(),
+            None => Some(posts.receive().await),
+        };
+
+        if let Some(idle) = &mut self.idle {
+            idle.feed(Instant::now());
+        }
+        posted
+    }
+
+    /// Frames to write in `mode` for `posted`, or for an idle interval
+    fn frames(&mut self, posted: Option<Frame>, mode: SysMode) -> Vec<Frame, LIMITED_IDS> {
+        let mut out = Vec::new();
+        if mode != SysMode::Normal {
+            return out;
+        }
+
+        let Some(frame) = posted else {
+            // Nothing posted for a while, hold the motors at zero.
+            let alive = self.slots.values().filter_map(|slot| slot.alive);
+            out.extend(alive.map(zero_command));
+            return out;
+        };
+
+        let id = *frame.id();
+        let allow = match self.slots.get_mut(&id) {
+            Some(slot) => slot.allow(&frame),
+            None => {
+                let mut limit = RateLimiter::new(MIN_INTERVAL);
+                let allow = limit.allow();
+                let alive = match id {
+                    Id::Standard(x) => Some(x.as_raw()),
+                    Id::Extended(_) => None,
+                };
+                let alive = alive.filter(|x| KEEP_ALIVE_IDS.contains(x));
+                let slot = Slot {
+                    limit,
+                    last: frame,
+                    alive,
+                };
+                let _ = self.slots.insert(id, slot);
+                allow
+            }
+        };
+
+        if allow {
+            let _ = out.push(frame);
+        }
+        out
+    }
+}
 
 #[embassy_executor::task]
 pub async fn sender(mut can: BufferedCanSender) -> ! {
-    let mut t = utils::init_ticker!(1, ms);
+    let mut state = Sender::new(Instant::now());
 
     loop {
-        match SysMode::get() {
-            SysMode::Error => {}
+        let posted = state.next(&CAN1_TX).await;
+        for frame in state.frames(posted, SysMode::get()) {
+            can.write(frame).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::bxcan::device::{DaMiaoConfig, DaMiaoCtrl, DaMiaoMotor};
+    use utils::atomic::AtomicU64;
+    use utils::atomic::Ordering::Relaxed as Order;
+    use utils::mock_time::block_on;
+    use utils::prelude::time::Timer;
+
+    crate::damiao!(SndMotor);
+
+    impl DaMiaoConfig for SndMotor {
+        const MSTID: u16 = 0x12;
+        const CANID: u16 = 0x02;
+        const P_MAX: f32 = 12.5;
+        const V_MAX: f32 = 30.;
+        const T_MAX: f32 = 10.;
+    }
+
+    /// Frames written for `posted` in normal mode
+    fn send(state: &mut Sender, posted: Frame) -> usize {
+        state.frames(Some(posted), SysMode::Normal).len()
+    }
+
+    #[test]
+    fn test_batch_on_one_id() {
+        let mut state = Sender::new(Instant::now());
+
+        // All requests go out on 0x7FF, back to back.
+        let batch = request_feedback_batch(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(batch.iter().all(|f| send(&mut state, *f) == 1));
+    }
+
+    #[test]
+    fn test_command_after_enable() {
+        let mut state = Sender::new(Instant::now());
+        let motor = SndMotor::get();
+
+        assert_eq!(send(&mut state, motor.clr_err()), 1);
+        assert_eq!(send(&mut state, motor.enable()), 1);
+        assert_eq!(send(&mut state, motor.set_pv(1., 2.)), 1);
+    }
 
-            SysMode::Boot => {}
+    #[test]
+    fn test_repeat_dropped() {
+        let mut state = Sender::new(Instant::now());
+        let frame = SndMotor::get().set_pv(1., 2.);
 
-            SysMode::Normal => {}
+        assert_eq!(send(&mut state, frame), 1);
+        assert_eq!(send(&mut state, frame), 0);
+
+        block_on(Timer::after(MIN_INTERVAL));
+        assert_eq!(send(&mut state, frame), 1);
+
+        // Nothing goes out outside normal mode.
+        assert!(state.frames(Some(frame), SysMode::Boot).is_empty());
+    }
+
+    #[test]
+    fn test_keep_alive() {
+        let posts: Channel<CriticalSectionRawMutex, Frame, 4> = Channel::new();
+        let interval = KEEP_ALIVE.unwrap();
+        let start = Instant::now();
+        let mut state = Sender::new(start);
+
+        // A control command and a feedback request are posted at once.
+        let gimbal = CtrlGroup::Gm6020Low.canid();
+        let cmd = Frame::new_standard(gimbal, &[0, 100, 0, 0, 0, 0, 0, 0]).unwrap();
+        for frame in [cmd, request_feedback_batch(&[1])[0]] {
+            posts.try_send(frame).unwrap();
+            let posted = block_on(state.next(&posts));
+            assert_eq!(state.frames(posted, SysMode::Normal).len(), 1);
         }
+        assert_eq!(start.elapsed(), Duration::from_ticks(0));
+
+        // Idle for the interval, only the control id is kept alive.
+        let posted = block_on(state.next(&posts));
+        assert!(posted.is_none());
+        assert_eq!(start.elapsed(), interval);
 
-        can.write(Frame::new_standard(0x08, &[1, 2, 3, 4]).unwrap())
-            .await;
+        let alive = state.frames(posted, SysMode::Normal);
+        assert_eq!(alive.len(), 1);
+        assert_eq!(*alive[0].id(), *zero_command(gimbal).id());
+        assert_eq!(alive[0].data(), &[0; 8]);
 
-        t.next().await
+        // And again every interval, while nothing is posted.
+        assert!(block_on(state.next(&posts)).is_none());
+        assert_eq!(start.elapsed(), interval * 2);
+        assert!(state.frames(None, SysMode::Error).is_empty());
     }
 }
@@ -7,8 +7,15 @@ pub mod can1_snd;
 pub mod can2_rcv;
 pub mod can2_snd;
 
+use crate::hal::can::Frame;
+use crate::sync::{blocking_mutex::raw, channel::Channel};
+
+/// Frames queued for transmission on CAN1
+pub static CAN1_TX: Channel<raw::CriticalSectionRawMutex, Frame, 4> = Channel::new();
+
 mod private {
     pub use super::super::{device::*, *};
+    pub use super::CAN1_TX;
     use crate::{hal::can, sync};
 
     pub use can::{BufferedCanReceiver, BufferedCanSender, Frame, Id};
@@ -2,6 +2,7 @@
 //! # Health Task
 //!
 
+use crate::tasks::buzzer::{PLAY, Song};
 use crate::{system::*, time::Instant};
 use utils::init_ticker;
 
@@ -13,7 +14,13 @@ pub async fn task() -> ! {
 
     loop {
         for device in WATCH_LIST {
-            if !device.tick() {
+            // Only an online device going offline is an error,
+            // one that has not come up since boot is not.
+            let online = device.check();
+            if !device.tick() && online {
+                if SysMode::get() != SysMode::Error {
+                    PLAY.signal(Song::ERROR);
+                }
                 SysMode::Error.set();
             }
         }
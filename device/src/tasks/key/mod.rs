@@ -0,0 +1,49 @@
+//!
+//! # Key Task
+//!
+//! Holding the user key for `HOLD_MS` stores the current gimbal
+//! position as its mechanical zero, see `DjiMotor::calibrate_now`.
+//!
+
+use crate::tasks::bxcan::{DMotor, DjiMotor};
+use crate::{hal, system::*};
+
+use hal::gpio::{Input, Pull};
+
+/// Key Polling Interval in ms
+const POLL_MS: u64 = 10;
+/// Hold Time before Calibrating, in ms
+const HOLD_MS: u64 = 1000;
+
+#[embassy_executor::task]
+pub async fn task(p: KeySrc) -> ! {
+    let mut t = utils::init_ticker!(POLL_MS);
+    let key = Input::new(p.key, Pull::Up);
+
+    // Polls the key has been held for, `None` once handled
+    let mut held = Some(0);
+
+    loop {
+        match (key.is_low(), held) {
+            (false, _) => held = Some(0),
+
+            (true, Some(n)) if (n + 1) * POLL_MS >= HOLD_MS => {
+                held = None;
+
+                let motor = DMotor::get();
+                if Device::DMotor.check() {
+                    motor.calibrate_now();
+                    defmt::info!("DMotor zero calibrated at {}°", motor.pos());
+                } else {
+                    defmt::warn!("DMotor offline, calibration skipped");
+                }
+            }
+
+            (true, Some(n)) => held = Some(n + 1),
+
+            (true, None) => {}
+        }
+
+        t.next().await
+    }
+}
@@ -0,0 +1,11 @@
+//!
+//! Configurations rejected at compile time.
+//!
+//! Run on the host, see `tests/compile_fail/*.stderr` for the errors.
+//!
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
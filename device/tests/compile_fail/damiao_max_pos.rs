@@ -0,0 +1,32 @@
+// A DaMiao motor with `MAX_POS` beyond `P_MAX` must not build,
+// even if `set_pv` is never called.
+
+use utils::prelude::*;
+
+#[allow(dead_code)]
+#[path = "../../src/tasks/bxcan/device/damiao.rs"]
+mod damiao;
+
+mod private {
+    pub use crate::hal::can::Frame;
+    pub use Ordering::Relaxed as Order;
+    pub use core::f32::consts::*;
+    pub use utils::atomic::{AtomicU64, Ordering};
+    pub use utils::heapless::Vec;
+}
+
+use damiao::*;
+use private::*;
+
+damiao!(XMotor);
+
+impl DaMiaoConfig for XMotor {
+    const MSTID: u16 = 0x11;
+    const CANID: u16 = 0x01;
+    const P_MAX: f32 = 3.;
+    const V_MAX: f32 = 30.;
+    const T_MAX: f32 = 10.;
+    const MAX_POS: f32 = 4.;
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+// Two motors reporting on the same feedback ID must not build.
+
+use utils::prelude::*;
+
+#[allow(dead_code)]
+#[path = "../../src/tasks/bxcan/device/dajiang.rs"]
+mod dajiang;
+
+#[allow(dead_code)]
+#[path = "../../src/tasks/bxcan/device/impls.rs"]
+mod impls;
+
+mod private {
+    pub use crate::dajiang::*;
+    pub use crate::hal::can::Frame;
+    pub use Ordering::Relaxed as Order;
+    pub use utils::MemCell;
+    pub use utils::atomic::{AtomicU64, Ordering};
+}
+
+use impls::*;
+use private::*;
+
+// A second motor on the yaw motor's ID
+dji_motor!(XMotor, 0x205, 3508);
+
+const _: () = assert_unique(&[DMotor::MSTID, XMotor::MSTID]);
+
+fn main() {}
@@ -32,6 +32,7 @@
 //!
 //! This abstraction does not provide atomicity guarantees.
 //! Partial writes may be observable if a reset occurs mid-operation.
+//! Use [`MemCellDouble`] where a torn value must not be observed.
 //!
 
 use core::cell::UnsafeCell;
@@ -197,6 +198,52 @@ impl<T> MemCell<T> {
     }
 }
 
+impl<T> MemCell<T> {
+    /// Magic value with the ABI version bits cleared.
+    const MAGIC_BASE: u64 = Self::MAGIC & !0xFFFF;
+
+    ///
+    /// Obtain the stored value, migrating it from an older layout if needed.
+    ///
+    /// If the cell holds a value of the current ABI version, this behaves
+    /// like `get()`. If it holds a value written by another ABI version,
+    /// `migrate` receives that version and a pointer to the raw stored
+    /// bytes, and may rebuild a `T` from them. A rebuilt value is written
+    /// back with the current magic; `None` leaves the cell uninitialized.
+    ///
+    /// A cell that was never initialized returns `None` without calling
+    /// `migrate`.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`MemCell::get`] and [`MemCell::init`]. In addition,
+    /// `migrate` must only read as many bytes as the old layout had,
+    /// and must not keep the pointer beyond the call.
+    ///
+    pub unsafe fn get_or_migrate(
+        &self,
+        migrate: impl FnOnce(u16, *mut u8) -> Option<T>,
+    ) -> Option<*mut T> {
+        let magic = unsafe { self.magic().read_volatile() };
+        if magic == Self::MAGIC {
+            return Some(self.value());
+        }
+
+        if magic & !0xFFFF != Self::MAGIC_BASE {
+            return None;
+        }
+
+        let version = (magic & 0xFFFF) as u16;
+        match migrate(version, self.value().cast()) {
+            Some(val) => Some(unsafe { self.init(val) }),
+            None => {
+                unsafe { self.invalidate() };
+                None
+            }
+        }
+    }
+}
+
 impl<T> MemCell<T> {
     ///
     /// Mark the memory cell as uninitialized.
@@ -218,3 +265,325 @@ impl<T> MemCell<T> {
         unsafe { self.magic().write_volatile(0) }
     }
 }
+
+///
+/// A contiguous array of persistent memory cells.
+///
+/// `MemCellArray<T, N>` places `N` [`MemCell<T>`]s back to back in one
+/// region, so a group of related buffers needs a single static. Each cell
+/// keeps its own magic value and is initialized and invalidated on its own.
+///
+/// # Layout
+///
+/// ```text
+/// +-------+---------+-------+---------+-----+-------+---------+
+/// | magic | value 0 | magic | value 1 | ... | magic | value N |
+/// +-------+---------+-------+---------+-----+-------+---------+
+/// ```
+///
+/// All safety requirements of [`MemCell`] apply to every cell.
+///
+#[repr(C)]
+pub struct MemCellArray<T: Sized, const N: usize> {
+    cells: [MemCell<T>; N],
+}
+
+impl<T, const N: usize> MemCellArray<T, N> {
+    ///
+    /// Creates a new array of uninitialized memory cells.
+    ///
+    /// Like [`MemCell::uninit`], nothing is written to memory.
+    ///
+    #[inline(always)]
+    pub const fn uninit() -> Self {
+        Self {
+            cells: [const { MemCell::uninit() }; N],
+        }
+    }
+
+    /// Returns the number of cells.
+    #[inline(always)]
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the array has no cells.
+    #[inline(always)]
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    ///
+    /// Returns the cell at `index`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index >= N`.
+    ///
+    #[inline(always)]
+    pub const fn cell(&self, index: usize) -> &MemCell<T> {
+        &self.cells[index]
+    }
+
+    ///
+    /// Initialize the cell at `index`, see [`MemCell::init`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`MemCell::init`], for the cell at `index`.
+    ///
+    pub unsafe fn init(&self, index: usize, val: T) -> *mut T {
+        unsafe { self.cells[index].init(val) }
+    }
+
+    ///
+    /// Pointer to the value of the cell at `index`, see [`MemCell::get`].
+    ///
+    /// # Safety
+    ///
+    /// Same as [`MemCell::get`], for the cell at `index`.
+    ///
+    pub unsafe fn get(&self, index: usize) -> Option<*mut T> {
+        unsafe { self.cells[index].get() }
+    }
+}
+
+///
+/// A stored value tagged with its write sequence.
+///
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Slot<T> {
+    seq: u32,
+    value: T,
+}
+
+///
+/// A double-buffered persistent memory cell.
+///
+/// `MemCellDouble<T>` keeps two [`MemCell`] slots, each tagged with a
+/// sequence number. `store()` always writes the slot *not* holding the
+/// latest value, and the slot only becomes valid once its magic is written
+/// last. A reset in the middle of a store therefore leaves the previous
+/// value intact, and `load()` returns it on the next boot.
+///
+/// # Layout
+///
+/// ```text
+/// +-------+-------+---------+-------+-------+---------+
+/// | magic | seq 0 | value 0 | magic | seq 1 | value 1 |
+/// +-------+-------+---------+-------+-------+---------+
+/// ```
+///
+/// All safety requirements of [`MemCell`] apply to both slots.
+///
+#[repr(C)]
+pub struct MemCellDouble<T: Sized> {
+    slots: [MemCell<Slot<T>>; 2],
+}
+
+impl<T: Copy> MemCellDouble<T> {
+    ///
+    /// Creates a new uninitialized double cell.
+    ///
+    /// Like [`MemCell::uninit`], nothing is written to memory.
+    ///
+    #[inline(always)]
+    pub const fn uninit() -> Self {
+        Self {
+            slots: [const { MemCell::uninit() }; 2],
+        }
+    }
+
+    ///
+    /// Find the valid slot with the newest sequence.
+    ///
+    /// Sequences are compared with wrapping arithmetic, so the
+    /// counter may overflow.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`MemCell::get`], for both slots.
+    ///
+    unsafe fn latest(&self) -> Option<(usize, *mut Slot<T>, u32)> {
+        let read = |index: usize| {
+            let slot = unsafe { self.slots[index].get()? };
+            let seq = unsafe { core::ptr::addr_of!((*slot).seq).read_volatile() };
+            Some((slot, seq))
+        };
+
+        match (read(0), read(1)) {
+            (Some((_, sa)), Some((b, sb))) if (sb.wrapping_sub(sa) as i32) > 0 => Some((1, b, sb)),
+            (Some((a, sa)), _) => Some((0, a, sa)),
+            (None, Some((b, sb))) => Some((1, b, sb)),
+            (None, None) => None,
+        }
+    }
+
+    ///
+    /// Read the latest completely stored value.
+    ///
+    /// Returns `None` if no store ever completed.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`MemCell::get`], for both slots. No `store()` may
+    /// run concurrently.
+    ///
+    pub unsafe fn load(&self) -> Option<T> {
+        let (_, slot, _) = unsafe { self.latest()? };
+        Some(unsafe { core::ptr::addr_of!((*slot).value).read_volatile() })
+    }
+
+    ///
+    /// Store a value without ever tearing the latest one.
+    ///
+    /// The inactive slot is invalidated, written, and then marked
+    /// valid with the next sequence number. Until that final magic
+    /// write lands, `load()` keeps returning the previous value.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`MemCell::init`], for both slots.
+    ///
+    pub unsafe fn store(&self, val: T) {
+        let (next, seq) = match unsafe { self.latest() } {
+            Some((index, _, seq)) => (1 - index, seq.wrapping_add(1)),
+            None => (0, 0),
+        };
+
+        let slot = &self.slots[next];
+        unsafe {
+            slot.invalidate();
+            compiler_fence(Ordering::SeqCst);
+            slot.init(Slot { seq, value: val });
+        }
+    }
+
+    ///
+    /// Mark both slots as uninitialized.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`MemCell::invalidate`], for both slots.
+    ///
+    pub unsafe fn invalidate(&self) {
+        unsafe {
+            self.slots[0].invalidate();
+            self.slots[1].invalidate();
+        }
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_array_cells_independent() {
+    let cells: MemCellArray<u32, 4> = MemCellArray::uninit();
+    // Safety: the cells are local to the test, so access is exclusive.
+    unsafe {
+        // Cleared memory, as on a cold boot
+        (0..cells.len()).for_each(|i| cells.cell(i).invalidate());
+
+        cells.init(0, 10);
+        cells.init(2, 30);
+
+        assert_eq!(cells.get(0).map(|x| x.read()), Some(10));
+        assert_eq!(cells.get(2).map(|x| x.read()), Some(30));
+        assert!(cells.get(1).is_none() && cells.get(3).is_none());
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_get_or_migrate() {
+    // A `u16` written by ABI version 0, before the cell held a `u32`
+    let old_version = |cell: &MemCell<u32>| unsafe {
+        cell.value().cast::<u16>().write(0x1234);
+        cell.magic().write(MemCell::<u32>::MAGIC_BASE);
+    };
+
+    let cell: MemCell<u32> = MemCell::uninit();
+    // Safety: the cell is local to the test, so access is exclusive.
+    unsafe {
+        // Never initialized, `migrate` is not called.
+        cell.invalidate();
+        assert!(cell.get_or_migrate(|_, _| unreachable!()).is_none());
+
+        // Migrated, and written back with the current magic
+        old_version(&cell);
+        let migrated = cell.get_or_migrate(|version, raw| {
+            assert_eq!(version, 0);
+            Some(raw.cast::<u16>().read() as u32 + 1)
+        });
+        assert_eq!(migrated.map(|x| x.read()), Some(0x1235));
+        assert_eq!(cell.get().map(|x| x.read()), Some(0x1235));
+
+        // Current values are returned as is.
+        assert!(cell.get_or_migrate(|_, _| unreachable!()).is_some());
+
+        // Not migratable, and invalidated
+        old_version(&cell);
+        assert!(cell.get_or_migrate(|_, _| None).is_none());
+        assert!(cell.get().is_none());
+        assert!(cell.get_or_migrate(|_, _| unreachable!()).is_none());
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_double_torn_store() {
+    let cell: MemCellDouble<u32> = MemCellDouble::uninit();
+    // Safety: the cell is local to the test, so access is exclusive.
+    unsafe {
+        cell.invalidate();
+        assert!(cell.load().is_none());
+
+        cell.store(1);
+        cell.store(2);
+        assert_eq!(cell.load(), Some(2));
+
+        // A reset after the inactive slot is written, before its magic
+        let (index, _, seq) = cell.latest().unwrap();
+        let slot = &cell.slots[1 - index];
+        slot.invalidate();
+        let torn = Slot {
+            seq: seq + 1,
+            value: 3,
+        };
+        slot.value().write(torn);
+        assert_eq!(cell.load(), Some(2));
+
+        // The next store goes to the same inactive slot.
+        cell.store(4);
+        assert_eq!(cell.load(), Some(4));
+        assert_eq!(cell.latest().unwrap().0, 1 - index);
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_double_seq_wrap() {
+    let cell: MemCellDouble<u32> = MemCellDouble::uninit();
+    // Safety: the cell is local to the test, so access is exclusive.
+    let write = |index: usize, seq: u32, value: u32| unsafe {
+        cell.slots[index].init(Slot { seq, value });
+    };
+
+    // Sequence 0 follows `u32::MAX`, in either slot.
+    write(0, u32::MAX, 1);
+    write(1, 0, 2);
+    assert_eq!(unsafe { cell.load() }, Some(2));
+
+    write(0, 1, 3);
+    write(1, u32::MAX, 4);
+    assert_eq!(unsafe { cell.load() }, Some(3));
+
+    // Stores keep counting through the wrap.
+    write(0, u32::MAX - 1, 5);
+    unsafe {
+        cell.slots[1].invalidate();
+        (6..9).for_each(|x| cell.store(x));
+        assert_eq!(cell.load(), Some(8));
+        assert_eq!(cell.latest().map(|(_, _, seq)| seq), Some(1));
+    }
+}
@@ -0,0 +1,80 @@
+//!
+//! Error type shared across communication layers.
+//!
+//! Tasks that read a UART, talk on CAN and decode frames would otherwise
+//! match each layer's error separately. [`CommError`] wraps them all, so
+//! task code can use `?` across layers and log a single type.
+//!
+
+use crate::prelude::hal::{can::enums::BusError, usart};
+use crate::prelude::time::TimeoutError;
+
+///
+/// Communication error from any layer.
+///
+/// Every variant converts with `From`, so `?` lifts a layer
+/// error into `CommError` without an explicit `map_err`.
+///
+#[derive(Debug, ::defmt::Format)]
+pub enum CommError {
+    /// UART transfer failed.
+    Uart(usart::Error),
+    /// CAN bus reported an error.
+    Can(BusError),
+    /// No data arrived in time.
+    Timeout,
+    /// Frame or payload could not be decoded.
+    #[cfg(feature = "frame")]
+    Frame(::dji_frame::Error),
+}
+
+impl CommError {
+    ///
+    /// Get the number of bytes to skip before decoding again.
+    ///
+    /// Only framing errors carry a position; every
+    /// other layer reports 0.
+    ///
+    pub fn skip(&self) -> usize {
+        match self {
+            #[cfg(feature = "frame")]
+            Self::Frame(e) => e.skip(),
+            _ => 0,
+        }
+    }
+}
+
+impl From<usart::Error> for CommError {
+    fn from(e: usart::Error) -> Self {
+        Self::Uart(e)
+    }
+}
+
+impl From<BusError> for CommError {
+    fn from(e: BusError) -> Self {
+        Self::Can(e)
+    }
+}
+
+impl From<TimeoutError> for CommError {
+    fn from(_: TimeoutError) -> Self {
+        Self::Timeout
+    }
+}
+
+#[cfg(feature = "frame")]
+impl From<::dji_frame::Error> for CommError {
+    fn from(e: ::dji_frame::Error) -> Self {
+        Self::Frame(e)
+    }
+}
+
+#[cfg(all(test, feature = "frame"))]
+#[test]
+fn test_frame_skip() {
+    let e: CommError = ::dji_frame::Error::MissingHeader { skip: 5 }.into();
+    assert!(matches!(e, CommError::Frame(_)));
+    assert_eq!(e.skip(), 5);
+
+    assert_eq!(CommError::Timeout.skip(), 0);
+}
@@ -0,0 +1,182 @@
+//!
+//! Signal filters for noisy sensor readings.
+//!
+//! This module provides two small, allocation-free smoothing filters,
+//! suited to motor velocity feedback or IMU samples:
+//!
+//! - [`Ema`], an exponential moving average with a single state value
+//! - [`MovingAverage`], the plain mean of the last `N` samples
+//!
+//! Both are `const`-constructible, so they can live in a `static`.
+//!
+
+///
+/// Exponential moving average.
+///
+/// Each update moves the state towards the sample by `alpha`:
+/// `alpha = 1.0` passes samples through, smaller values smooth
+/// more and react slower.
+///
+/// # Example
+/// ```
+/// let mut vel = Ema::new(0.2);
+///
+/// loop {
+///     let smooth = vel.update(motor.vel());
+/// }
+/// ```
+///
+#[derive(Debug, Clone, Copy, ::defmt::Format)]
+pub struct Ema {
+    alpha: f32,
+    state: f32,
+}
+
+impl Ema {
+    ///
+    /// Creates a new filter starting from zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is not within `(0, 1]`.
+    ///
+    pub const fn new(alpha: f32) -> Self {
+        assert!(alpha > 0.0 && alpha <= 1.0, "Alpha must be within (0, 1]");
+        Self { alpha, state: 0.0 }
+    }
+
+    /// Feed a sample and get the filtered value.
+    pub const fn update(&mut self, x: f32) -> f32 {
+        self.state += self.alpha * (x - self.state);
+        self.state
+    }
+
+    /// Restart the filter from `x`.
+    pub const fn reset(&mut self, x: f32) {
+        self.state = x;
+    }
+
+    /// Get the last filtered value.
+    pub const fn value(&self) -> f32 {
+        self.state
+    }
+}
+
+///
+/// Moving average over the last `N` samples.
+///
+/// Until `N` samples have been fed, the mean covers only the
+/// samples seen so far.
+///
+#[derive(Debug, Clone, Copy, ::defmt::Format)]
+pub struct MovingAverage<const N: usize> {
+    taps: [f32; N],
+    next: usize,
+    len: usize,
+}
+
+impl<const N: usize> MovingAverage<N> {
+    /// Creates a new, empty filter.
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "Moving average needs at least one tap") };
+        Self {
+            taps: [0.0; N],
+            next: 0,
+            len: 0,
+        }
+    }
+
+    /// Feed a sample and get the filtered value.
+    pub const fn update(&mut self, x: f32) -> f32 {
+        self.taps[self.next] = x;
+        self.next = (self.next + 1) % N;
+        if self.len < N {
+            self.len += 1;
+        }
+
+        self.value()
+    }
+
+    /// Get the mean of the buffered samples, zero when empty.
+    pub const fn value(&self) -> f32 {
+        if self.len == 0 {
+            return 0.0;
+        }
+
+        // Summed on demand, so rounding errors do not accumulate.
+        let mut sum = 0.0;
+        let mut i = 0;
+        while i < self.len {
+            sum += self.taps[i];
+            i += 1;
+        }
+        sum / self.len as f32
+    }
+
+    /// Drop every buffered sample.
+    pub const fn reset(&mut self) {
+        self.next = 0;
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Default for MovingAverage<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ema_pass_through() {
+        let mut ema = Ema::new(1.0);
+        assert_eq!(ema.update(3.5), 3.5);
+        assert_eq!(ema.update(-2.0), -2.0);
+        assert_eq!(ema.value(), -2.0);
+    }
+
+    #[test]
+    fn test_ema_step() {
+        // Half-way to the step each update.
+        let mut ema = Ema::new(0.5);
+        let steps: [f32; 4] = core::array::from_fn(|_| ema.update(1.0));
+        assert_eq!(steps, [0.5, 0.75, 0.875, 0.9375]);
+
+        // A smaller alpha reacts slower, but still settles on the step.
+        let mut slow = Ema::new(0.1);
+        assert!((slow.update(1.0) - 0.1).abs() < 1e-6);
+        (0..200).for_each(|_| _ = slow.update(1.0));
+        assert!((slow.value() - 1.0).abs() < 1e-6);
+
+        slow.reset(-4.0);
+        assert_eq!(slow.update(-4.0), -4.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Alpha must be within (0, 1]")]
+    fn test_ema_alpha_range() {
+        let _ = Ema::new(0.0);
+    }
+
+    #[test]
+    fn test_moving_average_step() {
+        // Ramps over the window, then settles on the step.
+        let mut avg = MovingAverage::<4>::new();
+        assert_eq!(avg.value(), 0.0);
+        assert_eq!(avg.update(0.0), 0.0);
+        assert_eq!(avg.update(4.0), 2.0);
+        assert_eq!(avg.update(4.0), 8.0 / 3.0);
+        assert_eq!(avg.update(4.0), 3.0);
+        assert_eq!(avg.update(4.0), 4.0);
+
+        // Older samples leave the window as it wraps.
+        assert_eq!(avg.update(0.0), 3.0);
+
+        avg.reset();
+        assert_eq!(avg.value(), 0.0);
+        assert_eq!(avg.update(2.0), 2.0);
+    }
+}
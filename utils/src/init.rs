@@ -12,6 +12,7 @@ use hal::{Config, Peripherals, init, rcc, time::mhz};
 use ll::Peripherals as CorePeripherals;
 
 // `__pre_init` will be called before main
+#[cfg(target_os = "none")]
 core::arch::global_asm! {
     ".global __pre_init",
     ".type __pre_init, %function",
@@ -39,9 +40,25 @@ core::arch::global_asm! {
 ///
 /// This function initializes the system peripherals and clocks.
 ///
+/// # Debug in Sleep
+///
+/// With the `debug-sleep` feature the debug module keeps the clocks
+/// running in sleep, so a probe stays attached while the executor
+/// waits. This costs power whenever the core is idle; production
+/// builds should leave it off with `--no-default-features`.
+///
 pub fn sys_init() -> (CorePeripherals, Peripherals) {
     defmt::debug!("System Initialization...");
 
+    if let Some(rec) = crate::last_panic() {
+        defmt::warn!(
+            "Reset after panic at {=u32:#010x}: {=str}",
+            rec.location(),
+            rec.message()
+        );
+        crate::clear_panic();
+    }
+
     let core = match CorePeripherals::take() {
         Some(x) => x,
         None => panic!("{}: Can Be Called Only Once!!!", file!()),
@@ -49,7 +66,7 @@ pub fn sys_init() -> (CorePeripherals, Peripherals) {
 
     let peripherals = {
         let mut config = Config::default();
-        config.enable_debug_during_sleep = true;
+        config.enable_debug_during_sleep = cfg!(feature = "debug-sleep");
 
         let rcc = &mut config.rcc;
 
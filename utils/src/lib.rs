@@ -2,19 +2,28 @@
 //! Utility Functions and Types for Embedded Development.
 //!
 
-#![no_std]
-#![no_main]
+#![cfg_attr(target_os = "none", no_std)]
+#![cfg_attr(target_os = "none", no_main)]
 #![allow(unused_imports)]
 
 use ::defmt_rtt as _;
-use ::panic_probe as _;
 
 mod cell;
+mod error;
 mod init;
 mod macros;
+mod panic;
 
-pub use cell::MemCell;
+pub mod filter;
+#[cfg(not(target_os = "none"))]
+pub mod mock_time;
+pub mod rate;
+pub mod soft_pwm;
+
+pub use cell::{MemCell, MemCellArray, MemCellDouble};
+pub use error::CommError;
 pub use init::sys_init;
+pub use panic::{PanicRecord, clear_panic, last_panic, location_hash};
 
 /// Re-exports of `Cortex-M` Assembly Instructions
 pub use prelude::ll::asm;
@@ -45,10 +54,12 @@ pub mod prelude {
     pub use ::embassy_stm32 as hal; // HAL
     pub use ::embassy_sync as sync; // Sync
     pub use ::embassy_time as time; // Time
-}
 
-/// Defmt Panic Handler
-#[::defmt::panic_handler]
-fn soft_panic() -> ! {
-    ::panic_probe::hard_fault()
+    /// Framing Types of `dji-frame`
+    #[cfg(feature = "frame")]
+    pub mod frame {
+        pub use ::dji_frame::{
+            DjiValidator, Error, HexDump, Marshaler, Messager, RawFrame, accumulate,
+        };
+    }
 }
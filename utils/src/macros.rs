@@ -55,3 +55,64 @@ macro_rules! init_ticker {
         Ticker::every(Duration::from_secs($val))
     }};
 }
+
+///
+/// Bound a future with a timeout.
+///
+/// `timeout!()` wraps a future so that it resolves to
+/// `Err(TimeoutError)` if it does not complete in time.
+///
+/// # Example
+/// ```
+/// match timeout!(200, ms, uart.read(&mut buf)).await {
+///     Ok(x) => { /* completed */ }
+///     Err(_) => { /* timed out */ }
+/// }
+/// ```
+///
+#[macro_export]
+macro_rules! timeout {
+    ($val:expr, ms, $fut:expr) => {{
+        use $crate::prelude::time::{Duration, with_timeout};
+        with_timeout(Duration::from_millis($val), $fut)
+    }};
+
+    ($val:expr, s, $fut:expr) => {{
+        use $crate::prelude::time::{Duration, with_timeout};
+        with_timeout(Duration::from_secs($val), $fut)
+    }};
+
+    ($val:expr, $fut:expr) => {{
+        use $crate::prelude::time::{Duration, with_timeout};
+        with_timeout(Duration::from_millis($val), $fut)
+    }};
+}
+
+///
+/// Trace motor feedback, enabled by the `log-motor` feature.
+///
+/// Without the feature the arguments are still type-checked,
+/// but nothing is logged.
+///
+/// # Example
+/// ```
+/// trace_motor!("{} => {}°", pos, angle);
+/// ```
+///
+#[cfg(feature = "log-motor")]
+#[macro_export]
+macro_rules! trace_motor {
+    ($($arg:tt)*) => {
+        ::defmt::trace!($($arg)*)
+    };
+}
+
+#[cfg(not(feature = "log-motor"))]
+#[macro_export]
+macro_rules! trace_motor {
+    ($($arg:tt)*) => {
+        if false {
+            ::defmt::trace!($($arg)*)
+        }
+    };
+}
@@ -0,0 +1,116 @@
+//!
+//! Mock time driver for host tests.
+//!
+//! Time only moves while [`block_on`] finds the future waiting on a
+//! timer: the clock then jumps to the earliest deadline. Timeouts and
+//! tickers run instantly and in a fixed order, and every test thread
+//! has its own clock, starting at zero.
+//!
+
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::task::Wake;
+
+use crate::prelude::time::Instant;
+use embassy_time_driver::Driver;
+
+/// Clock of one test thread
+struct Clock {
+    now: u64,
+    timers: Vec<(u64, Waker)>,
+}
+
+std::thread_local! {
+    static CLOCK: RefCell<Clock> = const {
+        RefCell::new(Clock {
+            now: 0,
+            timers: Vec::new(),
+        })
+    };
+}
+
+struct MockDriver;
+
+impl Driver for MockDriver {
+    fn now(&self) -> u64 {
+        CLOCK.with_borrow(|c| c.now)
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        CLOCK.with_borrow_mut(|c| match at <= c.now {
+            true => waker.wake_by_ref(),
+            false => c.timers.push((at, waker.clone())),
+        })
+    }
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: MockDriver = MockDriver);
+
+// The target gets a default from `defmt.x`, which host builds do not link.
+::defmt::timestamp!("{=u64:us}", Instant::now().as_micros());
+
+/// Waker that flags the future for another poll
+struct Flag(AtomicBool);
+
+impl Wake for Flag {
+    fn wake(self: Arc<Self>) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+///
+/// # Block On
+///
+/// Run `fut` to completion on the mock clock.
+///
+/// # Panics
+///
+/// Panics if the future waits while neither woken nor waiting on
+/// a timer, as it would never complete.
+///
+pub fn block_on<F: Future>(fut: F) -> F::Output {
+    let flag = Arc::new(Flag(AtomicBool::new(true)));
+    let waker = Waker::from(flag.clone());
+    let mut cx = Context::from_waker(&waker);
+    let mut fut = pin!(fut);
+
+    loop {
+        if flag.0.swap(false, Ordering::Relaxed) {
+            if let Poll::Ready(x) = fut.as_mut().poll(&mut cx) {
+                return x;
+            }
+            continue;
+        }
+
+        // Idle, skip ahead to the earliest timer.
+        let due = CLOCK.with_borrow_mut(|c| {
+            let at = c.timers.iter().map(|t| t.0).min()?;
+            c.now = at;
+            let (due, rest) = c.timers.drain(..).partition(|t| t.0 <= at);
+            c.timers = rest;
+            Some(due)
+        });
+
+        let due: Vec<(u64, Waker)> = due.expect("Future stalled without a pending timer");
+        due.into_iter().for_each(|(_, w)| w.wake());
+    }
+}
+
+#[cfg(test)]
+#[test]
+fn test_block_on() {
+    use crate::prelude::time::{Duration, Timer, with_timeout};
+
+    let start = Instant::now();
+    block_on(Timer::after_millis(5));
+    assert_eq!(start.elapsed(), Duration::from_millis(5));
+
+    // The earlier of two timers fires first.
+    let timeout = Duration::from_millis(2);
+    assert!(block_on(with_timeout(timeout, Timer::after_millis(3))).is_err());
+    assert_eq!(start.elapsed(), Duration::from_millis(7));
+}
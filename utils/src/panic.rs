@@ -0,0 +1,242 @@
+//!
+//! Panic handlers with a persistent reason log.
+//!
+//! Both the core and the `defmt` panic handler print the reason, store a
+//! [`PanicRecord`] in a [`MemCell`] in the `.uninit` section, then raise a
+//! `HardFault` like `panic_probe` does. The record survives the reset, so
+//! the next boot can report it with [`last_panic`].
+//!
+
+use crate::MemCell;
+use crate::atomic::{AtomicBool, Ordering};
+use crate::prelude::ll;
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+
+/// Bytes of the panic message kept, longer messages are truncated
+const MSG_LEN: usize = 48;
+
+///
+/// # Panic Record
+///
+/// A truncated panic message and a hash of its source location.
+///
+#[repr(C)]
+#[derive(Debug, Clone, Copy, ::defmt::Format)]
+pub struct PanicRecord {
+    location: u32,
+    len: u8,
+    msg: [u8; MSG_LEN],
+}
+
+impl PanicRecord {
+    /// Create a record with an empty message.
+    pub const fn new(location: u32) -> Self {
+        Self {
+            location,
+            len: 0,
+            msg: [0; MSG_LEN],
+        }
+    }
+
+    /// Append `msg`, truncated to whole characters once full.
+    pub const fn with_message(mut self, msg: &str) -> Self {
+        self.push(msg);
+        self
+    }
+
+    /// Hash of the panic location, see `location_hash`
+    pub const fn location(&self) -> u32 {
+        self.location
+    }
+
+    /// Stored, possibly truncated, panic message
+    pub const fn message(&self) -> &str {
+        let len = if (self.len as usize) < MSG_LEN {
+            self.len as usize
+        } else {
+            MSG_LEN
+        };
+
+        let (msg, _) = self.msg.split_at(len);
+        match core::str::from_utf8(msg) {
+            Ok(msg) => msg,
+            // Only reachable with memory corrupted across the reset.
+            Err(_) => "<corrupted>",
+        }
+    }
+
+    const fn push(&mut self, s: &str) {
+        let room = MSG_LEN - self.len as usize;
+        let mut n = if s.len() < room { s.len() } else { room };
+        while !s.is_char_boundary(n) {
+            n -= 1;
+        }
+
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < n {
+            self.msg[self.len as usize + i] = bytes[i];
+            i += 1;
+        }
+        self.len += n as u8;
+    }
+}
+
+impl Write for PanicRecord {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push(s);
+        Ok(())
+    }
+}
+
+///
+/// # Location Hash
+///
+/// FNV-1a over the file name, line and column of a panic.
+///
+pub const fn location_hash(file: &str, line: u32, column: u32) -> u32 {
+    const PRIME: u32 = 0x0100_0193;
+
+    let mut hash = 0x811C_9DC5;
+    let file = file.as_bytes();
+    let mut i = 0;
+    while i < file.len() {
+        hash = (hash ^ file[i] as u32).wrapping_mul(PRIME);
+        i += 1;
+    }
+
+    let pos = (line as u64) << 32 | column as u64;
+    let pos = pos.to_le_bytes();
+    let mut i = 0;
+    while i < pos.len() {
+        hash = (hash ^ pos[i] as u32).wrapping_mul(PRIME);
+        i += 1;
+    }
+    hash
+}
+
+// Truncation keeps whole characters, and the hash tells lines apart.
+const _: () = {
+    let rec = PanicRecord::new(7).with_message("overflow");
+    assert!(rec.location() == 7 && rec.len == 8);
+
+    let long = PanicRecord::new(0)
+        .with_message("called `Option::unwrap()` on a `None` value ")
+        .with_message("°C");
+    assert!(long.len as usize == MSG_LEN - 1);
+
+    // A 2-byte character does not fit in the last byte.
+    assert!(long.with_message("°").len as usize == MSG_LEN - 1);
+
+    let full = long.with_message("abc");
+    assert!(full.len as usize == MSG_LEN && full.msg[MSG_LEN - 1] == b'a');
+
+    assert!(location_hash("src/main.rs", 1, 1) != location_hash("src/main.rs", 2, 1));
+    assert!(location_hash("", 0, 0) != 0);
+};
+
+#[unsafe(link_section = ".uninit.PANIC")]
+static LAST_PANIC: MemCell<PanicRecord> = MemCell::uninit();
+
+///
+/// # Last Panic
+///
+/// Record of the panic before the last reset, if any.
+///
+pub fn last_panic() -> Option<PanicRecord> {
+    // Safety: the cell is only written by the panic handlers, which never return.
+    unsafe { LAST_PANIC.get() }.map(|rec| unsafe { rec.read_volatile() })
+}
+
+/// Forget the last panic, once it has been reported.
+pub fn clear_panic() {
+    // Safety: see `last_panic`.
+    unsafe { LAST_PANIC.invalidate() }
+}
+
+/// Store `rec` for the next boot.
+#[cfg_attr(not(target_os = "none"), allow(dead_code))]
+fn record(rec: PanicRecord) {
+    // Safety: interrupts are disabled and the handler never returns,
+    // so nothing else accesses the cell. `init` fences the magic write.
+    unsafe { LAST_PANIC.init(rec) };
+}
+
+/// Trigger a `HardFault` with `udf`, as `panic_probe::hard_fault`.
+#[cfg(target_os = "none")]
+fn hard_fault() -> ! {
+    // `udf` raises a `UsageFault` instead while that is enabled.
+    const SHCSR: *mut u32 = 0xE000_ED24usize as _;
+    const USGFAULTENA: u32 = 18;
+
+    // Safety: SHCSR is a valid System Control Block register.
+    unsafe {
+        let shcsr = SHCSR.read_volatile();
+        SHCSR.write_volatile(shcsr & !(1 << USGFAULTENA));
+    }
+
+    ll::asm::udf()
+}
+
+/// Core Panic Handler
+#[cfg(target_os = "none")]
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    static PANICKED: AtomicBool = AtomicBool::new(false);
+
+    ll::interrupt::disable();
+
+    // Guard against a panic while recording.
+    if !PANICKED.swap(true, Ordering::Relaxed) {
+        ::defmt::error!("{}", ::defmt::Display2Format(info));
+
+        let location = match info.location() {
+            Some(l) => location_hash(l.file(), l.line(), l.column()),
+            None => 0,
+        };
+
+        let mut rec = PanicRecord::new(location);
+        let _ = write!(rec, "{}", info.message());
+        record(rec);
+    }
+
+    hard_fault()
+}
+
+/// Defmt Panic Handler
+#[cfg(target_os = "none")]
+#[::defmt::panic_handler]
+fn soft_panic() -> ! {
+    ll::interrupt::disable();
+
+    // `defmt` already printed the reason, and does not pass it on.
+    record(PanicRecord::new(0).with_message("defmt panic"));
+
+    hard_fault()
+}
+
+/// Defmt Panic Handler of host builds, panics with the test
+#[cfg(not(target_os = "none"))]
+#[::defmt::panic_handler]
+fn soft_panic() -> ! {
+    core::panic!("defmt panic")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_round_trip() {
+        let location = location_hash("src/main.rs", 42, 5);
+        record(PanicRecord::new(location).with_message("index out of bounds"));
+
+        let rec = last_panic().unwrap();
+        assert_eq!(rec.location(), location);
+        assert_eq!(rec.message(), "index out of bounds");
+
+        clear_panic();
+        assert!(last_panic().is_none());
+    }
+}
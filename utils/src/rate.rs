@@ -0,0 +1,204 @@
+//!
+//! Rate limiting for periodic transmissions.
+//!
+//! Control frames sent faster than the receiver needs them only load
+//! the bus: a [`RateLimiter`] lets a sender drop the extra ones. Sent
+//! too slowly, the receiver may time out: a [`KeepAlive`] tells the
+//! sender when to repeat itself.
+//!
+
+use crate::prelude::time::{Duration, Instant};
+
+///
+/// Allows at most one event per `min_interval`.
+///
+/// Denied events do not restart the interval, so a steady stream is
+/// let through at the limit rate.
+///
+/// # Example
+/// ```
+/// let mut limit = RateLimiter::new(Duration::from_millis(1));
+///
+/// loop {
+///     let frame = CAN1_TX.receive().await;
+///     if limit.allow() {
+///         can.write(frame).await;
+///     }
+/// }
+/// ```
+///
+#[derive(Debug, Clone, Copy, ::defmt::Format)]
+pub struct RateLimiter {
+    min_interval: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing the first event at once.
+    pub const fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last: None,
+        }
+    }
+
+    pub const fn min_interval(&self) -> Duration {
+        self.min_interval
+    }
+
+    /// Whether an event is allowed now, and records it if so.
+    pub fn allow(&mut self) -> bool {
+        self.allow_at(Instant::now())
+    }
+
+    ///
+    /// Whether an event is allowed at `now`, and records it if so.
+    ///
+    /// A `now` before the last allowed event counts as no time passed.
+    ///
+    pub const fn allow_at(&mut self, now: Instant) -> bool {
+        if let Some(last) = self.last {
+            let elapsed = now.as_ticks().saturating_sub(last.as_ticks());
+            if elapsed < self.min_interval.as_ticks() {
+                return false;
+            }
+        }
+
+        self.last = Some(now);
+        true
+    }
+
+    /// Forget the last event, so the next one is allowed.
+    pub const fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+///
+/// Deadline for a keep-alive after `interval` without activity.
+///
+/// Receivers with a timeout, like the DaMiao motors, stop once no
+/// command arrives in time: [`KeepAlive::deadline`] tells a sender
+/// when to repeat the last one.
+///
+/// # Example
+/// ```
+/// let mut idle = KeepAlive::new(Duration::from_millis(50), Instant::now());
+///
+/// loop {
+///     match with_deadline(idle.deadline(), CAN1_TX.receive()).await {
+///         Ok(frame) => can.write(frame).await,
+///         Err(_) => can.write(last).await,
+///     }
+///     idle.feed(Instant::now());
+/// }
+/// ```
+///
+#[derive(Debug, Clone, Copy, ::defmt::Format)]
+pub struct KeepAlive {
+    interval: Duration,
+    last: Instant,
+}
+
+impl KeepAlive {
+    /// Creates a keep-alive with `now` as the last activity.
+    pub const fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            last: now,
+        }
+    }
+
+    pub const fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Instant the keep-alive is due, without activity before.
+    pub const fn deadline(&self) -> Instant {
+        Instant::from_ticks(self.last.as_ticks() + self.interval.as_ticks())
+    }
+
+    /// Whether the keep-alive is due at `now`.
+    pub const fn due_at(&self, now: Instant) -> bool {
+        now.as_ticks() >= self.deadline().as_ticks()
+    }
+
+    /// Record activity at `now`, a command or a keep-alive itself.
+    pub const fn feed(&mut self, now: Instant) {
+        self.last = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_time::block_on;
+    use crate::prelude::time::Timer;
+
+    /// Let `ticks` pass on the mock clock
+    fn wait(ticks: u64) {
+        block_on(Timer::after_ticks(ticks));
+    }
+
+    #[test]
+    fn test_limit_allow() {
+        let mut limit = RateLimiter::new(Duration::from_ticks(10));
+        assert!(limit.allow());
+
+        wait(5);
+        assert!(!limit.allow());
+        wait(4);
+        assert!(!limit.allow());
+
+        // Denied attempts do not restart the interval.
+        wait(1);
+        assert!(limit.allow());
+
+        limit.reset();
+        assert!(limit.allow() && !limit.allow());
+    }
+
+    #[test]
+    fn test_limit_rate() {
+        let mut limit = RateLimiter::new(Duration::from_ticks(10));
+
+        // One attempt per tick lets one in per interval.
+        let allowed = (0..100).filter(|_| {
+            let allow = limit.allow();
+            wait(1);
+            allow
+        });
+        assert_eq!(allowed.count(), 10);
+
+        let mut open = RateLimiter::new(Duration::from_ticks(0));
+        assert!(open.allow() && open.allow());
+    }
+
+    #[test]
+    fn test_limit_clock_behind() {
+        let mut limit = RateLimiter::new(Duration::from_ticks(10));
+        let now = Instant::now();
+
+        assert!(limit.allow_at(now + Duration::from_ticks(10)));
+        assert!(!limit.allow_at(now));
+    }
+
+    #[test]
+    fn test_keep_alive_due() {
+        let start = Instant::now();
+        let mut idle = KeepAlive::new(Duration::from_ticks(50), start);
+
+        wait(49);
+        assert!(!idle.due_at(Instant::now()));
+        wait(1);
+        assert!(idle.due_at(Instant::now()));
+
+        // Activity pushes it back, a keep-alive as much as a post.
+        idle.feed(Instant::now());
+        assert_eq!(idle.deadline(), start + Duration::from_ticks(100));
+        wait(49);
+        assert!(!idle.due_at(Instant::now()));
+        wait(1);
+        assert!(idle.due_at(Instant::now()));
+    }
+}
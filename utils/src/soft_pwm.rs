@@ -0,0 +1,104 @@
+//!
+//! Software PWM on a plain GPIO output.
+//!
+//! For a buzzer on a pin without a timer channel: [`tone`] toggles the
+//! pin from an async ticker, so other tasks run between the edges.
+//!
+//! The period is rounded to whole `embassy-time` ticks (32.768 kHz),
+//! so higher notes are slightly detuned; the duration is kept by
+//! counting edges rather than measuring time.
+//!
+
+use crate::prelude::hal::gpio::Output;
+use crate::prelude::time::{Duration, Ticker, Timer};
+
+///
+/// Number of pin toggles for a `hz` square wave lasting `ms`.
+///
+/// Each period takes two toggles; a partial period is dropped.
+///
+pub const fn toggles(hz: u32, ms: u32) -> u32 {
+    (hz as u64 * ms as u64 / 1000 * 2) as u32
+}
+
+///
+/// # Play a Tone
+///
+/// Drive `pin` with a 50% square wave of `hz` for `ms`, then leave
+/// it low. A frequency of 0 is a rest: the pin stays low for `ms`.
+///
+/// # Example
+/// ```
+/// let mut pin = Output::new(p.PB7, Level::Low, Speed::Low);
+/// soft_pwm::tone(&mut pin, 880, 200).await;
+/// ```
+///
+pub async fn tone(pin: &mut Output<'_>, hz: u32, ms: u32) {
+    pin.set_low();
+
+    square_wave(hz, ms, || pin.toggle()).await;
+    pin.set_low();
+}
+
+/// Call `toggle` on every edge of a `hz` square wave lasting `ms`.
+async fn square_wave(hz: u32, ms: u32, mut toggle: impl FnMut()) {
+    if hz == 0 {
+        return Timer::after_millis(ms as u64).await;
+    }
+
+    let mut ticker = Ticker::every(Duration::from_hz(2 * hz as u64));
+    for _ in 0..toggles(hz, ms) {
+        ticker.next().await;
+        toggle();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_time::block_on;
+    use crate::prelude::time::Instant;
+
+    /// Edge times of `square_wave`, in ticks since the start
+    fn edges(hz: u32, ms: u32) -> (Vec<u64>, u64) {
+        let start = Instant::now();
+        let mut edges = Vec::new();
+        block_on(square_wave(hz, ms, || {
+            edges.push(start.elapsed().as_ticks())
+        }));
+        (edges, start.elapsed().as_ticks())
+    }
+
+    #[test]
+    fn test_toggles() {
+        // 1 kHz for 100 ms is 100 periods; 523 Hz (C5) for 100 ms rounds down to 52.
+        assert_eq!(toggles(1000, 100), 200);
+        assert_eq!(toggles(523, 100), 104);
+        assert_eq!(toggles(0, 100), 0);
+        assert_eq!(toggles(1000, 0), 0);
+        assert_eq!(toggles(100_000, 60_000), 12_000_000);
+    }
+
+    #[test]
+    fn test_square_wave() {
+        let half = Duration::from_hz(2 * 1000).as_ticks();
+        let (edges, total) = edges(1000, 100);
+
+        // One toggle per half period, and none after the last one.
+        assert_eq!(edges.len() as u32, toggles(1000, 100));
+        assert!(
+            edges
+                .iter()
+                .enumerate()
+                .all(|(i, &t)| t == (i as u64 + 1) * half)
+        );
+        assert_eq!(total, edges.len() as u64 * half);
+    }
+
+    #[test]
+    fn test_square_wave_rest() {
+        let (edges, total) = edges(0, 100);
+        assert!(edges.is_empty());
+        assert_eq!(total, Duration::from_millis(100).as_ticks());
+    }
+}